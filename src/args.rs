@@ -1,16 +1,67 @@
-use clap::{Parser, Subcommand};
-use std::io::Result;
+use chrono::{DateTime, Local};
+use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
+use clap_complete::Shell;
+use objc2_foundation::MainThreadMarker;
+use std::collections::HashSet;
+use std::io::{Error, Result};
+use std::path::PathBuf;
 
-use crate::launchd::{Service, ID};
+use crate::calendar::{self, EventCollection, MonthSummary};
+use crate::clock::FrozenClock;
+use crate::config::Config;
+use crate::ffi::event_kit;
+use crate::keychain;
+use crate::launchd::Service;
+use crate::menu::{self, NextTemplate};
+use crate::state::StateDocument;
 
 #[derive(Parser)]
 #[command(name = "eventually")]
 #[command(about = "macOS menu bar calendar app", long_about = None)]
 pub struct Cli {
+    /// Run as a named instance with its own config and launchd label
+    #[arg(long, global = true, value_parser = parse_instance)]
+    pub instance: Option<String>,
+
+    /// Show the app as of this local time, e.g. "2025-03-07 08:55" (for debugging)
+    #[arg(long, global = true, value_parser = calendar::parse_local_time)]
+    pub at: Option<DateTime<Local>>,
+
+    /// Use the events in this TOML file instead of the calendar's (for debugging)
+    #[arg(long, global = true)]
+    pub fixture: Option<PathBuf>,
+
+    /// How many days, starting today, to list, instead of the config's `[menu] days`
+    #[arg(long, global = true)]
+    pub days: Option<u32>,
+
     #[command(subcommand)]
     pub command: Option<Command>,
 }
 
+fn parse_instance(value: &str) -> std::result::Result<String, String> {
+    if is_valid_name(value) {
+        Ok(value.to_string())
+    } else {
+        Err("instance names may only contain letters, digits, '-' and '_'".to_string())
+    }
+}
+
+fn parse_integration(value: &str) -> std::result::Result<String, String> {
+    if is_valid_name(value) {
+        Ok(value.to_ascii_lowercase())
+    } else {
+        Err("integration names may only contain letters, digits, '-' and '_'".to_string())
+    }
+}
+
+fn is_valid_name(value: &str) -> bool {
+    !value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
 #[derive(Subcommand)]
 pub enum Command {
     /// Manage launchd service
@@ -18,12 +69,63 @@ pub enum Command {
         #[command(subcommand)]
         action: ServiceAction,
     },
+    /// Print meeting statistics for the current month
+    Summary {
+        /// Summarize the current calendar month
+        #[arg(long, required = true)]
+        month: bool,
+        /// Print JSON instead of text
+        #[arg(long)]
+        json: bool,
+    },
+    /// Manage integration credentials stored in the keychain
+    Auth {
+        #[command(subcommand)]
+        action: AuthAction,
+    },
+    /// Export or import the config and local notes as one JSON document
+    State {
+        #[command(subcommand)]
+        action: StateAction,
+    },
+    /// Print the status title and menu rows as text, now or at `--at`
+    List {
+        /// "raycast" or "alfred" print upcoming events as JSON for launchers
+        #[arg(long, value_enum, default_value_t = ListFormat::Text)]
+        format: ListFormat,
+    },
+    /// Print the current or next event on one line, for prompts and dashboards
+    Next {
+        /// Template with {title}, {countdown}, {minutes}, {start}, {end},
+        /// {service}, {url}, {calendar}, and {urgency} ("now", "soon", "later")
+        #[arg(long, value_parser = NextTemplate::parse, default_value = menu::DEFAULT_NEXT_FORMAT)]
+        format: NextTemplate,
+        /// Color the line red during the event and yellow just before it
+        #[arg(long)]
+        color: bool,
+    },
+    /// Print shell completions to stdout
+    Completions {
+        /// Shell to generate completions for
+        shell: Shell,
+    },
+}
+
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+pub enum ListFormat {
+    Text,
+    Raycast,
+    Alfred,
 }
 
 #[derive(Subcommand)]
 pub enum ServiceAction {
     /// Install the launchd service
-    Install,
+    Install {
+        /// Print the plist and target path without writing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
     /// Uninstall the launchd service
     Uninstall,
     /// Start the service
@@ -32,29 +134,280 @@ pub enum ServiceAction {
     Stop,
     /// Restart the service
     Restart,
+    /// Print the generated launchd plist
+    Plist,
+}
+
+#[derive(Subcommand)]
+pub enum AuthAction {
+    /// Store a token for an integration, read from stdin
+    Set {
+        /// Integration the token is for, e.g. "slack"
+        #[arg(value_parser = parse_integration)]
+        integration: String,
+    },
+    /// Delete an integration's token
+    Remove {
+        #[arg(value_parser = parse_integration)]
+        integration: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum StateAction {
+    /// Print the state as JSON, or write it to a file
+    Export {
+        /// File to write instead of stdout
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+    /// Replace the state with an exported document, keeping `.bak` copies
+    Import {
+        /// Exported document, or "-" for stdin
+        file: PathBuf,
+    },
+}
+
+impl StateAction {
+    pub fn execute(&self, instance: Option<&str>) -> Result<()> {
+        match self {
+            Self::Export { output } => {
+                let json = StateDocument::collect(instance)?.to_json();
+                match output {
+                    Some(path) => std::fs::write(path, json),
+                    None => {
+                        print!("{}", json);
+                        Ok(())
+                    }
+                }
+            }
+            Self::Import { file } => {
+                let contents = if file.as_os_str() == "-" {
+                    std::io::read_to_string(std::io::stdin())?
+                } else {
+                    std::fs::read_to_string(file)?
+                };
+                let document = StateDocument::from_json(&contents)
+                    .map_err(|e| Error::other(format!("{}: {}", file.display(), e)))?;
+                let changed = document.apply(instance)?;
+                if changed.is_empty() {
+                    println!("Already up to date");
+                } else {
+                    for path in changed {
+                        println!("Replaced `{}`", path.display());
+                    }
+                    println!("Restart eventually to use the imported state");
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl AuthAction {
+    pub fn execute(&self) -> Result<()> {
+        match self {
+            Self::Set { integration } => {
+                let token = read_secret(&format!("Token for {}: ", integration))?;
+                if token.is_empty() {
+                    return Err(Error::other("No token given"));
+                }
+                let replaced = keychain::get_secret(integration)?.is_some();
+                keychain::set_secret(integration, &token)?;
+                if replaced {
+                    println!("Replaced the token for {}", integration);
+                } else {
+                    println!("Saved the token for {}", integration);
+                }
+                Ok(())
+            }
+            Self::Remove { integration } => {
+                if keychain::remove_secret(integration)? {
+                    println!("Removed the token for {}", integration);
+                } else {
+                    println!("No token stored for {}", integration);
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Reads one line from stdin, prompting without echo when it's a terminal so
+/// the secret stays off screen and out of shell history.
+fn read_secret(prompt: &str) -> Result<String> {
+    use std::io::{BufRead, Write};
+
+    let fd = libc::STDIN_FILENO;
+    let mut original = std::mem::MaybeUninit::<libc::termios>::uninit();
+    let is_tty =
+        unsafe { libc::isatty(fd) == 1 && libc::tcgetattr(fd, original.as_mut_ptr()) == 0 };
+    if is_tty {
+        eprint!("{}", prompt);
+        std::io::stderr().flush()?;
+        unsafe {
+            let mut silent = original.assume_init();
+            silent.c_lflag &= !libc::ECHO;
+            libc::tcsetattr(fd, libc::TCSANOW, &silent);
+        }
+    }
+
+    let mut line = String::new();
+    let read = std::io::stdin().lock().read_line(&mut line);
+    if is_tty {
+        unsafe { libc::tcsetattr(fd, libc::TCSANOW, original.as_ptr()) };
+        eprintln!();
+    }
+    read?;
+    Ok(line.trim_end_matches(['\r', '\n']).to_string())
 }
 
 impl ServiceAction {
-    pub fn execute(self) -> Result<()> {
-        let service = Service::try_new(ID)?;
-        
+    pub fn execute(&self, instance: Option<&str>) -> Result<()> {
+        let service = Service::for_instance(instance)?;
+
         match self {
-            Self::Install => service.install(),
+            Self::Install { dry_run: true } => service.install_dry_run(),
+            Self::Install { dry_run: false } => service.install(),
             Self::Uninstall => service.uninstall(),
             Self::Start => service.start(),
             Self::Stop => service.stop(),
             Self::Restart => service.restart(),
+            Self::Plist => {
+                print!("{}", service.launchd_plist()?);
+                Ok(())
+            }
         }
     }
 }
 
 impl Cli {
-    pub fn parse_and_execute() -> Option<Result<()>> {
-        let cli = Self::parse();
-        
-        match cli.command {
-            Some(Command::Service { action }) => Some(action.execute()),
+    /// Runs the requested subcommand, or returns `None` to launch the menu bar app.
+    pub fn execute(&self) -> Option<Result<()>> {
+        match &self.command {
+            Some(Command::Service { action }) => Some(action.execute(self.instance.as_deref())),
+            Some(Command::Summary { month: _, json }) => {
+                Some(print_month_summary(*json, &self.load_config()))
+            }
+            Some(Command::Auth { action }) => Some(action.execute()),
+            Some(Command::State { action }) => Some(action.execute(self.instance.as_deref())),
+            Some(Command::List { format }) => Some(self.print_preview(*format)),
+            Some(Command::Next { format, color }) => Some(self.print_next(format, *color)),
+            Some(Command::Completions { shell }) => {
+                clap_complete::generate(
+                    *shell,
+                    &mut Self::command(),
+                    "eventually",
+                    &mut std::io::stdout(),
+                );
+                Some(Ok(()))
+            }
             None => None,
         }
     }
+
+    /// The instance's config, with `--days` applied.
+    pub fn load_config(&self) -> Config {
+        let mut config = Config::load(self.instance.as_deref());
+        if let Some(days) = self.days {
+            config.menu.days = days;
+        }
+        config
+    }
+
+    /// Events from `--fixture`, if given.
+    pub fn load_fixture(&self) -> Result<Option<EventCollection>> {
+        let Some(path) = &self.fixture else {
+            return Ok(None);
+        };
+        let contents = std::fs::read_to_string(path)?;
+        EventCollection::from_fixture(&contents)
+            .map(Some)
+            .map_err(|e| Error::other(format!("{}: {}", path.display(), e)))
+    }
+
+    /// `--fixture`'s events, or else the calendar's as the menu would fetch them.
+    fn load_events(&self, clock: &FrozenClock, config: &Config) -> Result<EventCollection> {
+        if let Some(events) = self.load_fixture()? {
+            return Ok(events);
+        }
+        let mtm = MainThreadMarker::new()
+            .ok_or_else(|| Error::other("events must be fetched on the main thread"))?;
+        let store = event_kit::init_event_store(mtm);
+        calendar::request_access(&store).map_err(Error::other)?;
+        let days = config.menu.horizon();
+        EventCollection::fetch(&store, clock, days, config).map_err(Error::other)
+    }
+
+    /// Prints nothing when there's no event to show, which prompts can test for.
+    fn print_next(&self, format: &NextTemplate, color: bool) -> Result<()> {
+        let clock = FrozenClock(self.at.unwrap_or_else(Local::now));
+        let config = self.load_config();
+        let events = self.load_events(&clock, &config)?;
+        if let Some(status) = events.find_cur_or_next(&clock, &HashSet::new(), &config) {
+            let line = menu::render_next(&status, clock.0, format, &config.join.prefer, color);
+            println!("{}", line);
+        }
+        Ok(())
+    }
+
+    fn print_preview(&self, format: ListFormat) -> Result<()> {
+        let clock = FrozenClock(self.at.unwrap_or_else(Local::now));
+        let config = self.load_config();
+        let events = self.load_events(&clock, &config)?;
+        let output = match format {
+            ListFormat::Text => menu::render_preview(&events, &clock, &HashSet::new(), &config),
+            ListFormat::Raycast => menu::render_raycast(&events, clock.0, &config),
+            ListFormat::Alfred => menu::render_alfred(&events, clock.0, &config),
+        };
+        println!("{}", output);
+        Ok(())
+    }
+}
+
+fn print_month_summary(json: bool, config: &Config) -> Result<()> {
+    let mtm = MainThreadMarker::new()
+        .ok_or_else(|| Error::other("summary must run on the main thread"))?;
+    let store = event_kit::init_event_store(mtm);
+    calendar::request_access(&store).map_err(Error::other)?;
+
+    let (month, events) =
+        calendar::fetch_month(&store, Local::now().date_naive(), &config.calendars)
+            .map_err(Error::other)?;
+    let summary = MonthSummary::from_events(month, &events);
+    if json {
+        println!("{}", summary.to_json());
+    } else {
+        println!("{}", summary.to_text());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_valid_name() {
+        assert!(is_valid_name("work"));
+        assert!(is_valid_name("side-project_2"));
+        assert!(!is_valid_name(""));
+        assert!(!is_valid_name("work/home"));
+        assert!(!is_valid_name("../config"));
+        assert!(!is_valid_name("café"));
+    }
+
+    #[test]
+    fn test_parse_integration() {
+        assert_eq!(parse_integration("Slack"), Ok("slack".to_string()));
+        assert_eq!(parse_integration("zoom_2"), Ok("zoom_2".to_string()));
+        assert!(parse_integration("").is_err());
+        assert!(parse_integration("slack token").is_err());
+    }
+
+    #[test]
+    fn test_parse_instance_keeps_case() {
+        assert_eq!(parse_instance("Work"), Ok("Work".to_string()));
+        assert!(parse_instance("work.toml").is_err());
+    }
 }
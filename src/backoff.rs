@@ -0,0 +1,55 @@
+use std::time::Duration;
+
+const INITIAL_DELAY: Duration = Duration::from_secs(2);
+const MAX_DELAY: Duration = Duration::from_secs(300);
+
+/// Exponential backoff for retrying a failed operation: 2s, 4s, 8s, … capped at 5 minutes.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Backoff {
+    attempt: u32,
+}
+
+impl Backoff {
+    pub fn next_delay(&mut self) -> Duration {
+        let delay = INITIAL_DELAY
+            .checked_mul(2u32.saturating_pow(self.attempt))
+            .map_or(MAX_DELAY, |delay| delay.min(MAX_DELAY));
+        self.attempt = self.attempt.saturating_add(1);
+        delay
+    }
+
+    pub fn reset(&mut self) {
+        self.attempt = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_doubles() {
+        let mut backoff = Backoff::default();
+        assert_eq!(backoff.next_delay(), Duration::from_secs(2));
+        assert_eq!(backoff.next_delay(), Duration::from_secs(4));
+        assert_eq!(backoff.next_delay(), Duration::from_secs(8));
+    }
+
+    #[test]
+    fn test_backoff_caps() {
+        let mut backoff = Backoff::default();
+        for _ in 0..40 {
+            assert!(backoff.next_delay() <= MAX_DELAY);
+        }
+        assert_eq!(backoff.next_delay(), MAX_DELAY);
+    }
+
+    #[test]
+    fn test_backoff_reset() {
+        let mut backoff = Backoff::default();
+        backoff.next_delay();
+        backoff.next_delay();
+        backoff.reset();
+        assert_eq!(backoff.next_delay(), INITIAL_DELAY);
+    }
+}
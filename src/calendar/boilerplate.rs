@@ -0,0 +1,125 @@
+use super::links::find_urls;
+
+/// Google Meet fences its section with a line like "-::~:~::~:~:~:~::-".
+const MEET_FENCE: &str = "-::~:~::~";
+/// Teams fences its section with a long line of underscores.
+const TEAMS_FENCE_MIN_LENGTH: usize = 20;
+/// Zoom has no closing line; its section runs to the end of the notes.
+const ZOOM_MARKERS: [&str; 2] = [
+    "is inviting you to a scheduled zoom meeting",
+    "join zoom meeting",
+];
+
+/// The lines of invite notes without the join instructions Zoom, Teams, and
+/// Meet add to them, leaving what people wrote. Each section is replaced by its
+/// first link, the one to join with, while the dial-in lists, help links, and
+/// "Meeting options" links around it go. Everything returned borrows from
+/// `notes`.
+pub fn strip_invite_boilerplate(notes: &str) -> Vec<&str> {
+    let lines: Vec<&str> = notes.lines().collect();
+    let mut kept: Vec<&str> = Vec::with_capacity(lines.len());
+
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i];
+        let section_end = if is_fence(line) {
+            // Up to and including the closing fence, if there is one
+            lines[i + 1..]
+                .iter()
+                .position(|l| is_fence(l))
+                .map(|offset| i + 1 + offset + 1)
+        } else if is_zoom_start(line) {
+            Some(lines.len())
+        } else {
+            None
+        };
+
+        match section_end {
+            Some(end) => {
+                if let Some(url) = lines[i..end].iter().find_map(|l| find_urls(l).next()) {
+                    kept.push(url);
+                }
+                i = end;
+            }
+            None => {
+                kept.push(line);
+                i += 1;
+            }
+        }
+    }
+    kept
+}
+
+fn is_fence(line: &str) -> bool {
+    let line = line.trim();
+    line.starts_with(MEET_FENCE)
+        || line.len() >= TEAMS_FENCE_MIN_LENGTH && line.chars().all(|c| c == '_')
+}
+
+fn is_zoom_start(line: &str) -> bool {
+    let line = line.to_lowercase();
+    ZOOM_MARKERS.iter().any(|marker| line.contains(marker))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_teams_section() {
+        let notes = "Agenda: https://docs.example.com/q2\n\
+            \n\
+            ________________________________________________________________________________\n\
+            Microsoft Teams meeting\n\
+            Join on your computer, mobile app or room device\n\
+            Click here to join the meeting<https://teams.microsoft.com/l/meetup-join/19%3ameeting_abc>\n\
+            Meeting ID: 123 456 789\n\
+            Learn More<https://aka.ms/JoinTeamsMeeting> | Meeting options<https://teams.microsoft.com/meetingOptions/?id=1>\n\
+            ________________________________________________________________________________";
+
+        assert_eq!(
+            strip_invite_boilerplate(notes).join("\n"),
+            "Agenda: https://docs.example.com/q2\n\
+             \n\
+             https://teams.microsoft.com/l/meetup-join/19%3ameeting_abc"
+        );
+    }
+
+    #[test]
+    fn test_strip_meet_section() {
+        let notes = "Bring the numbers.\n\
+            -::~:~::~:~:~:~:~:~:~:~:~:~:~:~:~:~:~:~:~:~:~:~:~:~:~:~:~:~::~:~::-\n\
+            Join with Google Meet: https://meet.google.com/abc-defg-hij\n\
+            Learn more about Meet at: https://support.google.com/a/users/answer/9282720\n\
+            Please do not edit this section.\n\
+            -::~:~::~:~:~:~:~:~:~:~:~:~:~:~:~:~:~:~:~:~:~:~:~:~:~:~:~:~::~:~::-";
+
+        assert_eq!(
+            strip_invite_boilerplate(notes).join("\n"),
+            "Bring the numbers.\nhttps://meet.google.com/abc-defg-hij"
+        );
+    }
+
+    #[test]
+    fn test_strip_zoom_section() {
+        let notes = "Quarterly review\n\
+            \n\
+            Jane is inviting you to a scheduled Zoom meeting.\n\
+            Join Zoom Meeting\n\
+            https://zoom.us/j/123456789?pwd=abc\n\
+            \n\
+            One tap mobile\n\
+            Find your local number: https://zoom.us/u/abcdef";
+
+        assert_eq!(
+            strip_invite_boilerplate(notes).join("\n"),
+            "Quarterly review\n\nhttps://zoom.us/j/123456789?pwd=abc"
+        );
+    }
+
+    #[test]
+    fn test_plain_notes_untouched() {
+        let notes = "Bring charger\n___\nhttps://example.com";
+        assert_eq!(strip_invite_boilerplate(notes).join("\n"), notes);
+    }
+}
@@ -0,0 +1,171 @@
+/// What's needed to join a video call from a phone: the meeting ID and
+/// passcode to type in, and any dial-in numbers listed in the invite.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct PhoneJoin {
+    pub meeting_id: Option<String>,
+    pub passcode: Option<String>,
+    pub dial_in_numbers: Vec<String>,
+}
+
+impl PhoneJoin {
+    /// Looks for a Zoom link in the location and notes, Webex style "Meeting
+    /// number:" / "Password:" lines, and dial-in numbers in the notes. Returns `None`
+    /// when nothing useful was found.
+    pub fn from_event(location: Option<&str>, notes: Option<&str>) -> Option<Self> {
+        let zoom_url = [location, notes]
+            .into_iter()
+            .flatten()
+            .flat_map(str::split_whitespace)
+            .find(|word| word.contains("zoom.us/j/"));
+
+        let mut join = zoom_url.map(parse_zoom_url).unwrap_or_default();
+        if let Some(notes) = notes {
+            join.meeting_id = join
+                .meeting_id
+                .or_else(|| find_labeled(notes, &["meeting number", "meeting id"]));
+            // Newer Zoom links carry an encoded `pwd=`, so the typed passcode
+            // from the invite text wins when there is one
+            join.passcode = find_labeled(notes, &["passcode", "password"]).or(join.passcode.take());
+            join.dial_in_numbers = find_dial_in_numbers(notes);
+        }
+
+        (join != Self::default()).then_some(join)
+    }
+}
+
+fn parse_zoom_url(url: &str) -> PhoneJoin {
+    let meeting_id = url.split_once("zoom.us/j/").map(|(_, rest)| {
+        rest.chars()
+            .take_while(char::is_ascii_digit)
+            .collect::<String>()
+    });
+    let passcode = url.split_once("pwd=").map(|(_, rest)| {
+        rest.chars()
+            .take_while(|c| c.is_ascii_alphanumeric() || *c == '.')
+            .collect::<String>()
+    });
+
+    PhoneJoin {
+        meeting_id: meeting_id.filter(|id| !id.is_empty()),
+        passcode: passcode.filter(|pwd| !pwd.is_empty()),
+        dial_in_numbers: Vec::new(),
+    }
+}
+
+/// Value after the first "<label>: " line, e.g. "Meeting number: 2553 123 4567".
+fn find_labeled(text: &str, labels: &[&str]) -> Option<String> {
+    text.lines().find_map(|line| {
+        let (label, value) = line.split_once(':')?;
+        let label = label.trim().to_lowercase();
+        let value = value.trim();
+        (labels.iter().any(|l| label.contains(l)) && !value.is_empty() && !value.contains("//"))
+            .then(|| value.to_string())
+    })
+}
+
+/// Dial-in numbers as `tel:` URIs, in the order they appear and without
+/// duplicates. Besides explicit `tel:` links this picks up the bare "One tap
+/// mobile" style numbers, e.g. "+16465588656,,84512345678#".
+pub fn find_dial_in_numbers(text: &str) -> Vec<String> {
+    let mut numbers: Vec<String> = Vec::new();
+    for word in text.split_whitespace() {
+        let number = match word.find("tel:") {
+            Some(start) => &word[start + "tel:".len()..],
+            None if word.starts_with('+') => word,
+            None => continue,
+        };
+        let number = number.trim_end_matches(['>', ')', '"', '\'', ';']);
+        if !is_phone_number(number) {
+            continue;
+        }
+
+        let uri = format!("tel:{}", number);
+        if !numbers.contains(&uri) {
+            numbers.push(uri);
+        }
+    }
+    numbers
+}
+
+fn is_phone_number(number: &str) -> bool {
+    let number = number.strip_prefix('+').unwrap_or(number);
+    number.chars().filter(char::is_ascii_digit).count() >= 7
+        && number
+            .chars()
+            .all(|c| c.is_ascii_digit() || matches!(c, ',' | '#' | '*' | '-'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zoom_url_in_location() {
+        let join = PhoneJoin::from_event(
+            Some("https://acme.zoom.us/j/84512345678?pwd=aBc123.1"),
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(join.meeting_id.as_deref(), Some("84512345678"));
+        assert_eq!(join.passcode.as_deref(), Some("aBc123.1"));
+    }
+
+    #[test]
+    fn test_zoom_url_and_dial_in_in_notes() {
+        let notes = "Join Zoom Meeting\nhttps://zoom.us/j/98765432100\n\n\
+                     Meeting ID: 987 6543 2100\nPasscode: 424242\n\
+                     One tap mobile\n<tel:+16465588656,,98765432100#>\n\
+                     <tel:+16465588656,,98765432100#>";
+        let join = PhoneJoin::from_event(Some("Room 4"), Some(notes)).unwrap();
+
+        assert_eq!(join.meeting_id.as_deref(), Some("98765432100"));
+        assert_eq!(join.passcode.as_deref(), Some("424242"));
+        assert_eq!(join.dial_in_numbers, vec!["tel:+16465588656,,98765432100#"]);
+    }
+
+    #[test]
+    fn test_one_tap_mobile_numbers() {
+        let notes = "One tap mobile\n\
+                     +16465588656,,84512345678#,,,,*424242# US (New York)\n\
+                     +13017158592,,84512345678# US (Washington DC)\n\
+                     Budget: +5% vs last year";
+
+        assert_eq!(
+            find_dial_in_numbers(notes),
+            vec![
+                "tel:+16465588656,,84512345678#,,,,*424242#",
+                "tel:+13017158592,,84512345678#",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_passcode_from_notes_wins_over_url() {
+        let join = PhoneJoin::from_event(
+            Some("https://zoom.us/j/84512345678?pwd=QmFzZTY0ZW5jb2RlZA.1"),
+            Some("Passcode: 424242"),
+        )
+        .unwrap();
+
+        assert_eq!(join.passcode.as_deref(), Some("424242"));
+    }
+
+    #[test]
+    fn test_webex_notes() {
+        let notes = "Meeting number (access code): 2553 123 4567\nMeeting password: xYz9";
+        let join = PhoneJoin::from_event(None, Some(notes)).unwrap();
+
+        assert_eq!(join.meeting_id.as_deref(), Some("2553 123 4567"));
+        assert_eq!(join.passcode.as_deref(), Some("xYz9"));
+    }
+
+    #[test]
+    fn test_nothing_to_show() {
+        assert_eq!(
+            PhoneJoin::from_event(Some("Room 4"), Some("Agenda: TBD")),
+            None
+        );
+        assert_eq!(PhoneJoin::from_event(None, None), None);
+    }
+}
@@ -1,10 +1,21 @@
-use chrono::{DateTime, Duration, Local, Timelike};
+use chrono::{DateTime, Duration, Local, NaiveDate, NaiveTime, Timelike};
 use objc2::rc::Retained;
 use objc2_event_kit::{EKCalendar, EKEvent, EKEventStore};
 use objc2_foundation::NSDate;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
+use super::boilerplate;
+use super::error::CalendarError;
 use super::formatting;
+use super::google;
+use super::links;
+use super::occurrence::OccurrenceRef;
+use super::service::{extract_url, MeetingMedium, ServiceInfo};
+use crate::clock::Clock;
+use crate::config::{
+    is_absence_title, BannerConfig, CalendarsConfig, Config, CurrentCountdown, TitleConfig,
+    UpcomingCountdown,
+};
 
 impl From<Vec<EventInfo>> for EventCollection {
     fn from(events: Vec<EventInfo>) -> Self {
@@ -12,19 +23,241 @@ impl From<Vec<EventInfo>> for EventCollection {
     }
 }
 
-const DAYS_TO_FETCH: u8 = 4;
+impl<'a> IntoIterator for &'a EventCollection {
+    type Item = &'a EventInfo;
+    type IntoIter = std::slice::Iter<'a, EventInfo>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Caps on how many events get listed in the menu, so that subscribed calendars
+/// (public holidays, team PTO feeds) can't balloon it to hundreds of rows.
+const MAX_EVENTS_PER_DAY: usize = 20;
+const MAX_EVENTS: usize = 60;
+
+/// How many of a day's events get a menu row, given how many earlier days listed.
+pub fn listed_in_day(day_len: usize, listed_so_far: usize) -> usize {
+    day_len
+        .min(MAX_EVENTS_PER_DAY)
+        .min(MAX_EVENTS.saturating_sub(listed_so_far))
+}
+
+/// Whether each of `events`, in start order, can get a menu row. Muted events
+/// and out-of-office banners are listed in their own collapsed submenus, which
+/// aren't capped; the rest count toward their day's cap. The menu caps by day
+/// group rather than day, which never lists an event this leaves out.
+fn listed_events(events: &[EventInfo], config: &Config) -> Vec<bool> {
+    let mut per_day: HashMap<NaiveDate, usize> = HashMap::new();
+    events
+        .iter()
+        .map(|e| {
+            if e.is_muted(&config.calendars) || e.is_ooo_banner(&config.banners) {
+                return true;
+            }
+            let day_count = per_day.entry(e.start.date_naive()).or_default();
+            *day_count += 1;
+            *day_count <= MAX_EVENTS_PER_DAY
+        })
+        .collect()
+}
+
 const DEFAULT_CALENDAR_COLOR: (f64, f64, f64) = (0.5, 0.5, 0.5);
 
+/// The start of `day`, or `None` where a DST shift skips midnight.
+fn local_midnight(day: NaiveDate) -> Option<DateTime<Local>> {
+    day.and_time(NaiveTime::MIN)
+        .and_local_timezone(Local)
+        .earliest()
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct EventInfo {
     pub title: String,
     pub start: DateTime<Local>,
     pub end: DateTime<Local>,
     pub event_id: String,
-    pub occurrence_key: String,
+    pub occurrence: OccurrenceRef,
     pub has_recurrence: bool,
     pub location: Option<String>,
+    /// Display P3 components, matching how Calendar.app renders them
     pub calendar_color: (f64, f64, f64),
+    /// Availability is set to "Free", e.g. focus blocks or synced reminders
+    pub is_free: bool,
+    /// Accepted by me, or an event without invitees
+    pub is_accepted: bool,
+    /// Attendees besides me; zero for personal blocks
+    pub other_attendees: usize,
+    pub notes: Option<String>,
+    /// The event's URL field
+    pub url: Option<String>,
+    /// All-day event on my default calendar rather than a shared one
+    pub is_own: bool,
+    pub calendar_name: String,
+    /// EventKit's identifier for the event's calendar
+    pub calendar_id: String,
+    /// From a subscribed calendar, such as public holidays or birthdays
+    pub is_subscribed: bool,
+    /// Booked conference rooms, often missing from a location holding the call link
+    pub rooms: Vec<String>,
+    /// The event in Google Calendar on the web, for events synced from Google
+    pub google_url: Option<String>,
+}
+
+/// Attendee count (including me) from which a meeting counts as large.
+const LARGE_MEETING_ATTENDEES: usize = 10;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MeetingSize {
+    OneOnOne,
+    SmallGroup,
+    Large,
+}
+
+impl MeetingSize {
+    /// `None` for events without other attendees.
+    pub fn from_other_attendees(other_attendees: usize) -> Option<Self> {
+        match other_attendees + 1 {
+            1 => None,
+            2 => Some(Self::OneOnOne),
+            n if n < LARGE_MEETING_ATTENDEES => Some(Self::SmallGroup),
+            _ => Some(Self::Large),
+        }
+    }
+
+    pub fn label(&self, other_attendees: usize) -> String {
+        match self {
+            Self::OneOnOne => "1:1".to_string(),
+            Self::SmallGroup => format!("Group · {} people", other_attendees + 1),
+            Self::Large => format!("Large meeting · {} people", other_attendees + 1),
+        }
+    }
+}
+
+impl EventInfo {
+    /// The link for the Join action. Among known conferencing links the first
+    /// service in `prefer` wins, then whichever appears first; a plain URL in
+    /// the location is the last resort.
+    pub fn join_url(&self, prefer: &[ServiceInfo]) -> Option<&str> {
+        let calls: Vec<(ServiceInfo, &str)> = self
+            .links()
+            .into_iter()
+            .map(|link| (ServiceInfo::from_url(link), link))
+            .filter(|(service, _)| *service != ServiceInfo::Generic)
+            .collect();
+
+        prefer
+            .iter()
+            .find_map(|preferred| calls.iter().find(|(service, _)| service == preferred))
+            .or_else(|| calls.first())
+            .map(|(_, link)| *link)
+            .or_else(|| extract_url(self.location.as_deref()))
+    }
+
+    /// By video when there's a link to join, wherever it is, and otherwise as
+    /// the location says.
+    pub fn medium(&self, prefer: &[ServiceInfo]) -> MeetingMedium {
+        match self.join_url(prefer) {
+            Some(url) => MeetingMedium::Video(ServiceInfo::from_url(url)),
+            None => MeetingMedium::from_location(self.location.as_deref()),
+        }
+    }
+
+    /// Every URL in the location, URL field, and notes, without duplicates.
+    /// Conferencing boilerplate in the notes only contributes its join link.
+    pub fn links(&self) -> Vec<&str> {
+        let mut links: Vec<&str> = Vec::new();
+        let notes = self
+            .notes
+            .as_deref()
+            .map(boilerplate::strip_invite_boilerplate)
+            .unwrap_or_default();
+        for text in [self.location.as_deref(), self.url.as_deref()]
+            .into_iter()
+            .flatten()
+            .chain(notes)
+        {
+            for url in links::find_urls(text) {
+                if !links.contains(&url) {
+                    links.push(url);
+                }
+            }
+        }
+        links
+    }
+
+    pub fn meeting_size(&self) -> Option<MeetingSize> {
+        MeetingSize::from_other_attendees(self.other_attendees)
+    }
+
+    /// A colleague's all-day absence on a shared calendar.
+    pub fn is_ooo_banner(&self, config: &BannerConfig) -> bool {
+        formatting::is_all_day(&self.start, &self.end) && config.matches(&self.title)
+    }
+
+    /// My own day off, such as an "OOO" or "Holiday" all-day event.
+    pub fn is_own_absence(&self) -> bool {
+        self.is_own
+            && formatting::is_all_day(&self.start, &self.end)
+            && (is_absence_title(&self.title) || self.title.to_lowercase().contains("holiday"))
+    }
+
+    /// Collapsed under its calendar's name and never shown in the title.
+    pub fn is_muted(&self, config: &CalendarsConfig) -> bool {
+        config.is_muted(&self.calendar_name, self.is_subscribed)
+    }
+
+    /// Booked rooms the location doesn't already name, e.g. "Room 4A".
+    pub fn room_label(&self) -> Option<String> {
+        let location = self.location.as_deref().unwrap_or_default();
+        let rooms: Vec<&str> = self
+            .rooms
+            .iter()
+            .map(String::as_str)
+            .filter(|room| !location.contains(room))
+            .collect();
+        (!rooms.is_empty()).then(|| rooms.join(", "))
+    }
+
+    /// Counted toward meeting totals: neither all-day nor marked "Free".
+    pub fn is_meeting(&self) -> bool {
+        !self.is_free && !formatting::is_all_day(&self.start, &self.end)
+    }
+
+    /// What identifies the event and feeds link detection, for pasting into
+    /// bug reports.
+    pub fn debug_info(&self) -> String {
+        format!(
+            "title: {}\n\
+             event_id: {}\n\
+             occurrence: {}\n\
+             calendar: {} ({})\n\
+             start: {}\n\
+             end: {}\n\
+             recurring: {}\n\
+             location: {:?}\n\
+             url: {:?}\n\
+             notes: {:?}",
+            self.title,
+            self.event_id,
+            self.occurrence,
+            self.calendar_name,
+            self.calendar_id,
+            self.start.to_rfc3339(),
+            self.end.to_rfc3339(),
+            self.has_recurrence,
+            self.location,
+            self.url,
+            self.notes,
+        )
+    }
+
+    /// Whether the event warrants "join now" emphasis. Solo events only do when
+    /// they have a link to join, wherever `join_url` finds it.
+    pub fn needs_join_urgency(&self) -> bool {
+        self.other_attendees > 0 || self.join_url(&[]).is_some()
+    }
 }
 
 pub enum EventStatus<'a> {
@@ -41,28 +274,278 @@ impl<'a> EventStatus<'a> {
     }
 }
 
+/// What the status title counts for the event it shows, per `title.current`
+/// and `title.upcoming`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Countdown {
+    /// "Standup • 12m left"
+    Remaining,
+    /// "Standup • 18m in"
+    Elapsed,
+    /// "Standup • next in 38m", counting to the start of the next event
+    NextIn(DateTime<Local>),
+    /// "Standup • started 7m ago", for calls not joined yet
+    StartedAgo,
+    /// "⚠ Next overlaps in 5m", when the next event starts before the current
+    /// one ends
+    NextOverlaps(DateTime<Local>),
+    /// "⏳ Standup • 5m left", in the last minutes per `title.wrap-up`
+    WrapUp,
+    /// "Standup • in 12m"
+    Until,
+    /// "Standup at 09:30"
+    StartTime,
+}
+
+impl Countdown {
+    /// `next_start` is when the event after the current one starts, if any
+    /// does today.
+    fn for_status(
+        status: &EventStatus,
+        joined: Option<&OccurrenceRef>,
+        next_start: Option<DateTime<Local>>,
+        now: DateTime<Local>,
+        config: &Config,
+    ) -> Self {
+        if let (EventStatus::Current(e), Some(start)) = (status, next_start) {
+            if Self::warns_overlap(e, start, now, config) {
+                return Self::NextOverlaps(start);
+            }
+        }
+        match status {
+            EventStatus::Current(e) if Self::wraps_up(e, now, config) => Self::WrapUp,
+            EventStatus::Current(e) if Self::awaits_join(e, joined, config) => Self::StartedAgo,
+            EventStatus::Current(_) => match config.title.current {
+                CurrentCountdown::Remaining => Self::Remaining,
+                CurrentCountdown::Elapsed => Self::Elapsed,
+                CurrentCountdown::NextCommitment => {
+                    next_start.map_or(Self::Remaining, Self::NextIn)
+                }
+            },
+            EventStatus::Upcoming(_) => match config.title.upcoming {
+                UpcomingCountdown::Until => Self::Until,
+                UpcomingCountdown::StartTime => Self::StartTime,
+            },
+        }
+    }
+
+    /// A call in progress that hasn't been joined from eventually, when
+    /// `started-ago` asks to nudge about those.
+    fn awaits_join(event: &EventInfo, joined: Option<&OccurrenceRef>, config: &Config) -> bool {
+        config.title.started_ago
+            && joined != Some(&event.occurrence)
+            && event.join_url(&config.join.prefer).is_some()
+    }
+
+    /// The next event starts before `event` ends, and soon. All-day events
+    /// overlap everything, so they never warn.
+    fn warns_overlap(
+        event: &EventInfo,
+        next_start: DateTime<Local>,
+        now: DateTime<Local>,
+        config: &Config,
+    ) -> bool {
+        let warning = Duration::minutes(config.title.overlap_warning as i64);
+        next_start < event.end
+            && next_start.signed_duration_since(now) <= warning
+            && !formatting::is_all_day(&event.start, &event.end)
+    }
+
+    fn wraps_up(event: &EventInfo, now: DateTime<Local>, config: &Config) -> bool {
+        config.title.wrap_up.is_some_and(|minutes| {
+            event.end.signed_duration_since(now) <= Duration::minutes(minutes as i64)
+                && !formatting::is_all_day(&event.start, &event.end)
+        })
+    }
+
+    /// Warnings are about what comes next, so they leave out the current
+    /// event's title.
+    fn shows_title(self) -> bool {
+        !matches!(self, Self::NextOverlaps(_))
+    }
+
+    fn template(self) -> &'static str {
+        match self {
+            Self::Remaining => "{} • {} left",
+            Self::Elapsed => "{} • {} in",
+            Self::NextIn(_) => "{} • next in {}",
+            Self::StartedAgo => "{} • started {} ago",
+            Self::NextOverlaps(_) => "⚠ Next overlaps in {}",
+            Self::WrapUp => "⏳ {} • {} left",
+            Self::Until => "{} • in {}",
+            Self::StartTime => "{} at {}",
+        }
+    }
+
+    fn time(self, event: &EventInfo, now: DateTime<Local>) -> String {
+        match self {
+            Self::Remaining | Self::WrapUp => {
+                formatting::format_duration(event.end.signed_duration_since(now))
+            }
+            Self::Elapsed | Self::StartedAgo => {
+                formatting::format_duration(now.signed_duration_since(event.start))
+            }
+            Self::NextIn(start) | Self::NextOverlaps(start) => {
+                formatting::format_duration(start.signed_duration_since(now))
+            }
+            Self::Until => formatting::format_duration(event.start.signed_duration_since(now)),
+            Self::StartTime => formatting::format_time(&event.start),
+        }
+    }
+
+    /// The time alone for `time-only` titles. Upcoming events show their start
+    /// time either way, as a bare duration would read as time left.
+    fn time_only(self, event: &EventInfo, now: DateTime<Local>) -> String {
+        match self {
+            Self::Remaining => self.time(event, now),
+            Self::Elapsed => format!("{} in", self.time(event, now)),
+            Self::NextIn(_) => format!("next in {}", self.time(event, now)),
+            Self::StartedAgo => format!("{} ago", self.time(event, now)),
+            Self::NextOverlaps(_) => format!("⚠ overlaps in {}", self.time(event, now)),
+            Self::WrapUp => format!("⏳ {}", self.time(event, now)),
+            Self::Until | Self::StartTime => formatting::format_time(&event.start),
+        }
+    }
+}
+
+pub(super) fn round_up_to_quarter_hour(time: DateTime<Local>) -> DateTime<Local> {
+    let time = time
+        .with_second(0)
+        .and_then(|t| t.with_nanosecond(0))
+        .unwrap_or(time);
+    let past_quarter = time.minute() % 15;
+    if past_quarter == 0 {
+        time
+    } else {
+        time + Duration::minutes((15 - past_quarter) as i64)
+    }
+}
+
+#[derive(Clone, Debug, Default)]
 pub struct EventCollection(Vec<EventInfo>);
 
 impl EventCollection {
-    pub fn fetch(store: &EKEventStore) -> Self {
-        let (start_date, end_date) = Self::date_range();
-        let events = Self::fetch_raw_events(store, &start_date, &end_date);
+    /// Fetches from the start of `clock`'s day through the day `days` days
+    /// later, from the calendars `config.calendars` shows.
+    pub fn fetch(
+        store: &EKEventStore,
+        clock: &dyn Clock,
+        days: u32,
+        config: &Config,
+    ) -> Result<Self, CalendarError> {
+        use super::super::ffi::event_kit;
+        let (start_date, end_date) = Self::date_range(clock.now(), days);
+        let mut events: Vec<_> =
+            Self::fetch_raw_events(store, &start_date, &end_date, &config.calendars)
+                .ok_or(CalendarError::StoreUnavailable)?
+                .into_iter()
+                .map(|e| {
+                    let start = Self::timestamp_to_local(
+                        event_kit::get_event_start(&e).timeIntervalSince1970(),
+                    );
+                    (start, e)
+                })
+                .collect();
+        events.sort_by_key(|(start, _)| *start);
+
+        let default_calendar_id = event_kit::get_default_calendar_id(store);
 
-        let mut event_list: Vec<EventInfo> = events.iter().map(|e| Self::parse_event(e)).collect();
+        let mut event_list: Vec<_> = events
+            .iter()
+            .map(|(_, e)| Self::parse_event(e, default_calendar_id.as_deref()))
+            .collect();
+        // Events past the caps never get a row of their own, so skip looking up
+        // what only rows show
+        let listed = listed_events(&event_list, config);
+        for ((info, listed), (_, e)) in event_list.iter_mut().zip(listed).zip(&events) {
+            if listed {
+                Self::add_row_details(info, e);
+            }
+        }
 
-        event_list.sort_by_key(|e| e.start);
-        Self(event_list)
+        Ok(Self(event_list))
     }
 
-    pub fn find_cur_or_next(&self, dismissed: &HashSet<String>) -> Option<EventStatus<'_>> {
-        let now = Local::now();
+    pub fn iter(&self) -> std::slice::Iter<'_, EventInfo> {
+        self.0.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Events starting on `day`, in start order.
+    pub fn events_on(&self, day: NaiveDate) -> impl Iterator<Item = &EventInfo> {
+        self.0.iter().filter(move |e| e.start.date_naive() == day)
+    }
+
+    /// Events overlapping `day`, including ones still running from the day
+    /// before, in start order.
+    pub fn during(&self, day: NaiveDate) -> impl Iterator<Item = &EventInfo> {
+        let start = local_midnight(day);
+        let end = day.succ_opt().and_then(local_midnight);
+        self.0.iter().filter(move |e| match (start, end) {
+            (Some(start), Some(end)) => e.start < end && e.end > start,
+            _ => e.start.date_naive() == day,
+        })
+    }
+
+    /// Events overlapping `start..end`, including ones already in progress at
+    /// `start`.
+    pub fn between(
+        &self,
+        start: DateTime<Local>,
+        end: DateTime<Local>,
+    ) -> impl Iterator<Item = &EventInfo> {
+        self.0
+            .iter()
+            .filter(move |e| e.start < end && e.end > start)
+    }
+
+    pub fn by_calendar<'a>(&'a self, calendar_id: &'a str) -> impl Iterator<Item = &'a EventInfo> {
+        self.0.iter().filter(move |e| e.calendar_id == calendar_id)
+    }
+
+    /// The same events with their offsets recomputed for the current time
+    /// zone. A `DateTime<Local>` keeps the offset it was created with, so after
+    /// travel or a DST shift held events would show, and group by, stale
+    /// wall-clock times; the instants themselves don't change.
+    pub fn in_current_time_zone(&self) -> Self {
+        Self(
+            self.0
+                .iter()
+                .map(|e| EventInfo {
+                    start: e.start.with_timezone(&Local),
+                    end: e.end.with_timezone(&Local),
+                    ..e.clone()
+                })
+                .collect(),
+        )
+    }
+
+    /// The first event starting after `time`.
+    pub fn next_after(&self, time: DateTime<Local>) -> Option<&EventInfo> {
+        self.0.iter().find(|e| e.start > time)
+    }
+
+    pub fn find_cur_or_next(
+        &self,
+        clock: &dyn Clock,
+        dismissed: &HashSet<OccurrenceRef>,
+        config: &Config,
+    ) -> Option<EventStatus<'_>> {
+        let now = clock.now();
         let today = now.date_naive();
+        if !config.countdown.keep_on_ooo_days && self.is_ooo_day(today) {
+            return None;
+        }
         let mut upcoming = None;
 
+        // By overlap rather than start date, so an event running past midnight
+        // stays current until it ends
         for event in self
-            .0
-            .iter()
-            .filter(|e| e.start.date_naive() == today && !dismissed.contains(&e.occurrence_key))
+            .during(today)
+            .filter(|e| !dismissed.contains(&e.occurrence) && Self::drives_countdown(e, config))
         {
             if event.start <= now && now <= event.end {
                 return Some(EventStatus::Current(event));
@@ -75,50 +558,334 @@ impl EventCollection {
         upcoming
     }
 
-    pub fn get_title(&self, dismissed: &HashSet<String>) -> String {
-        let now = Local::now();
+    /// Start of the first event after `now` today that could drive the
+    /// countdown, for `current = "next-commitment"`.
+    fn next_commitment_start(
+        &self,
+        now: DateTime<Local>,
+        dismissed: &HashSet<OccurrenceRef>,
+        config: &Config,
+    ) -> Option<DateTime<Local>> {
+        self.events_on(now.date_naive())
+            .filter(|e| !dismissed.contains(&e.occurrence) && Self::drives_countdown(e, config))
+            .map(|e| e.start)
+            .filter(|start| *start > now)
+            .min()
+    }
 
-        match self.find_cur_or_next(dismissed) {
-            Some(EventStatus::Current(e)) => {
-                let remaining = e.end.signed_duration_since(now);
-                formatting::format_event_title(&e.title, remaining, "{} • {} left")
+    /// Whether one of my own OOO/holiday events covers `day`.
+    pub fn is_ooo_day(&self, day: NaiveDate) -> bool {
+        self.0
+            .iter()
+            .any(|e| e.is_own_absence() && e.start.date_naive() <= day && day <= e.end.date_naive())
+    }
+
+    /// Whether `event` may interrupt, with a banner or by pausing music as it
+    /// starts. It follows the countdown's rules, and also leaves out solo
+    /// events without a call link and, unless `keep-on-ooo-days`, my own days
+    /// off.
+    pub fn alerts_for(&self, event: &EventInfo, config: &Config) -> bool {
+        Self::drives_countdown(event, config)
+            && event.needs_join_urgency()
+            && (config.countdown.keep_on_ooo_days || !self.is_ooo_day(event.start.date_naive()))
+    }
+
+    /// Events left out here are still listed in the menu, they just never
+    /// become the current/next event.
+    fn drives_countdown(event: &EventInfo, config: &Config) -> bool {
+        if event.is_muted(&config.calendars) {
+            return false;
+        }
+        let config = &config.countdown;
+        let too_long = config.max_duration.is_some_and(|max| {
+            event.end.signed_duration_since(event.start) > Duration::minutes(max)
+        }) && !config.is_pinned(&event.title);
+        !(config.exclude_free && event.is_free
+            || config.accepted_only && !event.is_accepted
+            || too_long)
+    }
+
+    /// `joined` is the occurrence key of the meeting last joined, which counts
+    /// down instead of showing how long ago it started.
+    pub fn get_title(
+        &self,
+        clock: &dyn Clock,
+        dismissed: &HashSet<OccurrenceRef>,
+        joined: Option<&OccurrenceRef>,
+        config: &Config,
+    ) -> String {
+        let now = clock.now();
+        let title_config = &config.title;
+        let abbreviations = title_config
+            .smart_shorten
+            .then_some(&title_config.abbreviations);
+
+        match self.find_cur_or_next(clock, dismissed, config) {
+            Some(status) => {
+                let e = status.event();
+                let next_start = self.next_commitment_start(now, dismissed, config);
+                let countdown = Countdown::for_status(&status, joined, next_start, now, config);
+                if !countdown.shows_title() {
+                    return countdown
+                        .template()
+                        .replacen("{}", &countdown.time(e, now), 1);
+                }
+                let template = Self::with_glyph(e, title_config, countdown.template());
+                formatting::format_event_title(
+                    &e.title,
+                    &countdown.time(e, now),
+                    &template,
+                    title_config.max_length,
+                    abbreviations,
+                )
+            }
+            None if self.is_ooo_day(now.date_naive()) => config.text.ooo_today.clone(),
+            None => title_config
+                .idle_format
+                .as_deref()
+                .map(|format| now.format(format).to_string())
+                .unwrap_or_else(|| config.text.no_more_events_today.clone()),
+        }
+    }
+
+    pub fn get_time_only_title(
+        &self,
+        clock: &dyn Clock,
+        dismissed: &HashSet<OccurrenceRef>,
+        joined: Option<&OccurrenceRef>,
+        config: &Config,
+    ) -> String {
+        let now = clock.now();
+        let title_config = &config.title;
+        match self.find_cur_or_next(clock, dismissed, config) {
+            Some(status) => {
+                let e = status.event();
+                let next_start = self.next_commitment_start(now, dismissed, config);
+                let countdown = Countdown::for_status(&status, joined, next_start, now, config);
+                Self::with_glyph(e, title_config, &countdown.time_only(e, now))
             }
-            Some(EventStatus::Upcoming(e)) => {
-                let until = e.start.signed_duration_since(now);
-                formatting::format_event_title(&e.title, until, "{} • in {}")
+            None if self.is_ooo_day(now.date_naive()) => config.text.ooo_today.clone(),
+            None => title_config
+                .idle_format
+                .as_deref()
+                .map(|format| now.format(format).to_string())
+                .unwrap_or_default(),
+        }
+    }
+
+    fn with_glyph(event: &EventInfo, config: &TitleConfig, text: &str) -> String {
+        match config
+            .service_glyph
+            .then(|| event.medium(&[]).glyph())
+            .flatten()
+        {
+            Some(glyph) => format!("{} {}", glyph, text),
+            None => text.to_string(),
+        }
+    }
+
+    /// One-line overview of today, e.g. "5 meetings · next: Standup 09:30 ·
+    /// free after 16:00".
+    pub fn today_summary(
+        &self,
+        clock: &dyn Clock,
+        dismissed: &HashSet<OccurrenceRef>,
+        config: &Config,
+    ) -> String {
+        let now = clock.now();
+        let today = now.date_naive();
+        let meetings: Vec<_> = self.events_on(today).filter(|e| e.is_meeting()).collect();
+        if meetings.is_empty() {
+            return "No meetings today".to_string();
+        }
+
+        let mut parts = vec![match meetings.len() {
+            1 => "1 meeting".to_string(),
+            n => format!("{} meetings", n),
+        }];
+        if let Some(next) = meetings.iter().find(|e| {
+            e.start > now && !dismissed.contains(&e.occurrence) && Self::drives_countdown(e, config)
+        }) {
+            parts.push(format!(
+                "next: {} {}",
+                next.title,
+                formatting::format_time(&next.start)
+            ));
+        }
+        match meetings.iter().map(|e| e.end).max() {
+            Some(last_end) if last_end > now => {
+                parts.push(format!("free after {}", formatting::format_time(&last_end)))
             }
-            None => "No more events today".to_string(),
+            _ => parts.push("free for the rest of the day".to_string()),
         }
+        parts.join(" · ")
     }
 
-    pub fn into_vec(self) -> Vec<EventInfo> {
+    /// The rest of today's meetings as sentences to read aloud, e.g. "2 more
+    /// meetings today. Now, Standup, until 09:45. At 14:00, Review. Free after
+    /// 15:00." Dismissed events and muted calendars are left out.
+    pub fn agenda_speech(
+        &self,
+        clock: &dyn Clock,
+        dismissed: &HashSet<OccurrenceRef>,
+        config: &Config,
+    ) -> String {
+        let now = clock.now();
+        let remaining: Vec<_> = self
+            .events_on(now.date_naive())
+            .filter(|e| {
+                e.is_meeting()
+                    && e.end > now
+                    && !dismissed.contains(&e.occurrence)
+                    && !e.is_muted(&config.calendars)
+            })
+            .collect();
+        let Some(last_end) = remaining.iter().map(|e| e.end).max() else {
+            return "No more meetings today.".to_string();
+        };
+
+        let mut sentences = vec![match remaining.len() {
+            1 => "1 more meeting today.".to_string(),
+            n => format!("{} more meetings today.", n),
+        }];
+        for event in remaining {
+            sentences.push(if event.start <= now {
+                format!(
+                    "Now, {}, until {}.",
+                    event.title,
+                    formatting::format_time(&event.end)
+                )
+            } else {
+                format!(
+                    "At {}, {}.",
+                    formatting::format_time(&event.start),
+                    event.title
+                )
+            });
+        }
+        sentences.push(format!(
+            "Free after {}.",
+            formatting::format_time(&last_end)
+        ));
+        sentences.join(" ")
+    }
+
+    /// Timed events whose notification, `minutes_before` their start, falls
+    /// after `since` and by `now`. Dismissed events and those `alerts_for`
+    /// leaves out get none.
+    pub fn due_notifications(
+        &self,
+        since: DateTime<Local>,
+        now: DateTime<Local>,
+        minutes_before: i64,
+        dismissed: &HashSet<OccurrenceRef>,
+        config: &Config,
+    ) -> Vec<&EventInfo> {
         self.0
+            .iter()
+            .filter(|e| {
+                let due = e.start - Duration::minutes(minutes_before);
+                since < due
+                    && due <= now
+                    && e.end > now
+                    && !formatting::is_all_day(&e.start, &e.end)
+                    && !dismissed.contains(&e.occurrence)
+                    && self.alerts_for(e, config)
+            })
+            .collect()
     }
 
-    fn date_range() -> (Retained<NSDate>, Retained<NSDate>) {
-        let today = Local::now().date_naive();
+    /// Pairs of a timed event and the next one, starting before it ends, whose
+    /// overlap warning fell after `since` and by `now`. Like the title's, the
+    /// warning comes `title.overlap-warning` minutes before the next start,
+    /// but not before the first event has begun.
+    pub fn due_overlap_warnings(
+        &self,
+        since: DateTime<Local>,
+        now: DateTime<Local>,
+        dismissed: &HashSet<OccurrenceRef>,
+        config: &Config,
+    ) -> Vec<(&EventInfo, &EventInfo)> {
+        let warning = Duration::minutes(config.title.overlap_warning as i64);
+        if warning <= Duration::zero() {
+            return Vec::new();
+        }
+        let alerting: Vec<&EventInfo> = self
+            .0
+            .iter()
+            .filter(|e| {
+                !formatting::is_all_day(&e.start, &e.end)
+                    && !dismissed.contains(&e.occurrence)
+                    && self.alerts_for(e, config)
+            })
+            .collect();
+
+        let mut warnings = Vec::new();
+        for next in &alerting {
+            let current = alerting.iter().find(|current| {
+                let warn_at = (next.start - warning).max(current.start);
+                current.start < next.start
+                    && next.start < current.end
+                    && since < warn_at
+                    && warn_at <= now
+                    && now < next.start
+            });
+            if let Some(current) = current {
+                warnings.push((*current, *next));
+            }
+        }
+        warnings
+    }
+
+    pub fn find_occurrence(&self, occurrence: &OccurrenceRef) -> Option<&EventInfo> {
+        self.0.iter().find(|e| &e.occurrence == occurrence)
+    }
+
+    /// Start of the first gap of `length` at or after `after`, on a quarter
+    /// hour. Free and all-day events don't block a slot.
+    pub fn next_free_slot(&self, after: DateTime<Local>, length: Duration) -> DateTime<Local> {
+        let mut busy: Vec<_> = self
+            .0
+            .iter()
+            .filter(|e| e.is_meeting())
+            .map(|e| (e.start, e.end))
+            .collect();
+        busy.sort_by_key(|(start, _)| *start);
+
+        let mut slot = round_up_to_quarter_hour(after);
+        for (start, end) in busy {
+            if start >= slot + length {
+                break;
+            }
+            if end > slot {
+                slot = round_up_to_quarter_hour(end);
+            }
+        }
+        slot
+    }
+
+    fn date_range(now: DateTime<Local>, days: u32) -> (Retained<NSDate>, Retained<NSDate>) {
+        let today = now.date_naive();
 
         let start = today
             .and_hms_opt(0, 0, 0)
             .and_then(|dt| dt.and_local_timezone(Local).single())
             .unwrap_or_else(|| {
-                Local::now()
-                    .with_hour(0)
+                now.with_hour(0)
                     .and_then(|t| t.with_minute(0))
                     .and_then(|t| t.with_second(0))
-                    .unwrap_or_else(|| Local::now())
+                    .unwrap_or(now)
             });
 
-        let end = (today + Duration::days(DAYS_TO_FETCH as i64))
+        let end = (today + Duration::days(days as i64))
             .and_hms_opt(23, 59, 59)
             .and_then(|dt| dt.and_local_timezone(Local).single())
             .unwrap_or_else(|| {
-                Local::now()
-                    .with_hour(23)
+                now.with_hour(23)
                     .and_then(|t| t.with_minute(59))
                     .and_then(|t| t.with_second(59))
-                    .unwrap_or_else(|| Local::now())
-                    + Duration::days(DAYS_TO_FETCH as i64)
+                    .unwrap_or(now)
+                    + Duration::days(days as i64)
             });
 
         (
@@ -131,18 +898,30 @@ impl EventCollection {
         store: &EKEventStore,
         start: &NSDate,
         end: &NSDate,
-    ) -> Vec<Retained<EKEvent>> {
+        calendars: &CalendarsConfig,
+    ) -> Option<Vec<Retained<EKEvent>>> {
         use super::super::ffi::event_kit;
-        event_kit::fetch_events(store, start, end)
+        event_kit::fetch_events(store, start, end, |id, name| calendars.is_shown(id, name))
     }
 
-    fn parse_event(event: &EKEvent) -> EventInfo {
+    /// Everything but what only menu rows show, which `add_row_details` adds.
+    fn parse_event(event: &EKEvent, default_calendar_id: Option<&str>) -> EventInfo {
         use super::super::ffi::event_kit;
-        let (start_date, end_date, event_id, title, location, calendar, has_recurrence) =
+        let (start_date, end_date, event_id, title, location, has_recurrence) =
             event_kit::get_event_properties(event);
+        let calendar = event_kit::get_event_calendar(event);
 
         let start_ts = start_date.timeIntervalSince1970();
         let end_ts = end_date.timeIntervalSince1970();
+        let start = Self::timestamp_to_local(start_ts);
+        let end = Self::timestamp_to_local(end_ts);
+        // Only all-day events can mark my own day off, so others skip the lookup
+        let is_own = formatting::is_all_day(&start, &end)
+            && default_calendar_id.is_some_and(|default_id| {
+                calendar
+                    .as_ref()
+                    .is_some_and(|c| event_kit::get_calendar_id(c) == default_id)
+            });
         let event_id_str = event_id
             .as_ref()
             .map(|id| id.to_string())
@@ -150,25 +929,79 @@ impl EventCollection {
 
         EventInfo {
             title: title.to_string(),
-            start: Self::timestamp_to_local(start_ts),
-            end: Self::timestamp_to_local(end_ts),
-            occurrence_key: format!("{event_id_str}|||{}", start_ts as i64),
+            start,
+            end,
+            occurrence: OccurrenceRef::new(event_id_str.clone(), start_ts as i64),
             event_id: event_id_str,
             has_recurrence,
             location: location.map(|l| l.to_string()),
-            calendar_color: calendar
-                .map(|c| Self::extract_color(&c))
-                .unwrap_or(DEFAULT_CALENDAR_COLOR),
+            calendar_color: DEFAULT_CALENDAR_COLOR,
+            is_free: event_kit::is_event_free(event),
+            is_accepted: event_kit::is_event_accepted(event),
+            other_attendees: event_kit::count_other_attendees(event),
+            // Read for every event, since call links in them drive joining too
+            notes: event_kit::get_event_notes(event).map(|notes| notes.to_string()),
+            url: event_kit::get_event_url(event),
+            is_own,
+            calendar_name: calendar
+                .as_ref()
+                .map(|c| event_kit::get_calendar_title(c))
+                .unwrap_or_default(),
+            calendar_id: calendar
+                .as_ref()
+                .map(|c| event_kit::get_calendar_id(c))
+                .unwrap_or_default(),
+            is_subscribed: calendar
+                .as_ref()
+                .is_some_and(|c| event_kit::is_subscribed_calendar(c)),
+            rooms: Vec::new(),
+            google_url: None,
         }
     }
 
+    /// The calendar color, rooms, and Google Calendar link, which only menu
+    /// rows show.
+    fn add_row_details(info: &mut EventInfo, event: &EKEvent) {
+        use super::super::ffi::event_kit;
+        let calendar = event_kit::get_event_calendar(event);
+        if let Some(color) = calendar.as_ref().and_then(|c| Self::extract_color(c)) {
+            info.calendar_color = color;
+        }
+        info.rooms = event_kit::get_event_rooms(event);
+        info.google_url = calendar
+            .as_ref()
+            .and_then(|c| Self::google_url(event, c, &info.start, &info.end, info.has_recurrence));
+    }
+
+    /// The calendar's email is its name for a Google account's own calendar,
+    /// and the account's for calendars shared with it.
+    fn google_url(
+        event: &EKEvent,
+        calendar: &EKCalendar,
+        start: &DateTime<Local>,
+        end: &DateTime<Local>,
+        has_recurrence: bool,
+    ) -> Option<String> {
+        use super::super::ffi::event_kit;
+        let external_id = event_kit::get_event_external_id(event)?;
+        let account = event_kit::get_caldav_account(calendar)?;
+        let title = event_kit::get_calendar_title(calendar);
+        let email = if title.contains('@') { title } else { account };
+        google::google_calendar_url(
+            &external_id,
+            &email,
+            has_recurrence.then_some(*start),
+            formatting::is_all_day(start, end),
+        )
+    }
+
     fn timestamp_to_local(ts: f64) -> DateTime<Local> {
         DateTime::from_timestamp(ts as i64, 0)
             .unwrap_or_else(|| DateTime::UNIX_EPOCH)
             .with_timezone(&Local)
     }
 
-    fn extract_color(calendar: &EKCalendar) -> (f64, f64, f64) {
+    fn extract_color(calendar: &EKCalendar) -> Option<(f64, f64, f64)> {
         use super::super::ffi::event_kit;
         event_kit::get_calendar_color(calendar)
     }
@@ -177,6 +1010,7 @@ impl EventCollection {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::clock::{FrozenClock, SystemClock};
 
     #[test]
     fn test_event_status_current() {
@@ -185,10 +1019,21 @@ mod tests {
             start: Local::now(),
             end: Local::now() + Duration::hours(1),
             event_id: "test-id".to_string(),
-            occurrence_key: "test-key".to_string(),
+            occurrence: OccurrenceRef::new("test-key", 0),
             has_recurrence: false,
             location: None,
             calendar_color: (0.5, 0.5, 0.5),
+            is_free: false,
+            is_accepted: true,
+            other_attendees: 0,
+            notes: None,
+            url: None,
+            is_own: false,
+            calendar_name: String::new(),
+            calendar_id: String::new(),
+            is_subscribed: false,
+            rooms: Vec::new(),
+            google_url: None,
         };
 
         let status = EventStatus::Current(&event);
@@ -202,10 +1047,21 @@ mod tests {
             start: Local::now() + Duration::hours(1),
             end: Local::now() + Duration::hours(2),
             event_id: "test-id".to_string(),
-            occurrence_key: "test-key".to_string(),
+            occurrence: OccurrenceRef::new("test-key", 0),
             has_recurrence: false,
             location: None,
             calendar_color: (0.5, 0.5, 0.5),
+            is_free: false,
+            is_accepted: true,
+            other_attendees: 0,
+            notes: None,
+            url: None,
+            is_own: false,
+            calendar_name: String::new(),
+            calendar_id: String::new(),
+            is_subscribed: false,
+            rooms: Vec::new(),
+            google_url: None,
         };
 
         let status = EventStatus::Upcoming(&event);
@@ -220,15 +1076,26 @@ mod tests {
             start: now - Duration::minutes(30),
             end: now + Duration::minutes(30),
             event_id: "id1".to_string(),
-            occurrence_key: "key1".to_string(),
+            occurrence: OccurrenceRef::new("key1", 0),
             has_recurrence: false,
             location: None,
             calendar_color: (0.5, 0.5, 0.5),
+            is_free: false,
+            is_accepted: true,
+            other_attendees: 0,
+            notes: None,
+            url: None,
+            is_own: false,
+            calendar_name: String::new(),
+            calendar_id: String::new(),
+            is_subscribed: false,
+            rooms: Vec::new(),
+            google_url: None,
         }];
 
         let collection = EventCollection(events);
         let dismissed = HashSet::new();
-        let result = collection.find_cur_or_next(&dismissed);
+        let result = collection.find_cur_or_next(&SystemClock, &dismissed, &Config::default());
 
         assert!(result.is_some());
         if let Some(EventStatus::Current(event)) = result {
@@ -246,15 +1113,26 @@ mod tests {
             start: now + Duration::hours(1),
             end: now + Duration::hours(2),
             event_id: "id1".to_string(),
-            occurrence_key: "key1".to_string(),
+            occurrence: OccurrenceRef::new("key1", 0),
             has_recurrence: false,
             location: None,
             calendar_color: (0.5, 0.5, 0.5),
+            is_free: false,
+            is_accepted: true,
+            other_attendees: 0,
+            notes: None,
+            url: None,
+            is_own: false,
+            calendar_name: String::new(),
+            calendar_id: String::new(),
+            is_subscribed: false,
+            rooms: Vec::new(),
+            google_url: None,
         }];
 
         let collection = EventCollection(events);
         let dismissed = HashSet::new();
-        let result = collection.find_cur_or_next(&dismissed);
+        let result = collection.find_cur_or_next(&SystemClock, &dismissed, &Config::default());
 
         assert!(result.is_some());
         if let Some(EventStatus::Upcoming(event)) = result {
@@ -272,20 +1150,80 @@ mod tests {
             start: now + Duration::hours(1),
             end: now + Duration::hours(2),
             event_id: "id1".to_string(),
-            occurrence_key: "key1".to_string(),
+            occurrence: OccurrenceRef::new("key1", 0),
             has_recurrence: false,
             location: None,
             calendar_color: (0.5, 0.5, 0.5),
+            is_free: false,
+            is_accepted: true,
+            other_attendees: 0,
+            notes: None,
+            url: None,
+            is_own: false,
+            calendar_name: String::new(),
+            calendar_id: String::new(),
+            is_subscribed: false,
+            rooms: Vec::new(),
+            google_url: None,
         }];
 
         let collection = EventCollection(events);
         let mut dismissed = HashSet::new();
-        dismissed.insert("key1".to_string());
-        let result = collection.find_cur_or_next(&dismissed);
+        dismissed.insert(OccurrenceRef::new("key1", 0));
+        let result = collection.find_cur_or_next(&SystemClock, &dismissed, &Config::default());
 
         assert!(result.is_none());
     }
 
+    #[test]
+    fn test_event_collection_exclude_free() {
+        let day = NaiveDate::from_ymd_opt(2025, 3, 7).unwrap();
+        let clock = FrozenClock(at(day, 10, 0).unwrap());
+        let collection = EventCollection(vec![
+            EventInfo {
+                is_free: true,
+                ..timed_event("Focus", at(day, 9, 50).unwrap(), at(day, 11, 0).unwrap())
+            },
+            timed_event("Standup", at(day, 10, 30).unwrap(), at(day, 11, 0).unwrap()),
+        ]);
+        let dismissed = HashSet::new();
+
+        let result = collection.find_cur_or_next(&clock, &dismissed, &Config::default());
+        assert!(matches!(result, Some(EventStatus::Current(e)) if e.title == "Focus"));
+
+        let mut config = Config::default();
+        config.countdown.exclude_free = true;
+        let result = collection.find_cur_or_next(&clock, &dismissed, &config);
+        assert!(matches!(result, Some(EventStatus::Upcoming(e)) if e.title == "Standup"));
+    }
+
+    #[test]
+    fn test_event_collection_accepted_only() {
+        let day = NaiveDate::from_ymd_opt(2025, 3, 7).unwrap();
+        let clock = FrozenClock(at(day, 10, 0).unwrap());
+        let mut invite = EventInfo {
+            is_accepted: false,
+            ..timed_event("Invite", at(day, 10, 30).unwrap(), at(day, 11, 0).unwrap())
+        };
+        let dismissed = HashSet::new();
+        let mut config = Config::default();
+        config.countdown.accepted_only = true;
+
+        let collection = EventCollection(vec![invite.clone()]);
+        assert!(collection
+            .find_cur_or_next(&clock, &dismissed, &config)
+            .is_none());
+        assert!(collection
+            .find_cur_or_next(&clock, &dismissed, &Config::default())
+            .is_some());
+
+        invite.is_accepted = true;
+        let collection = EventCollection(vec![invite]);
+        assert!(collection
+            .find_cur_or_next(&clock, &dismissed, &config)
+            .is_some());
+    }
+
     #[test]
     fn test_event_collection_different_day() {
         let tomorrow = Local::now() + Duration::days(1);
@@ -294,15 +1232,26 @@ mod tests {
             start: tomorrow,
             end: tomorrow + Duration::hours(1),
             event_id: "id1".to_string(),
-            occurrence_key: "key1".to_string(),
+            occurrence: OccurrenceRef::new("key1", 0),
             has_recurrence: false,
             location: None,
             calendar_color: (0.5, 0.5, 0.5),
+            is_free: false,
+            is_accepted: true,
+            other_attendees: 0,
+            notes: None,
+            url: None,
+            is_own: false,
+            calendar_name: String::new(),
+            calendar_id: String::new(),
+            is_subscribed: false,
+            rooms: Vec::new(),
+            google_url: None,
         }];
 
         let collection = EventCollection(events);
         let dismissed = HashSet::new();
-        let result = collection.find_cur_or_next(&dismissed);
+        let result = collection.find_cur_or_next(&SystemClock, &dismissed, &Config::default());
 
         assert!(result.is_none());
     }
@@ -315,20 +1264,255 @@ mod tests {
             start: now - Duration::minutes(30),
             end: now + Duration::minutes(30),
             event_id: "id1".to_string(),
-            occurrence_key: "key1".to_string(),
+            occurrence: OccurrenceRef::new("key1", 0),
             has_recurrence: false,
             location: None,
             calendar_color: (0.5, 0.5, 0.5),
+            is_free: false,
+            is_accepted: true,
+            other_attendees: 0,
+            notes: None,
+            url: None,
+            is_own: false,
+            calendar_name: String::new(),
+            calendar_id: String::new(),
+            is_subscribed: false,
+            rooms: Vec::new(),
+            google_url: None,
         }];
 
         let collection = EventCollection(events);
         let dismissed = HashSet::new();
-        let title = collection.get_title(&dismissed);
+        let title = collection.get_title(&SystemClock, &dismissed, None, &Config::default());
 
         assert!(title.contains("Current"));
         assert!(title.contains("left"));
     }
 
+    #[test]
+    fn test_get_title_started_ago_until_joined() {
+        let day = NaiveDate::from_ymd_opt(2025, 3, 7).unwrap();
+        let clock = FrozenClock(at(day, 10, 7).unwrap());
+        let standup = EventInfo {
+            location: Some("https://zoom.us/j/123".to_string()),
+            ..timed_event("Standup", at(day, 10, 0).unwrap(), at(day, 10, 15).unwrap())
+        };
+        let joined = standup.occurrence.clone();
+        let collection = EventCollection(vec![standup]);
+        let dismissed = HashSet::new();
+        let mut config = Config::default();
+        config.title.started_ago = true;
+
+        assert_eq!(
+            collection.get_title(&clock, &dismissed, None, &config),
+            "Standup • started 7m ago"
+        );
+        assert!(collection
+            .get_title(&clock, &dismissed, Some(&joined), &config)
+            .contains("left"));
+        assert_eq!(
+            collection.get_time_only_title(&clock, &dismissed, None, &config),
+            "7m ago"
+        );
+
+        config.title.started_ago = false;
+        assert!(collection
+            .get_title(&clock, &dismissed, None, &config)
+            .contains("left"));
+    }
+
+    #[test]
+    fn test_get_title_countdown_strategies() {
+        let day = NaiveDate::from_ymd_opt(2025, 3, 7).unwrap();
+        let clock = FrozenClock(at(day, 10, 18).unwrap());
+        let dismissed = HashSet::new();
+        let mut config = Config::default();
+        config.title.current = CurrentCountdown::Elapsed;
+        config.title.upcoming = UpcomingCountdown::StartTime;
+
+        let current = EventCollection(vec![timed_event(
+            "Review",
+            at(day, 10, 0).unwrap(),
+            at(day, 10, 30).unwrap(),
+        )]);
+        assert_eq!(
+            current.get_title(&clock, &dismissed, None, &config),
+            "Review • 18m in"
+        );
+        assert_eq!(
+            current.get_time_only_title(&clock, &dismissed, None, &config),
+            "18m in"
+        );
+
+        let upcoming = EventCollection(vec![timed_event(
+            "Standup",
+            at(day, 11, 18).unwrap(),
+            at(day, 11, 33).unwrap(),
+        )]);
+        assert_eq!(
+            upcoming.get_title(&clock, &dismissed, None, &config),
+            "Standup at 11:18"
+        );
+    }
+
+    #[test]
+    fn test_in_current_time_zone() {
+        use chrono::FixedOffset;
+
+        let now = Local::now();
+        // Fetched before flying two hours east of here
+        let stale_offset = FixedOffset::east_opt(now.offset().local_minus_utc() + 7200).unwrap();
+        let stale = DateTime::<Local>::from_naive_utc_and_offset(now.naive_utc(), stale_offset);
+        let events = EventCollection(vec![timed_event(
+            "Standup",
+            stale,
+            stale + Duration::minutes(15),
+        )]);
+
+        let refreshed = events.in_current_time_zone();
+        let event = refreshed.iter().next().unwrap();
+        assert_eq!(event.start, now);
+        assert_eq!(event.start.naive_local(), now.naive_local());
+        assert_eq!(
+            event.end.naive_local(),
+            (now + Duration::minutes(15)).naive_local()
+        );
+    }
+
+    #[test]
+    fn test_debug_info() {
+        let day = NaiveDate::from_ymd_opt(2025, 3, 7).unwrap();
+        let mut event = timed_event("Standup", at(day, 9, 0).unwrap(), at(day, 9, 15).unwrap());
+        event.calendar_name = "Work".to_string();
+        event.calendar_id = "cal-1".to_string();
+        event.location = Some("https://zoom.us/j/123".to_string());
+
+        let info = event.debug_info();
+        assert!(info.contains(&format!("occurrence: {}\n", event.occurrence)));
+        assert!(info.contains("calendar: Work (cal-1)\n"));
+        assert!(info.contains("location: Some(\"https://zoom.us/j/123\")\n"));
+        assert!(info.ends_with("notes: None"));
+    }
+
+    #[test]
+    fn test_get_title_next_commitment() {
+        let day = NaiveDate::from_ymd_opt(2025, 3, 7).unwrap();
+        let now = at(day, 10, 22).unwrap();
+        let clock = FrozenClock(now);
+        let dismissed = HashSet::new();
+        let mut config = Config::default();
+        config.title.current = CurrentCountdown::NextCommitment;
+
+        let mut events = EventCollection(vec![
+            timed_event("Review", at(day, 10, 0).unwrap(), at(day, 10, 30).unwrap()),
+            timed_event("Planning", at(day, 11, 0).unwrap(), at(day, 12, 0).unwrap()),
+        ]);
+        assert_eq!(
+            events.get_title(&clock, &dismissed, None, &config),
+            "Review • next in 38m"
+        );
+        assert_eq!(
+            events.get_time_only_title(&clock, &dismissed, None, &config),
+            "next in 38m"
+        );
+
+        // Nothing else today: the time left is the break that's coming
+        events.0.pop();
+        assert_eq!(
+            events.get_title(&clock, &dismissed, None, &config),
+            "Review • 8m left"
+        );
+    }
+
+    #[test]
+    fn test_get_title_next_overlaps() {
+        let day = NaiveDate::from_ymd_opt(2025, 3, 7).unwrap();
+        let dismissed = HashSet::new();
+        let mut config = Config::default();
+        let events = EventCollection(vec![
+            timed_event("Review", at(day, 10, 0).unwrap(), at(day, 10, 30).unwrap()),
+            timed_event(
+                "Planning",
+                at(day, 10, 25).unwrap(),
+                at(day, 11, 0).unwrap(),
+            ),
+        ]);
+
+        let early = FrozenClock(at(day, 10, 10).unwrap());
+        assert_eq!(
+            events.get_title(&early, &dismissed, None, &config),
+            "Review • 20m left"
+        );
+
+        let soon = FrozenClock(at(day, 10, 20).unwrap());
+        assert_eq!(
+            events.get_title(&soon, &dismissed, None, &config),
+            "⚠ Next overlaps in 5m"
+        );
+        assert_eq!(
+            events.get_time_only_title(&soon, &dismissed, None, &config),
+            "⚠ overlaps in 5m"
+        );
+
+        config.title.overlap_warning = 0;
+        assert_eq!(
+            events.get_title(&soon, &dismissed, None, &config),
+            "Review • 10m left"
+        );
+    }
+
+    #[test]
+    fn test_get_title_wrap_up() {
+        let day = NaiveDate::from_ymd_opt(2025, 3, 7).unwrap();
+        let dismissed = HashSet::new();
+        let mut config = Config::default();
+        let events = EventCollection(vec![timed_event(
+            "Review",
+            at(day, 10, 0).unwrap(),
+            at(day, 10, 30).unwrap(),
+        )]);
+        let clock = FrozenClock(at(day, 10, 25).unwrap());
+        assert_eq!(
+            events.get_title(&clock, &dismissed, None, &config),
+            "Review • 5m left"
+        );
+
+        config.title.wrap_up = Some(5);
+        config.title.current = CurrentCountdown::Elapsed;
+        assert_eq!(
+            events.get_title(&clock, &dismissed, None, &config),
+            "⏳ Review • 5m left"
+        );
+        assert_eq!(
+            events.get_time_only_title(&clock, &dismissed, None, &config),
+            "⏳ 5m"
+        );
+
+        let earlier = FrozenClock(at(day, 10, 20).unwrap());
+        assert_eq!(
+            events.get_title(&earlier, &dismissed, None, &config),
+            "Review • 20m in"
+        );
+    }
+
+    #[test]
+    fn test_get_title_back_to_back_does_not_warn() {
+        let day = NaiveDate::from_ymd_opt(2025, 3, 7).unwrap();
+        let events = EventCollection(vec![
+            timed_event("Review", at(day, 10, 0).unwrap(), at(day, 10, 30).unwrap()),
+            timed_event(
+                "Planning",
+                at(day, 10, 30).unwrap(),
+                at(day, 11, 0).unwrap(),
+            ),
+        ]);
+        let clock = FrozenClock(at(day, 10, 25).unwrap());
+        assert_eq!(
+            events.get_title(&clock, &HashSet::new(), None, &Config::default()),
+            "Review • 5m left"
+        );
+    }
+
     #[test]
     fn test_event_collection_get_title_upcoming() {
         let now = Local::now();
@@ -337,15 +1521,26 @@ mod tests {
             start: now + Duration::hours(1),
             end: now + Duration::hours(2),
             event_id: "id1".to_string(),
-            occurrence_key: "key1".to_string(),
+            occurrence: OccurrenceRef::new("key1", 0),
             has_recurrence: false,
             location: None,
             calendar_color: (0.5, 0.5, 0.5),
+            is_free: false,
+            is_accepted: true,
+            other_attendees: 0,
+            notes: None,
+            url: None,
+            is_own: false,
+            calendar_name: String::new(),
+            calendar_id: String::new(),
+            is_subscribed: false,
+            rooms: Vec::new(),
+            google_url: None,
         }];
 
         let collection = EventCollection(events);
         let dismissed = HashSet::new();
-        let title = collection.get_title(&dismissed);
+        let title = collection.get_title(&SystemClock, &dismissed, None, &Config::default());
 
         assert!(title.contains("Upcoming"));
         assert!(title.contains("in"));
@@ -356,28 +1551,823 @@ mod tests {
         let events = vec![];
         let collection = EventCollection(events);
         let dismissed = HashSet::new();
-        let title = collection.get_title(&dismissed);
+        let title = collection.get_title(&SystemClock, &dismissed, None, &Config::default());
 
         assert_eq!(title, "No more events today");
     }
 
     #[test]
-    fn test_event_collection_into_vec() {
+    fn test_event_collection_get_title_custom_text() {
+        let collection = EventCollection(vec![]);
+        let dismissed = HashSet::new();
+        let mut config = Config::default();
+        config.text.no_more_events_today = "Done".to_string();
+
+        assert_eq!(
+            collection.get_title(&SystemClock, &dismissed, None, &config),
+            "Done"
+        );
+    }
+
+    #[test]
+    fn test_event_collection_get_title_idle_format() {
+        let collection = EventCollection(vec![]);
+        let dismissed = HashSet::new();
+        let mut config = Config::default();
+        config.title.idle_format = Some("Week %V".to_string());
+        let title = collection.get_title(&SystemClock, &dismissed, None, &config);
+
+        assert_eq!(title, Local::now().format("Week %V").to_string());
+    }
+
+    #[test]
+    fn test_event_collection_get_time_only_title_upcoming() {
+        let day = NaiveDate::from_ymd_opt(2025, 3, 7).unwrap();
+        let start = at(day, 11, 0).unwrap();
+        let collection = EventCollection(vec![timed_event(
+            "Upcoming",
+            start,
+            start + Duration::hours(1),
+        )]);
+        let dismissed = HashSet::new();
+        let clock = FrozenClock(at(day, 10, 0).unwrap());
+        let title = collection.get_time_only_title(&clock, &dismissed, None, &Config::default());
+
+        assert_eq!(title, formatting::format_time(&start));
+    }
+
+    #[test]
+    fn test_event_collection_iter() {
         let now = Local::now();
         let events = vec![EventInfo {
             title: "Test".to_string(),
             start: now,
             end: now + Duration::hours(1),
             event_id: "id1".to_string(),
-            occurrence_key: "key1".to_string(),
+            occurrence: OccurrenceRef::new("key1", 0),
             has_recurrence: false,
             location: None,
             calendar_color: (0.5, 0.5, 0.5),
+            is_free: false,
+            is_accepted: true,
+            other_attendees: 0,
+            notes: None,
+            url: None,
+            is_own: false,
+            calendar_name: String::new(),
+            calendar_id: String::new(),
+            is_subscribed: false,
+            rooms: Vec::new(),
+            google_url: None,
         }];
 
         let collection = EventCollection(events);
-        let vec = collection.into_vec();
+        let vec: Vec<_> = collection.iter().collect();
         assert_eq!(vec.len(), 1);
         assert_eq!(vec[0].title, "Test");
+        assert_eq!((&collection).into_iter().count(), 1);
+    }
+
+    #[test]
+    fn test_event_collection_queries() {
+        let day = Local::now()
+            .date_naive()
+            .and_hms_opt(0, 0, 0)
+            .and_then(|dt| dt.and_local_timezone(Local).single())
+            .unwrap();
+        let event = |title: &str, start_hour: i64, calendar_id: &str| EventInfo {
+            title: title.to_string(),
+            start: day + Duration::hours(start_hour),
+            end: day + Duration::hours(start_hour + 1),
+            event_id: title.to_string(),
+            occurrence: OccurrenceRef::new(title, 0),
+            has_recurrence: false,
+            location: None,
+            calendar_color: (0.5, 0.5, 0.5),
+            is_free: false,
+            is_accepted: true,
+            other_attendees: 0,
+            notes: None,
+            url: None,
+            is_own: false,
+            calendar_name: String::new(),
+            calendar_id: calendar_id.to_string(),
+            is_subscribed: false,
+            rooms: Vec::new(),
+            google_url: None,
+        };
+        let collection = EventCollection(vec![
+            event("Standup", 9, "work"),
+            event("Lunch", 12, "home"),
+            event("Review", 15, "work"),
+            event("Planning", 33, "work"),
+        ]);
+        let titles = |events: Vec<&EventInfo>| -> Vec<String> {
+            events.iter().map(|e| e.title.clone()).collect()
+        };
+
+        assert_eq!(
+            titles(collection.events_on(day.date_naive()).collect()),
+            ["Standup", "Lunch", "Review"]
+        );
+        assert_eq!(
+            titles(
+                collection
+                    .between(
+                        day + Duration::minutes(9 * 60 + 30),
+                        day + Duration::hours(15)
+                    )
+                    .collect()
+            ),
+            ["Standup", "Lunch"]
+        );
+        assert_eq!(
+            titles(collection.by_calendar("work").collect()),
+            ["Standup", "Review", "Planning"]
+        );
+        assert_eq!(
+            collection
+                .next_after(day + Duration::hours(12))
+                .map(|e| e.title.as_str()),
+            Some("Review")
+        );
+        assert!(collection.next_after(day + Duration::hours(40)).is_none());
+    }
+
+    #[test]
+    fn test_listed_in_day() {
+        assert_eq!(listed_in_day(5, 0), 5);
+        assert_eq!(listed_in_day(250, 0), MAX_EVENTS_PER_DAY);
+        assert_eq!(listed_in_day(30, MAX_EVENTS - 3), 3);
+        assert_eq!(listed_in_day(10, MAX_EVENTS), 0);
+    }
+
+    #[test]
+    fn test_listed_events_skip_muted_calendars() {
+        let mut fixture = String::new();
+        for n in 0..25 {
+            fixture.push_str(&format!(
+                "[[event]]\ntitle = \"Holiday {}\"\nstart = \"2025-03-07\"\ncalendar = \"Holidays\"\n",
+                n
+            ));
+        }
+        fixture.push_str("[[event]]\ntitle = \"Standup\"\nstart = \"2025-03-07 09:00\"\n");
+        let events = EventCollection::from_fixture(&fixture).unwrap();
+        let mut config = Config::default();
+
+        // Unmuted, the holidays fill the day's rows ahead of the meeting
+        assert_eq!(listed_events(&events.0, &config).last(), Some(&false));
+
+        // Muted, they're listed apart and the meeting keeps its row
+        config.calendars.muted = vec!["Holidays".to_string()];
+        let listed = listed_events(&events.0, &config);
+        assert_eq!(listed.len(), 26);
+        assert!(listed.iter().all(|&listed| listed));
+    }
+
+    #[test]
+    fn test_needs_join_urgency() {
+        let mut event = EventInfo {
+            title: "Focus".to_string(),
+            start: Local::now(),
+            end: Local::now() + Duration::hours(1),
+            event_id: "id1".to_string(),
+            occurrence: OccurrenceRef::new("key1", 0),
+            has_recurrence: false,
+            location: None,
+            calendar_color: (0.5, 0.5, 0.5),
+            is_free: false,
+            is_accepted: true,
+            other_attendees: 0,
+            notes: None,
+            url: None,
+            is_own: false,
+            calendar_name: String::new(),
+            calendar_id: String::new(),
+            is_subscribed: false,
+            rooms: Vec::new(),
+            google_url: None,
+        };
+        assert!(!event.needs_join_urgency());
+
+        event.location = Some("https://meet.google.com/abc-defg-hij".to_string());
+        assert!(event.needs_join_urgency());
+
+        event.location = Some("Room 4".to_string());
+        event.notes = Some("Agenda: https://docs.example.com/plan".to_string());
+        assert!(!event.needs_join_urgency());
+
+        event.notes = Some("Join at https://zoom.us/j/123456789".to_string());
+        assert!(event.needs_join_urgency());
+
+        event.notes = None;
+        event.url = Some("https://teams.microsoft.com/l/meetup-join/abc".to_string());
+        assert!(event.needs_join_urgency());
+
+        event.url = None;
+        event.other_attendees = 1;
+        assert!(event.needs_join_urgency());
+    }
+
+    #[test]
+    fn test_meeting_size() {
+        assert_eq!(MeetingSize::from_other_attendees(0), None);
+        assert_eq!(
+            MeetingSize::from_other_attendees(1),
+            Some(MeetingSize::OneOnOne)
+        );
+        assert_eq!(
+            MeetingSize::from_other_attendees(4),
+            Some(MeetingSize::SmallGroup)
+        );
+        assert_eq!(
+            MeetingSize::from_other_attendees(29),
+            Some(MeetingSize::Large)
+        );
+        assert_eq!(MeetingSize::SmallGroup.label(4), "Group · 5 people");
+    }
+
+    #[test]
+    fn test_event_links() {
+        let event = EventInfo {
+            title: "Planning".to_string(),
+            start: Local::now(),
+            end: Local::now() + Duration::hours(1),
+            event_id: "id1".to_string(),
+            occurrence: OccurrenceRef::new("key1", 0),
+            has_recurrence: false,
+            location: Some("https://zoom.us/j/123".to_string()),
+            calendar_color: (0.5, 0.5, 0.5),
+            is_free: false,
+            is_accepted: true,
+            other_attendees: 0,
+            notes: Some(
+                "Join: https://zoom.us/j/123\nDoc: https://docs.example.com/plan".to_string(),
+            ),
+            url: Some("https://tracker.example.com/42".to_string()),
+            is_own: false,
+            calendar_name: String::new(),
+            calendar_id: String::new(),
+            is_subscribed: false,
+            rooms: Vec::new(),
+            google_url: None,
+        };
+
+        assert_eq!(
+            event.links(),
+            vec![
+                "https://zoom.us/j/123",
+                "https://tracker.example.com/42",
+                "https://docs.example.com/plan",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_event_links_skip_invite_boilerplate() {
+        let day = NaiveDate::from_ymd_opt(2025, 3, 7).unwrap();
+        let mut event = timed_event("Sync", at(day, 9, 0).unwrap(), at(day, 9, 30).unwrap());
+        event.notes = Some(
+            "Notes: https://docs.example.com/sync\n\
+             -::~:~::~:~:~:~:~:~:~:~:~:~:~::~:~::-\n\
+             Join with Google Meet: https://meet.google.com/abc-defg-hij\n\
+             Learn more about Meet at: https://support.google.com/a/users/answer/9282720\n\
+             -::~:~::~:~:~:~:~:~:~:~:~:~:~::~:~::-"
+                .to_string(),
+        );
+
+        assert_eq!(
+            event.links(),
+            vec![
+                "https://docs.example.com/sync",
+                "https://meet.google.com/abc-defg-hij",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_join_url_precedence() {
+        let event = EventInfo {
+            title: "Sync".to_string(),
+            start: Local::now(),
+            end: Local::now() + Duration::hours(1),
+            event_id: "id1".to_string(),
+            occurrence: OccurrenceRef::new("key1", 0),
+            has_recurrence: false,
+            location: Some("https://meet.google.com/abc-defg-hij".to_string()),
+            calendar_color: (0.5, 0.5, 0.5),
+            is_free: false,
+            is_accepted: true,
+            other_attendees: 2,
+            notes: Some("Backup: https://acme.zoom.us/j/123".to_string()),
+            url: None,
+            is_own: false,
+            calendar_name: String::new(),
+            calendar_id: String::new(),
+            is_subscribed: false,
+            rooms: Vec::new(),
+            google_url: None,
+        };
+
+        assert_eq!(
+            event.join_url(&[]),
+            Some("https://meet.google.com/abc-defg-hij")
+        );
+        assert_eq!(
+            event.join_url(&[ServiceInfo::Zoom, ServiceInfo::GoogleMeet]),
+            Some("https://acme.zoom.us/j/123")
+        );
+        assert_eq!(
+            event.join_url(&[ServiceInfo::MicrosoftTeams]),
+            Some("https://meet.google.com/abc-defg-hij")
+        );
+    }
+
+    #[test]
+    fn test_next_free_slot() {
+        let base = Local::now()
+            .date_naive()
+            .and_hms_opt(10, 0, 0)
+            .and_then(|dt| dt.and_local_timezone(Local).single())
+            .unwrap();
+        let event = |start: i64, end: i64, is_free: bool| EventInfo {
+            title: "Busy".to_string(),
+            start: base + Duration::minutes(start),
+            end: base + Duration::minutes(end),
+            event_id: "id".to_string(),
+            occurrence: OccurrenceRef::new(format!("key{}", start), 0),
+            has_recurrence: false,
+            location: None,
+            calendar_color: (0.5, 0.5, 0.5),
+            is_free,
+            is_accepted: true,
+            other_attendees: 1,
+            notes: None,
+            url: None,
+            is_own: false,
+            calendar_name: String::new(),
+            calendar_id: String::new(),
+            is_subscribed: false,
+            rooms: Vec::new(),
+            google_url: None,
+        };
+        let collection = EventCollection(vec![
+            event(60, 90, false),
+            event(0, 50, false),
+            event(100, 150, false),
+            event(150, 240, true),
+        ]);
+        let length = Duration::minutes(30);
+
+        // 10:50 rounds to 11:00, which runs into the 11:00-11:30 event, and
+        // 11:30-12:00 is cut short by the one at 11:40
+        assert_eq!(
+            collection.next_free_slot(base + Duration::minutes(3), length),
+            base + Duration::minutes(150)
+        );
+        assert_eq!(
+            collection.next_free_slot(base + Duration::minutes(300), length),
+            base + Duration::minutes(300)
+        );
+    }
+
+    #[test]
+    fn test_today_summary() {
+        let now = Local::now()
+            .date_naive()
+            .and_hms_opt(10, 0, 0)
+            .and_then(|dt| dt.and_local_timezone(Local).single())
+            .unwrap();
+        let event = |title: &str, start: i64, end: i64| EventInfo {
+            title: title.to_string(),
+            start: now + Duration::minutes(start),
+            end: now + Duration::minutes(end),
+            event_id: "id".to_string(),
+            occurrence: OccurrenceRef::new(title, 0),
+            has_recurrence: false,
+            location: None,
+            calendar_color: (0.5, 0.5, 0.5),
+            is_free: false,
+            is_accepted: true,
+            other_attendees: 1,
+            notes: None,
+            url: None,
+            is_own: false,
+            calendar_name: String::new(),
+            calendar_id: String::new(),
+            is_subscribed: false,
+            rooms: Vec::new(),
+            google_url: None,
+        };
+        let mut focus = event("Focus", 60, 120);
+        focus.is_free = true;
+        let collection = EventCollection(vec![
+            event("Standup", -60, -45),
+            event("Planning", 30, 90),
+            event("Retro", 300, 360),
+            focus,
+        ]);
+        let config = Config::default();
+
+        assert_eq!(
+            collection.today_summary(&FrozenClock(now), &HashSet::new(), &config),
+            "3 meetings · next: Planning 10:30 · free after 16:00"
+        );
+        let dismissed = HashSet::from([OccurrenceRef::new("Planning", 0)]);
+        assert_eq!(
+            collection.today_summary(&FrozenClock(now), &dismissed, &config),
+            "3 meetings · next: Retro 15:00 · free after 16:00"
+        );
+        assert_eq!(
+            collection.today_summary(&FrozenClock(now + Duration::hours(7)), &dismissed, &config),
+            "3 meetings · free for the rest of the day"
+        );
+        assert_eq!(
+            EventCollection::default().today_summary(&FrozenClock(now), &dismissed, &config),
+            "No meetings today"
+        );
+    }
+
+    #[test]
+    fn test_due_notifications() {
+        let day = NaiveDate::from_ymd_opt(2025, 3, 7).unwrap();
+        let event = |title: &str, minute: u32| {
+            timed_event(
+                title,
+                at(day, 10, minute).unwrap(),
+                at(day, 10, minute + 30).unwrap(),
+            )
+        };
+        let one_on_one = event("1:1", 5);
+        let dismissed = HashSet::from([one_on_one.occurrence.clone()]);
+        let collection = EventCollection(vec![
+            event("Standup", 4),
+            event("Planning", 5),
+            one_on_one,
+            event("Retro", 6),
+        ]);
+        let now = at(day, 10, 0).unwrap();
+        let since = now - Duration::minutes(1);
+        let titles: Vec<_> = collection
+            .due_notifications(since, now, 5, &dismissed, &Config::default())
+            .iter()
+            .map(|e| e.title.as_str())
+            .collect();
+
+        // Standup was due at the previous refresh, Retro is due at the next
+        assert_eq!(titles, vec!["Planning"]);
+    }
+
+    #[test]
+    fn test_due_notifications_follow_countdown_rules() {
+        let day = NaiveDate::from_ymd_opt(2025, 3, 7).unwrap();
+        let start = at(day, 10, 5).unwrap();
+        let end = at(day, 10, 35).unwrap();
+        let planning = timed_event("Planning", start, end);
+        let declined = EventInfo {
+            is_accepted: false,
+            ..timed_event("Declined", start, end)
+        };
+        let free = EventInfo {
+            is_free: true,
+            ..timed_event("Free", start, end)
+        };
+        let solo = EventInfo {
+            other_attendees: 0,
+            ..timed_event("Solo", start, end)
+        };
+        let midnight = at(day, 0, 0).unwrap();
+        let holiday = EventInfo {
+            is_own: true,
+            ..timed_event(
+                "Public Holiday",
+                midnight,
+                midnight + Duration::seconds(86399),
+            )
+        };
+        let now = at(day, 10, 0).unwrap();
+        let since = now - Duration::minutes(1);
+        let mut config = Config::default();
+        config.countdown.exclude_free = true;
+        config.countdown.accepted_only = true;
+        let titles = |events: &EventCollection, config: &Config| -> Vec<String> {
+            events
+                .due_notifications(since, now, 5, &HashSet::new(), config)
+                .iter()
+                .map(|e| e.title.clone())
+                .collect()
+        };
+
+        let events = EventCollection(vec![planning.clone(), declined, free, solo]);
+        assert_eq!(titles(&events, &config), vec!["Planning"]);
+
+        let ooo_day = EventCollection(vec![holiday, planning]);
+        assert!(titles(&ooo_day, &config).is_empty());
+        config.countdown.keep_on_ooo_days = true;
+        assert_eq!(titles(&ooo_day, &config), vec!["Planning"]);
+    }
+
+    #[test]
+    fn test_due_overlap_warnings() {
+        let day = NaiveDate::from_ymd_opt(2025, 3, 7).unwrap();
+        let events = EventCollection(vec![
+            timed_event("Review", at(day, 10, 0).unwrap(), at(day, 11, 0).unwrap()),
+            timed_event("Sync", at(day, 10, 50).unwrap(), at(day, 11, 20).unwrap()),
+        ]);
+        let dismissed = HashSet::new();
+        let config = Config::default();
+        let warned = |since: u32, now: u32| -> Vec<(String, String)> {
+            events
+                .due_overlap_warnings(
+                    at(day, 10, since).unwrap(),
+                    at(day, 10, now).unwrap(),
+                    &dismissed,
+                    &config,
+                )
+                .iter()
+                .map(|(current, next)| (current.title.clone(), next.title.clone()))
+                .collect()
+        };
+
+        // Ten minutes before Sync starts, as the title warns
+        assert_eq!(
+            warned(39, 40),
+            vec![("Review".to_string(), "Sync".to_string())]
+        );
+        assert!(warned(40, 41).is_empty());
+    }
+
+    #[test]
+    fn test_agenda_speech() {
+        let day = NaiveDate::from_ymd_opt(2025, 3, 7).unwrap();
+        let event = |title: &str, start: (u32, u32), end: (u32, u32)| {
+            timed_event(
+                title,
+                at(day, start.0, start.1).unwrap(),
+                at(day, end.0, end.1).unwrap(),
+            )
+        };
+        let one_on_one = event("1:1", (12, 0), (12, 30));
+        let dismissed = HashSet::from([one_on_one.occurrence.clone()]);
+        let collection = EventCollection(vec![
+            event("Standup", (9, 0), (9, 15)),
+            event("Planning", (9, 45), (10, 30)),
+            one_on_one,
+            event("Retro", (15, 0), (16, 0)),
+        ]);
+        let config = Config::default();
+
+        assert_eq!(
+            collection.agenda_speech(&FrozenClock(at(day, 10, 0).unwrap()), &dismissed, &config),
+            "2 more meetings today. Now, Planning, until 10:30. At 15:00, Retro. \
+             Free after 16:00."
+        );
+        assert_eq!(
+            collection.agenda_speech(&FrozenClock(at(day, 17, 0).unwrap()), &dismissed, &config),
+            "No more meetings today."
+        );
+    }
+
+    #[test]
+    fn test_find_cur_or_next_skips_subscribed_calendars() {
+        let day = NaiveDate::from_ymd_opt(2025, 3, 7).unwrap();
+        let clock = FrozenClock(at(day, 10, 0).unwrap());
+        let mut event = EventInfo {
+            calendar_name: "Fixtures".to_string(),
+            is_subscribed: true,
+            ..timed_event(
+                "Match day",
+                at(day, 10, 30).unwrap(),
+                at(day, 12, 0).unwrap(),
+            )
+        };
+        let dismissed = HashSet::new();
+        let mut config = Config::default();
+
+        let collection = EventCollection(vec![event.clone()]);
+        assert!(collection
+            .find_cur_or_next(&clock, &dismissed, &config)
+            .is_none());
+        config.calendars.unmuted = vec!["Fixtures".to_string()];
+        assert!(collection
+            .find_cur_or_next(&clock, &dismissed, &config)
+            .is_some());
+
+        event.is_subscribed = false;
+        let collection = EventCollection(vec![event]);
+        config.calendars.muted = vec!["Fixtures".to_string()];
+        config.calendars.unmuted.clear();
+        assert!(collection
+            .find_cur_or_next(&clock, &dismissed, &config)
+            .is_none());
+    }
+
+    #[test]
+    fn test_find_cur_or_next_skips_long_events() {
+        let day = NaiveDate::from_ymd_opt(2025, 3, 7).unwrap();
+        let clock = FrozenClock(at(day, 10, 0).unwrap());
+        let collection = EventCollection(vec![
+            timed_event("On-call", at(day, 9, 0).unwrap(), at(day, 20, 0).unwrap()),
+            timed_event("Sync", at(day, 10, 1).unwrap(), at(day, 10, 31).unwrap()),
+        ]);
+        let mut config = Config::default();
+        config.countdown.max_duration = Some(240);
+        let dismissed = HashSet::new();
+
+        let title = |config: &Config| {
+            collection
+                .find_cur_or_next(&clock, &dismissed, config)
+                .map(|status| status.event().title.clone())
+        };
+        assert_eq!(title(&config), Some("Sync".to_string()));
+        config.countdown.pinned = vec!["on-call".to_string()];
+        assert_eq!(title(&config), Some("On-call".to_string()));
+    }
+
+    #[test]
+    fn test_get_title_on_own_ooo_day() {
+        let day = NaiveDate::from_ymd_opt(2025, 3, 7).unwrap();
+        let clock = FrozenClock(at(day, 10, 0).unwrap());
+        let midnight = at(day, 0, 0).unwrap();
+        let all_day_end = midnight + Duration::seconds(86399);
+        let all_day = |title: &str, is_own| EventInfo {
+            is_own,
+            ..timed_event(title, midnight, all_day_end)
+        };
+        let sync = timed_event("Sync", at(day, 10, 1).unwrap(), at(day, 10, 31).unwrap());
+        let dismissed = HashSet::new();
+        let mut config = Config::default();
+
+        let colleague = EventCollection(vec![all_day("Alice OOO", false), sync.clone()]);
+        assert_ne!(
+            colleague.get_title(&clock, &dismissed, None, &config),
+            "OOO today"
+        );
+
+        let mine = EventCollection(vec![all_day("Public Holiday", true), sync]);
+        assert_eq!(
+            mine.get_title(&clock, &dismissed, None, &config),
+            "OOO today"
+        );
+        config.countdown.keep_on_ooo_days = true;
+        assert!(mine.find_cur_or_next(&clock, &dismissed, &config).is_some());
+    }
+
+    #[test]
+    fn test_room_label() {
+        let mut event = EventInfo {
+            title: "Design review".to_string(),
+            start: Local::now(),
+            end: Local::now() + Duration::hours(1),
+            event_id: "id1".to_string(),
+            occurrence: OccurrenceRef::new("key1", 0),
+            has_recurrence: false,
+            location: Some("https://meet.google.com/abc-defg-hij".to_string()),
+            calendar_color: (0.5, 0.5, 0.5),
+            is_free: false,
+            is_accepted: true,
+            other_attendees: 3,
+            notes: None,
+            url: None,
+            is_own: false,
+            calendar_name: String::new(),
+            calendar_id: String::new(),
+            is_subscribed: false,
+            rooms: vec!["Room 4A".to_string(), "Annex".to_string()],
+            google_url: None,
+        };
+        assert_eq!(event.room_label().as_deref(), Some("Room 4A, Annex"));
+
+        event.location = Some("Room 4A; https://meet.google.com/abc".to_string());
+        assert_eq!(event.room_label().as_deref(), Some("Annex"));
+
+        event.rooms.clear();
+        assert_eq!(event.room_label(), None);
+    }
+
+    fn at(day: NaiveDate, hour: u32, minute: u32) -> Option<DateTime<Local>> {
+        day.and_hms_opt(hour, minute, 0)?
+            .and_local_timezone(Local)
+            .earliest()
+    }
+
+    fn timed_event(title: &str, start: DateTime<Local>, end: DateTime<Local>) -> EventInfo {
+        EventInfo {
+            title: title.to_string(),
+            start,
+            end,
+            event_id: title.to_string(),
+            occurrence: OccurrenceRef::new(title, start.timestamp()),
+            has_recurrence: false,
+            location: None,
+            calendar_color: (0.5, 0.5, 0.5),
+            is_free: false,
+            is_accepted: true,
+            other_attendees: 2,
+            notes: None,
+            url: None,
+            is_own: false,
+            calendar_name: String::new(),
+            calendar_id: String::new(),
+            is_subscribed: false,
+            rooms: Vec::new(),
+            google_url: None,
+        }
+    }
+
+    #[test]
+    fn test_title_around_midnight() {
+        let day = NaiveDate::from_ymd_opt(2025, 3, 7).unwrap();
+        let next_day = day.succ_opt().unwrap();
+        let early = at(next_day, 0, 5).unwrap();
+        let collection = EventCollection(vec![timed_event(
+            "Early sync",
+            early,
+            early + Duration::minutes(25),
+        )]);
+        let dismissed = HashSet::new();
+        let config = Config::default();
+
+        // Tomorrow's first event never drives tonight's title
+        let late = FrozenClock(at(day, 23, 50).unwrap());
+        assert!(collection
+            .find_cur_or_next(&late, &dismissed, &config)
+            .is_none());
+        assert_eq!(
+            collection.get_title(&late, &dismissed, None, &config),
+            "No more events today"
+        );
+
+        let midnight = FrozenClock(at(next_day, 0, 0).unwrap());
+        assert_eq!(
+            collection.get_title(&midnight, &dismissed, None, &config),
+            "Early sync • in 5m"
+        );
+        assert_eq!(
+            collection.today_summary(&midnight, &dismissed, &config),
+            "1 meeting · next: Early sync 00:05 · free after 00:30"
+        );
+    }
+
+    #[test]
+    fn test_event_spanning_midnight_stays_current() {
+        let day = NaiveDate::from_ymd_opt(2025, 3, 7).unwrap();
+        let start = at(day, 23, 30).unwrap();
+        let collection = EventCollection(vec![timed_event(
+            "Release",
+            start,
+            start + Duration::hours(1),
+        )]);
+        let dismissed = HashSet::new();
+        let config = Config::default();
+
+        let clock = FrozenClock(at(day, 23, 45).unwrap());
+        assert_eq!(
+            collection.get_title(&clock, &dismissed, None, &config),
+            "Release • 45m left"
+        );
+
+        // Still current once the date has changed
+        let clock = FrozenClock(at(day.succ_opt().unwrap(), 0, 10).unwrap());
+        assert_eq!(
+            collection.get_title(&clock, &dismissed, None, &config),
+            "Release • 20m left"
+        );
+    }
+
+    #[test]
+    fn test_countdown_across_dst_transition() {
+        // Only meaningful where the local zone observes DST
+        let today = Local::now().date_naive();
+        let offset_at_noon = |day: NaiveDate| at(day, 12, 0).map(|t| *t.offset());
+        let Some(day) = (1..=366)
+            .filter_map(|n| today.checked_add_days(chrono::Days::new(n)))
+            .find(|day| offset_at_noon(*day) != day.pred_opt().and_then(offset_at_noon))
+        else {
+            return;
+        };
+        let (Some(now), Some(start)) = (at(day, 0, 30), at(day, 6, 0)) else {
+            return;
+        };
+        let collection = EventCollection(vec![timed_event(
+            "Flight",
+            start,
+            start + Duration::hours(1),
+        )]);
+        let clock = FrozenClock(now);
+        let dismissed = HashSet::new();
+        let config = Config::default();
+
+        // The countdown is real elapsed time, not the difference in wall-clock
+        // hours, and the start time shown is the wall-clock one
+        let real = Duration::seconds(start.timestamp() - now.timestamp());
+        assert_eq!(
+            collection.get_title(&clock, &dismissed, None, &config),
+            format!("Flight • in {}", formatting::format_duration(real))
+        );
+        assert_eq!(
+            collection.get_time_only_title(&clock, &dismissed, None, &config),
+            "06:00"
+        );
     }
 }
@@ -0,0 +1,191 @@
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, NaiveTime};
+use serde::Deserialize;
+
+use super::events::{EventCollection, EventInfo};
+use super::occurrence::OccurrenceRef;
+
+/// A hand-written set of events to preview the app with instead of the
+/// calendar store:
+///
+/// ```toml
+/// [[event]]
+/// title = "Standup"
+/// start = "2025-03-07 09:00"
+/// end = "2025-03-07 09:15"
+/// location = "https://zoom.us/j/123"
+///
+/// [[event]]
+/// title = "Company holiday"
+/// start = "2025-03-10" # a date alone makes an all-day event
+/// ```
+#[derive(Debug, Deserialize)]
+struct Fixture {
+    #[serde(default, rename = "event")]
+    events: Vec<FixtureEvent>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct FixtureEvent {
+    title: String,
+    start: String,
+    /// Defaults to the end of the start day for all-day events, and an hour
+    /// after the start otherwise
+    end: Option<String>,
+    location: Option<String>,
+    #[serde(default)]
+    calendar: String,
+    #[serde(default)]
+    free: bool,
+    #[serde(default = "default_accepted")]
+    accepted: bool,
+    #[serde(default)]
+    attendees: usize,
+    #[serde(default)]
+    recurring: bool,
+    notes: Option<String>,
+    url: Option<String>,
+    #[serde(default)]
+    rooms: Vec<String>,
+}
+
+fn default_accepted() -> bool {
+    true
+}
+
+/// Parses "2025-03-07 08:55" as local time, the format `--at` takes too.
+pub fn parse_local_time(value: &str) -> Result<DateTime<Local>, String> {
+    let naive = NaiveDateTime::parse_from_str(value.trim(), "%Y-%m-%d %H:%M")
+        .map_err(|_| format!("expected a time like \"2025-03-07 08:55\", got `{}`", value))?;
+    to_local(naive)
+}
+
+fn to_local(naive: NaiveDateTime) -> Result<DateTime<Local>, String> {
+    naive
+        .and_local_timezone(Local)
+        .earliest()
+        .ok_or_else(|| format!("{} doesn't exist in the local time zone", naive))
+}
+
+fn parse_date(value: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d").ok()
+}
+
+impl FixtureEvent {
+    fn into_event_info(self) -> Result<EventInfo, String> {
+        let (start, end) = match parse_date(&self.start) {
+            Some(day) => {
+                let last_day = match &self.end {
+                    Some(end) => parse_date(end)
+                        .ok_or_else(|| format!("`{}`: all-day events end on a date", self.title))?,
+                    None => day,
+                };
+                // EventKit ends all-day events a second before midnight
+                let end_of_day = NaiveTime::from_hms_opt(23, 59, 59).unwrap_or(NaiveTime::MIN);
+                (
+                    to_local(day.and_time(NaiveTime::MIN))?,
+                    to_local(last_day.and_time(end_of_day))?,
+                )
+            }
+            None => {
+                let start = parse_local_time(&self.start)?;
+                let end = match &self.end {
+                    Some(end) => parse_local_time(end)?,
+                    None => start + chrono::Duration::hours(1),
+                };
+                (start, end)
+            }
+        };
+        if end < start {
+            return Err(format!("`{}` ends before it starts", self.title));
+        }
+
+        Ok(EventInfo {
+            event_id: self.title.clone(),
+            occurrence: OccurrenceRef::new(self.title.clone(), start.timestamp()),
+            title: self.title,
+            start,
+            end,
+            has_recurrence: self.recurring,
+            location: self.location,
+            calendar_color: (0.5, 0.5, 0.5),
+            is_free: self.free,
+            is_accepted: self.accepted,
+            other_attendees: self.attendees,
+            notes: self.notes,
+            url: self.url,
+            is_own: false,
+            calendar_name: self.calendar.clone(),
+            calendar_id: self.calendar,
+            is_subscribed: false,
+            rooms: self.rooms,
+            google_url: None,
+        })
+    }
+}
+
+impl EventCollection {
+    /// Events from a fixture file, in start order.
+    pub fn from_fixture(contents: &str) -> Result<Self, String> {
+        let fixture: Fixture = toml::from_str(contents).map_err(|e| e.to_string())?;
+        let mut events = fixture
+            .events
+            .into_iter()
+            .map(FixtureEvent::into_event_info)
+            .collect::<Result<Vec<_>, _>>()?;
+        events.sort_by_key(|e| e.start);
+        Ok(Self::from(events))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendar::is_all_day;
+
+    #[test]
+    fn test_from_fixture() {
+        let collection = EventCollection::from_fixture(
+            r#"
+            [[event]]
+            title = "Review"
+            start = "2025-03-07 10:00"
+            end = "2025-03-07 10:45"
+            calendar = "Work"
+            attendees = 4
+
+            [[event]]
+            title = "Standup"
+            start = "2025-03-07 09:00"
+            location = "https://zoom.us/j/123"
+
+            [[event]]
+            title = "Company holiday"
+            start = "2025-03-10"
+            "#,
+        )
+        .unwrap();
+        let events: Vec<_> = collection.iter().collect();
+
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[0].title, "Standup");
+        assert_eq!(events[0].end, parse_local_time("2025-03-07 10:00").unwrap());
+        assert_eq!(events[1].calendar_name, "Work");
+        assert_eq!(events[1].other_attendees, 4);
+        assert!(is_all_day(&events[2].start, &events[2].end));
+    }
+
+    #[test]
+    fn test_from_fixture_errors() {
+        assert!(EventCollection::from_fixture(
+            r#"
+            [[event]]
+            title = "Backwards"
+            start = "2025-03-07 10:00"
+            end = "2025-03-07 09:00"
+            "#
+        )
+        .is_err());
+        assert!(parse_local_time("tomorrow").is_err());
+    }
+}
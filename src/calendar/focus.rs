@@ -0,0 +1,150 @@
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, NaiveTime, Weekday};
+
+use crate::config::FocusConfig;
+
+use super::events::{round_up_to_quarter_hour, EventCollection};
+
+/// A gap between meetings proposed for heads-down work.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FocusBlock {
+    pub start: DateTime<Local>,
+    pub end: DateTime<Local>,
+}
+
+impl EventCollection {
+    /// Up to `per_day` blocks on each weekday from `now` through `days` days,
+    /// within the configured working hours. The longest gaps win, and a gap
+    /// longer than `max_minutes` gets a block at its start. Free and all-day
+    /// events don't count as busy, like for follow-ups.
+    pub fn focus_blocks(
+        &self,
+        now: DateTime<Local>,
+        days: u32,
+        config: &FocusConfig,
+    ) -> Vec<FocusBlock> {
+        let min_length = Duration::minutes(config.min_minutes);
+        let max_length = Duration::minutes(config.max_minutes.max(config.min_minutes));
+        let mut blocks = Vec::new();
+
+        for offset in 0..days as i64 {
+            let date = now.date_naive() + Duration::days(offset);
+            if matches!(date.weekday(), Weekday::Sat | Weekday::Sun) {
+                continue;
+            }
+            let (Some(day_start), Some(day_end)) = (
+                local_time(date, config.day_start),
+                local_time(date, config.day_end),
+            ) else {
+                continue;
+            };
+            let from = day_start.max(round_up_to_quarter_hour(now));
+            if from >= day_end {
+                continue;
+            }
+
+            let mut busy: Vec<_> = self
+                .iter()
+                .filter(|e| e.is_meeting() && e.start < day_end && e.end > from)
+                .map(|e| (e.start, e.end))
+                .collect();
+            busy.sort_by_key(|(start, _)| *start);
+
+            let mut gaps = Vec::new();
+            let mut cursor = from;
+            for (start, end) in busy {
+                if start > cursor {
+                    gaps.push((cursor, start));
+                }
+                cursor = cursor.max(round_up_to_quarter_hour(end));
+            }
+            if day_end > cursor {
+                gaps.push((cursor, day_end));
+            }
+
+            gaps.retain(|(start, end)| *end - *start >= min_length);
+            // Longest first; ties go to the earlier gap
+            gaps.sort_by_key(|(start, end)| (*start - *end, *start));
+            let mut day_blocks: Vec<FocusBlock> = gaps
+                .into_iter()
+                .take(config.per_day)
+                .map(|(start, end)| FocusBlock {
+                    start,
+                    end: end.min(start + max_length),
+                })
+                .collect();
+            day_blocks.sort_by_key(|block| block.start);
+            blocks.extend(day_blocks);
+        }
+        blocks
+    }
+}
+
+fn local_time(date: NaiveDate, time: NaiveTime) -> Option<DateTime<Local>> {
+    date.and_time(time).and_local_timezone(Local).single()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendar::parse_local_time;
+
+    fn events() -> EventCollection {
+        // Friday 7 March
+        EventCollection::from_fixture(
+            r#"
+            [[event]]
+            title = "Standup"
+            start = "2025-03-07 09:30"
+            end = "2025-03-07 09:45"
+
+            [[event]]
+            title = "Lunch"
+            start = "2025-03-07 12:00"
+            end = "2025-03-07 13:00"
+
+            [[event]]
+            title = "Review"
+            start = "2025-03-07 15:00"
+            end = "2025-03-07 16:10"
+            "#,
+        )
+        .unwrap()
+    }
+
+    fn block(start: &str, end: &str) -> FocusBlock {
+        FocusBlock {
+            start: parse_local_time(start).unwrap(),
+            end: parse_local_time(end).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_focus_blocks_take_longest_gaps() {
+        let now = parse_local_time("2025-03-07 08:00").unwrap();
+        assert_eq!(
+            events().focus_blocks(now, 1, &FocusConfig::default()),
+            // 09:45-12:00 and 13:00-15:00 beat 16:15-17:00 and 09:00-09:30
+            vec![
+                block("2025-03-07 09:45", "2025-03-07 11:45"),
+                block("2025-03-07 13:00", "2025-03-07 15:00"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_focus_blocks_skip_past_and_weekends() {
+        // Friday afternoon through Monday: the weekend gets nothing
+        let now = parse_local_time("2025-03-07 13:20").unwrap();
+        let config = FocusConfig {
+            per_day: 1,
+            ..Default::default()
+        };
+        assert_eq!(
+            events().focus_blocks(now, 4, &config),
+            vec![
+                block("2025-03-07 13:30", "2025-03-07 15:00"),
+                block("2025-03-10 09:00", "2025-03-10 11:00"),
+            ]
+        );
+    }
+}
@@ -1,7 +1,7 @@
 use chrono::{DateTime, Duration, Local, Timelike};
 use std::borrow::Cow;
+use std::collections::HashMap;
 
-const MAX_TITLE_LENGTH: usize = 50;
 const END_OF_DAY_SECS: u32 = 86399;
 
 pub fn format_time(dt: &DateTime<Local>) -> String {
@@ -13,11 +13,11 @@ pub fn is_all_day(start: &DateTime<Local>, end: &DateTime<Local>) -> bool {
         && end.time().num_seconds_from_midnight() == END_OF_DAY_SECS
 }
 
-pub fn format_event_title(title: &str, duration: Duration, template: &str) -> String {
+pub fn format_duration(duration: Duration) -> String {
     let secs = duration.num_seconds();
     let mins = duration.num_minutes();
-    
-    let time_str = if mins > 60 {
+
+    if mins > 60 {
         let hours = mins / 60;
         let remaining_mins = mins % 60;
         if remaining_mins >= 30 {
@@ -32,15 +32,96 @@ pub fn format_event_title(title: &str, duration: Duration, template: &str) -> St
         } else {
             format!("{}m", mins)
         }
-    };
+    }
+}
 
-    let overhead = template.len() - 4 + time_str.len();
-    let max_len = MAX_TITLE_LENGTH.saturating_sub(overhead);
-    let title = truncate_title(title, max_len);
+/// Exact hours and minutes, e.g. "2h 30m", for totals rather than countdowns.
+pub fn format_total_duration(duration: Duration) -> String {
+    let mins = duration.num_minutes();
+    match (mins / 60, mins % 60) {
+        (0, mins) => format!("{}m", mins),
+        (hours, 0) => format!("{}h", hours),
+        (hours, mins) => format!("{}h {}m", hours, mins),
+    }
+}
+
+/// Fills `template` with the title and `time_str`, e.g. "12m" or "09:30".
+/// `abbreviations` turns on smart shortening for titles that don't fit, which
+/// runs before truncation.
+pub fn format_event_title(
+    title: &str,
+    time_str: &str,
+    template: &str,
+    max_length: usize,
+    abbreviations: Option<&HashMap<String, String>>,
+) -> String {
+    let overhead = template.chars().count() - 4 + time_str.len();
+    let max_len = max_length.saturating_sub(overhead);
+    let shortened = abbreviations
+        .filter(|_| title.chars().count() > max_len)
+        .map(|abbreviations| shorten_title(title, abbreviations));
+    let title = truncate_title(shortened.as_deref().unwrap_or(title), max_len);
 
     template
         .replacen("{}", &title, 1)
-        .replacen("{}", &time_str, 1)
+        .replacen("{}", time_str, 1)
+}
+
+const ABBREVIATIONS: [(&str, &str); 5] = [
+    ("engineering", "Eng"),
+    ("meeting", "Mtg"),
+    ("management", "Mgmt"),
+    ("development", "Dev"),
+    ("department", "Dept"),
+];
+
+/// Drops leading "[EXT]"/"(Tentative)" tags and "Weekly"/"Biweekly", and
+/// abbreviates long words. `abbreviations` (lowercase word → replacement)
+/// extend and override the built-in ones.
+pub fn shorten_title(title: &str, abbreviations: &HashMap<String, String>) -> String {
+    let mut rest = title.trim();
+    while let Some(after_tag) = strip_leading_tag(rest) {
+        rest = after_tag.trim_start();
+    }
+
+    let words: Vec<String> = rest
+        .split_whitespace()
+        .filter(|word| {
+            !matches!(
+                word.to_lowercase().as_str(),
+                "weekly" | "biweekly" | "bi-weekly"
+            )
+        })
+        .map(|word| {
+            let core = word.trim_end_matches(|c: char| !c.is_alphanumeric());
+            let lower = core.to_lowercase();
+            let abbreviation = abbreviations.get(&lower).map(String::as_str).or_else(|| {
+                ABBREVIATIONS
+                    .iter()
+                    .find(|(long, _)| *long == lower)
+                    .map(|(_, short)| *short)
+            });
+            match abbreviation {
+                Some(short) => format!("{}{}", short, &word[core.len()..]),
+                None => word.to_string(),
+            }
+        })
+        .collect();
+
+    if words.is_empty() {
+        title.to_string()
+    } else {
+        words.join(" ")
+    }
+}
+
+fn strip_leading_tag(title: &str) -> Option<&str> {
+    let close = match title.chars().next()? {
+        '[' => ']',
+        '(' => ')',
+        _ => return None,
+    };
+    title.find(close).map(|end| &title[end + 1..])
 }
 
 pub fn truncate_title(title: &str, max_len: usize) -> Cow<'_, str> {
@@ -52,3 +133,45 @@ pub fn truncate_title(title: &str, max_len: usize) -> Cow<'_, str> {
         Cow::Owned(truncated)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shorten_title() {
+        let none = HashMap::new();
+        assert_eq!(
+            shorten_title("[EXT] (Tentative) Weekly Engineering Sync", &none),
+            "Eng Sync"
+        );
+        assert_eq!(
+            shorten_title("Biweekly Management meeting: Q3", &none),
+            "Mgmt Mtg: Q3"
+        );
+        assert_eq!(shorten_title("Weekly", &none), "Weekly");
+
+        let custom = HashMap::from([
+            ("infrastructure".to_string(), "Infra".to_string()),
+            ("meeting".to_string(), "Meet".to_string()),
+        ]);
+        assert_eq!(
+            shorten_title("Infrastructure meeting", &custom),
+            "Infra Meet"
+        );
+    }
+
+    #[test]
+    fn test_format_event_title_shortens_before_truncating() {
+        let title = "[EXT] Weekly Engineering Planning";
+        let none = HashMap::new();
+        assert_eq!(
+            format_event_title(title, "5m", "{} • in {}", 30, Some(&none)),
+            "Eng Planning • in 5m"
+        );
+        assert_eq!(
+            format_event_title(title, "5m", "{} • in {}", 30, None),
+            "[EXT] Weekly Engineer… • in 5m"
+        );
+    }
+}
@@ -0,0 +1,93 @@
+use chrono::{DateTime, Local, Utc};
+
+const GOOGLE_UID_SUFFIX: &str = "@google.com";
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Google Calendar web link for an event synced from a Google account, or
+/// `None` when the iCalendar UID isn't one of Google's.
+///
+/// Google's `eid` is the base64 of "<event id> <calendar email>", where
+/// instances of a recurring event add their start to the id. `all_day`
+/// instances use the date alone.
+pub fn google_calendar_url(
+    external_id: &str,
+    calendar_email: &str,
+    instance_start: Option<DateTime<Local>>,
+    all_day: bool,
+) -> Option<String> {
+    let event_id = external_id.strip_suffix(GOOGLE_UID_SUFFIX)?;
+    let event_id = match instance_start {
+        Some(start) if all_day => format!("{}_{}", event_id, start.format("%Y%m%d")),
+        Some(start) => format!(
+            "{}_{}",
+            event_id,
+            start.with_timezone(&Utc).format("%Y%m%dT%H%M%SZ")
+        ),
+        None => event_id.to_string(),
+    };
+    let eid = base64(format!("{} {}", event_id, calendar_email).as_bytes());
+    Some(format!(
+        "https://calendar.google.com/calendar/event?eid={}",
+        eid.trim_end_matches('=')
+    ))
+}
+
+fn base64(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, byte)| n | (*byte as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(BASE64_ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_base64() {
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"f"), "Zg==");
+        assert_eq!(base64(b"fo"), "Zm8=");
+        assert_eq!(base64(b"foo"), "Zm9v");
+        assert_eq!(base64(b"foobar"), "Zm9vYmFy");
+    }
+
+    #[test]
+    fn test_google_calendar_url() {
+        assert_eq!(
+            google_calendar_url("abc123@google.com", "me@example.com", None, false).as_deref(),
+            Some("https://calendar.google.com/calendar/event?eid=YWJjMTIzIG1lQGV4YW1wbGUuY29t")
+        );
+
+        let start = Utc
+            .with_ymd_and_hms(2025, 3, 7, 9, 0, 0)
+            .unwrap()
+            .with_timezone(&Local);
+        let url =
+            google_calendar_url("abc123@google.com", "me@example.com", Some(start), false).unwrap();
+        assert!(url.ends_with(&base64(b"abc123_20250307T090000Z me@example.com")));
+
+        assert_eq!(
+            google_calendar_url(
+                "040000008200E00074C5B7101A82E008",
+                "me@example.com",
+                None,
+                false
+            ),
+            None
+        );
+    }
+}
@@ -0,0 +1,51 @@
+use super::formatting::truncate_title;
+
+const MAX_LINK_LABEL_LENGTH: usize = 48;
+
+/// Every http(s) URL in `text`, in order of appearance.
+pub fn find_urls(text: &str) -> impl Iterator<Item = &str> {
+    text.split_whitespace().filter_map(|word| {
+        let start = word.find("https://").or_else(|| word.find("http://"))?;
+        let url = word[start..].trim_end_matches(['>', ')', ']', '"', '\'', ',', '.', ';']);
+        Some(url)
+    })
+}
+
+/// Short menu label for a URL: no scheme, no trailing slash, truncated.
+pub fn link_label(url: &str) -> String {
+    let label = url
+        .trim_start_matches("https://")
+        .trim_start_matches("http://")
+        .trim_start_matches("www.")
+        .trim_end_matches('/');
+    truncate_title(label, MAX_LINK_LABEL_LENGTH).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_urls() {
+        let notes = "Agenda: <https://docs.google.com/document/d/abc>\n\
+                     Board (https://miro.com/app/board/xyz=/), see you there.";
+        let urls: Vec<_> = find_urls(notes).collect();
+
+        assert_eq!(
+            urls,
+            vec![
+                "https://docs.google.com/document/d/abc",
+                "https://miro.com/app/board/xyz=/",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_link_label() {
+        assert_eq!(link_label("https://www.example.com/"), "example.com");
+        assert_eq!(
+            link_label("https://docs.google.com/document/d/1a2b3c4d5e6f7g8h9i0j/edit"),
+            "docs.google.com/document/d/1a2b3c4d5e6f7g8h9i0j…"
+        );
+    }
+}
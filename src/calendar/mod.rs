@@ -1,14 +1,28 @@
+mod boilerplate;
+mod dial_in;
 mod error;
 mod events;
+mod fixture;
+mod focus;
 mod formatting;
+mod google;
+mod links;
+mod occurrence;
 mod service;
+mod summary;
 
 use objc2_event_kit::EKEventStore;
 
+pub use dial_in::PhoneJoin;
 pub use error::CalendarError;
-pub use events::{EventCollection, EventInfo, EventStatus};
-pub use formatting::{format_time, is_all_day};
-pub use service::{extract_url, Icon, ServiceInfo, SlackHuddleUrl};
+pub use events::{listed_in_day, EventCollection, EventInfo, EventStatus};
+pub use fixture::parse_local_time;
+pub use focus::FocusBlock;
+pub use formatting::{format_duration, format_time, format_total_duration, is_all_day};
+pub use links::link_label;
+pub use occurrence::OccurrenceRef;
+pub use service::{Icon, MeetingMedium, ServiceInfo};
+pub use summary::{fetch_month, MonthSummary};
 
 pub fn request_access(store: &EKEventStore) -> Result<(), CalendarError> {
     use super::ffi::event_kit;
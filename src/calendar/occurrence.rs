@@ -0,0 +1,104 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+
+use crate::json::{self, Value};
+
+/// Identifies one occurrence of an event: recurring events share an ID, so
+/// the start tells their occurrences apart.
+///
+/// Menu items and saved notes carry it as a token, a small JSON object, so an
+/// ID can hold any characters and fields can be added without breaking tokens
+/// written before.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct OccurrenceRef {
+    pub event_id: String,
+    /// Unix timestamp of the occurrence's start
+    pub start: i64,
+}
+
+/// Separated the ID and start in keys from before tokens were JSON.
+const LEGACY_SEPARATOR: &str = "|||";
+
+impl OccurrenceRef {
+    pub fn new(event_id: impl Into<String>, start: i64) -> Self {
+        Self {
+            event_id: event_id.into(),
+            start,
+        }
+    }
+
+    /// `{"event_id":"…","start":1741338000}`, which never contains a newline.
+    pub fn to_token(&self) -> String {
+        format!(
+            "{{\"event_id\":{},\"start\":{}}}",
+            json::string(&self.event_id),
+            self.start
+        )
+    }
+
+    /// Reads a token from `to_token`, or an `<id>|||<start>` key from an
+    /// older notes file.
+    pub fn from_token(token: &str) -> Option<Self> {
+        if !token.starts_with('{') {
+            let (event_id, start) = token.rsplit_once(LEGACY_SEPARATOR)?;
+            return Some(Self::new(event_id, start.parse().ok()?));
+        }
+
+        let mut object = json::parse_flat_object(token).ok()?;
+        let Some(Value::String(event_id)) = object.remove("event_id") else {
+            return None;
+        };
+        let Some(Value::Number(start)) = object.remove("start") else {
+            return None;
+        };
+        Some(Self::new(event_id, start as i64))
+    }
+}
+
+impl fmt::Display for OccurrenceRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_token())
+    }
+}
+
+impl Serialize for OccurrenceRef {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_token())
+    }
+}
+
+impl<'de> Deserialize<'de> for OccurrenceRef {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let token = String::deserialize(deserializer)?;
+        Self::from_token(&token)
+            .ok_or_else(|| serde::de::Error::custom(format!("invalid occurrence `{}`", token)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_round_trip() {
+        let occurrence = OccurrenceRef::new("AB12:CD34|||x\"y\n", 1741338000);
+        let token = occurrence.to_token();
+        assert!(!token.contains('\n'));
+        assert_eq!(OccurrenceRef::from_token(&token), Some(occurrence));
+    }
+
+    #[test]
+    fn test_from_legacy_key() {
+        assert_eq!(
+            OccurrenceRef::from_token("AB12:CD34|||1741338000"),
+            Some(OccurrenceRef::new("AB12:CD34", 1741338000))
+        );
+    }
+
+    #[test]
+    fn test_from_invalid_token() {
+        assert_eq!(OccurrenceRef::from_token("standup"), None);
+        assert_eq!(OccurrenceRef::from_token("{\"event_id\":\"a\"}"), None);
+        assert_eq!(OccurrenceRef::from_token("{\"start\":1}"), None);
+    }
+}
@@ -1,6 +1,7 @@
+use serde::Deserialize;
 use std::borrow::Cow;
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub enum Icon {
     Calendar,
     CircleX,
@@ -10,12 +11,16 @@ pub enum Icon {
     Video,
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "kebab-case")]
 pub enum ServiceInfo {
     Slack,
     Zoom,
+    #[serde(rename = "meet")]
     GoogleMeet,
+    #[serde(rename = "teams")]
     MicrosoftTeams,
+    #[serde(skip_deserializing)]
     Generic,
 }
 
@@ -44,6 +49,34 @@ impl ServiceInfo {
         }
     }
 
+    /// Link that opens `url` in the service's desktop app, when it has one.
+    pub fn native_url(&self, url: &str) -> Option<String> {
+        match self {
+            Self::Slack => SlackHuddleUrl::parse(url).map(|huddle| huddle.to_native_url()),
+            Self::Zoom => {
+                let (base, rest) = url.split_once("/j/")?;
+                let host = base.split_once("://").map_or(base, |(_, host)| host);
+                let meeting_id: String = rest.chars().take_while(char::is_ascii_digit).collect();
+                if meeting_id.is_empty() {
+                    return None;
+                }
+                let pwd = rest
+                    .split_once("pwd=")
+                    .map(|(_, pwd)| pwd.split(['&', '#']).next().unwrap_or(pwd))
+                    .map(|pwd| format!("&pwd={}", pwd))
+                    .unwrap_or_default();
+                Some(format!(
+                    "zoommtg://{}/join?action=join&confno={}{}",
+                    host, meeting_id, pwd
+                ))
+            }
+            Self::MicrosoftTeams => url
+                .strip_prefix("https://")
+                .map(|rest| format!("msteams://{}", rest)),
+            Self::GoogleMeet | Self::Generic => None,
+        }
+    }
+
     pub fn icon(&self) -> Icon {
         match self {
             Self::Slack => Icon::Slack,
@@ -55,6 +88,34 @@ impl ServiceInfo {
     }
 }
 
+/// How an event is attended, as far as its location tells us.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum MeetingMedium {
+    Video(ServiceInfo),
+    InPerson,
+    Unspecified,
+}
+
+impl MeetingMedium {
+    pub fn from_location(location: Option<&str>) -> Self {
+        match location.map(str::trim).filter(|loc| !loc.is_empty()) {
+            Some(loc) => match extract_url(Some(loc)) {
+                Some(url) => Self::Video(ServiceInfo::from_url(url)),
+                None => Self::InPerson,
+            },
+            None => Self::Unspecified,
+        }
+    }
+
+    pub fn glyph(&self) -> Option<&'static str> {
+        match self {
+            Self::Video(_) => Some("📹"),
+            Self::InPerson => Some("📍"),
+            Self::Unspecified => None,
+        }
+    }
+}
+
 pub fn extract_url(location: Option<&str>) -> Option<&str> {
     location.filter(|loc| loc.starts_with("http://") || loc.starts_with("https://"))
 }
@@ -140,6 +201,24 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_service_native_url() {
+        assert_eq!(
+            ServiceInfo::Zoom.native_url("https://acme.zoom.us/j/84512345678?pwd=aBc.1&from=addon"),
+            Some(
+                "zoommtg://acme.zoom.us/join?action=join&confno=84512345678&pwd=aBc.1".to_string()
+            )
+        );
+        assert_eq!(
+            ServiceInfo::MicrosoftTeams.native_url("https://teams.microsoft.com/l/meetup-join/x"),
+            Some("msteams://teams.microsoft.com/l/meetup-join/x".to_string())
+        );
+        assert_eq!(
+            ServiceInfo::GoogleMeet.native_url("https://meet.google.com/abc-defg-hij"),
+            None
+        );
+    }
+
     #[test]
     fn test_service_name() {
         assert_eq!(ServiceInfo::Slack.name(), "Slack");
@@ -154,6 +233,32 @@ mod tests {
         assert_eq!(ServiceInfo::Generic.icon(), Icon::Video);
     }
 
+    #[test]
+    fn test_meeting_medium_from_location() {
+        assert_eq!(
+            MeetingMedium::from_location(Some("https://zoom.us/j/123")),
+            MeetingMedium::Video(ServiceInfo::Zoom)
+        );
+        assert_eq!(
+            MeetingMedium::from_location(Some("Room 4A")),
+            MeetingMedium::InPerson
+        );
+        assert_eq!(
+            MeetingMedium::from_location(Some("  ")),
+            MeetingMedium::Unspecified
+        );
+        assert_eq!(
+            MeetingMedium::from_location(None),
+            MeetingMedium::Unspecified
+        );
+    }
+
+    #[test]
+    fn test_meeting_medium_glyph() {
+        assert_eq!(MeetingMedium::InPerson.glyph(), Some("📍"));
+        assert_eq!(MeetingMedium::Unspecified.glyph(), None);
+    }
+
     #[test]
     fn test_extract_url_https() {
         assert_eq!(
@@ -0,0 +1,288 @@
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate};
+use objc2_event_kit::EKEventStore;
+use objc2_foundation::NSDate;
+use std::collections::HashMap;
+
+use super::error::CalendarError;
+use super::formatting::is_all_day;
+use crate::config::CalendarsConfig;
+use crate::ffi::event_kit;
+use crate::json;
+
+/// The parts of an event the month summary aggregates.
+pub struct SummaryEvent {
+    pub start: DateTime<Local>,
+    pub end: DateTime<Local>,
+    pub calendar: String,
+    pub has_recurrence: bool,
+    pub is_free: bool,
+}
+
+/// Meeting load over one calendar month. All-day and "Free" events don't count
+/// as meetings.
+#[derive(Debug, PartialEq)]
+pub struct MonthSummary {
+    /// First day of the month
+    pub month: NaiveDate,
+    pub meetings: usize,
+    pub meeting_time: Duration,
+    /// Meeting counts per calendar, most meetings first
+    pub by_calendar: Vec<(String, usize)>,
+    pub busiest_day: Option<(NaiveDate, Duration)>,
+    pub recurring_meetings: usize,
+    pub recurring_time: Duration,
+}
+
+impl MonthSummary {
+    pub fn from_events(month: NaiveDate, events: &[SummaryEvent]) -> Self {
+        let mut summary = Self {
+            month,
+            meetings: 0,
+            meeting_time: Duration::zero(),
+            by_calendar: Vec::new(),
+            busiest_day: None,
+            recurring_meetings: 0,
+            recurring_time: Duration::zero(),
+        };
+        let mut by_calendar: HashMap<&str, usize> = HashMap::new();
+        let mut by_day: HashMap<NaiveDate, Duration> = HashMap::new();
+
+        for event in events.iter().filter(|e| {
+            !e.is_free
+                && !is_all_day(&e.start, &e.end)
+                && e.start.year() == month.year()
+                && e.start.month() == month.month()
+        }) {
+            let length = event.end.signed_duration_since(event.start);
+            summary.meetings += 1;
+            summary.meeting_time += length;
+            *by_calendar.entry(&event.calendar).or_default() += 1;
+            *by_day
+                .entry(event.start.date_naive())
+                .or_insert(Duration::zero()) += length;
+            if event.has_recurrence {
+                summary.recurring_meetings += 1;
+                summary.recurring_time += length;
+            }
+        }
+
+        summary.by_calendar = by_calendar
+            .into_iter()
+            .map(|(calendar, count)| (calendar.to_string(), count))
+            .collect();
+        summary
+            .by_calendar
+            .sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        // Ties go to the earlier day
+        summary.busiest_day = by_day
+            .into_iter()
+            .max_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.cmp(&a.0)));
+        summary
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut lines = vec![
+            self.month.format("%B %Y").to_string(),
+            format!(
+                "Meetings: {} ({})",
+                self.meetings,
+                format_hours(self.meeting_time)
+            ),
+        ];
+        if let Some((day, time)) = self.busiest_day {
+            lines.push(format!(
+                "Busiest day: {} ({})",
+                day.format("%a %d %b"),
+                format_hours(time)
+            ));
+        }
+        lines.push(format!(
+            "Recurring: {} meetings ({}, {}% of meeting time)",
+            self.recurring_meetings,
+            format_hours(self.recurring_time),
+            self.recurring_share()
+        ));
+        if !self.by_calendar.is_empty() {
+            lines.push("By calendar:".to_string());
+            for (calendar, count) in &self.by_calendar {
+                lines.push(format!("  {}: {}", calendar, count));
+            }
+        }
+        lines.join("\n")
+    }
+
+    pub fn to_json(&self) -> String {
+        let by_calendar = self
+            .by_calendar
+            .iter()
+            .map(|(calendar, count)| format!("{}:{}", json::string(calendar), count))
+            .collect::<Vec<_>>()
+            .join(",");
+        let busiest_day = self
+            .busiest_day
+            .map(|(day, time)| {
+                format!(
+                    "{{\"date\":\"{}\",\"meeting_hours\":{}}}",
+                    day.format("%Y-%m-%d"),
+                    hours(time)
+                )
+            })
+            .unwrap_or_else(|| "null".to_string());
+
+        format!(
+            "{{\"month\":\"{}\",\"meetings\":{},\"meeting_hours\":{},\"by_calendar\":{{{}}},\
+             \"busiest_day\":{},\"recurring_meetings\":{},\"recurring_hours\":{}}}",
+            self.month.format("%Y-%m"),
+            self.meetings,
+            hours(self.meeting_time),
+            by_calendar,
+            busiest_day,
+            self.recurring_meetings,
+            hours(self.recurring_time)
+        )
+    }
+
+    fn recurring_share(&self) -> i64 {
+        let total = self.meeting_time.num_minutes();
+        if total == 0 {
+            0
+        } else {
+            self.recurring_time.num_minutes() * 100 / total
+        }
+    }
+}
+
+/// Fetches every event in the month containing `day`, a wider range than the
+/// menu ever loads, from the calendars `calendars` shows.
+pub fn fetch_month(
+    store: &EKEventStore,
+    day: NaiveDate,
+    calendars: &CalendarsConfig,
+) -> Result<(NaiveDate, Vec<SummaryEvent>), CalendarError> {
+    let month = day.with_day(1).unwrap_or(day);
+    let next_month = month
+        .checked_add_months(chrono::Months::new(1))
+        .unwrap_or(month);
+    let to_date = |date: NaiveDate| {
+        let timestamp = date
+            .and_hms_opt(0, 0, 0)
+            .and_then(|dt| dt.and_local_timezone(Local).earliest())
+            .map(|dt| dt.timestamp())
+            .unwrap_or_default();
+        NSDate::dateWithTimeIntervalSince1970(timestamp as f64)
+    };
+
+    let events =
+        event_kit::fetch_events(store, &to_date(month), &to_date(next_month), |id, name| {
+            calendars.is_shown(id, name)
+        })
+        .ok_or(CalendarError::StoreUnavailable)?
+        .iter()
+        .map(|event| {
+            let (start, end, _, _, _, has_recurrence) = event_kit::get_event_properties(event);
+            SummaryEvent {
+                start: to_local(start.timeIntervalSince1970()),
+                end: to_local(end.timeIntervalSince1970()),
+                calendar: event_kit::get_event_calendar(event)
+                    .map(|calendar| event_kit::get_calendar_title(&calendar))
+                    .unwrap_or_default(),
+                has_recurrence,
+                is_free: event_kit::is_event_free(event),
+            }
+        })
+        .collect();
+    Ok((month, events))
+}
+
+fn to_local(ts: f64) -> DateTime<Local> {
+    DateTime::from_timestamp(ts as i64, 0)
+        .unwrap_or(DateTime::UNIX_EPOCH)
+        .with_timezone(&Local)
+}
+
+/// Hours to one decimal place.
+fn hours(duration: Duration) -> f64 {
+    (duration.num_minutes() as f64 / 6.0).round() / 10.0
+}
+
+fn format_hours(duration: Duration) -> String {
+    format!("{}h", hours(duration))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(day: u32, hour: u32, minutes: i64, calendar: &str, recurring: bool) -> SummaryEvent {
+        let start = NaiveDate::from_ymd_opt(2025, 3, day)
+            .and_then(|d| d.and_hms_opt(hour, 0, 0))
+            .and_then(|dt| dt.and_local_timezone(Local).single())
+            .unwrap();
+        SummaryEvent {
+            start,
+            end: start + Duration::minutes(minutes),
+            calendar: calendar.to_string(),
+            has_recurrence: recurring,
+            is_free: false,
+        }
+    }
+
+    #[test]
+    fn test_month_summary() {
+        let month = NaiveDate::from_ymd_opt(2025, 3, 1).unwrap();
+        let mut focus = event(4, 13, 120, "Work", false);
+        focus.is_free = true;
+        let events = vec![
+            event(3, 9, 30, "Work", true),
+            event(4, 10, 60, "Work", false),
+            event(4, 15, 90, "Personal", false),
+            event(5, 9, 30, "Work", true),
+            focus,
+        ];
+
+        let summary = MonthSummary::from_events(month, &events);
+        assert_eq!(summary.meetings, 4);
+        assert_eq!(summary.meeting_time, Duration::minutes(210));
+        assert_eq!(
+            summary.by_calendar,
+            vec![("Work".to_string(), 3), ("Personal".to_string(), 1)]
+        );
+        assert_eq!(
+            summary.busiest_day,
+            Some((
+                NaiveDate::from_ymd_opt(2025, 3, 4).unwrap(),
+                Duration::minutes(150)
+            ))
+        );
+        assert_eq!(summary.recurring_meetings, 2);
+        assert_eq!(summary.recurring_time, Duration::hours(1));
+    }
+
+    #[test]
+    fn test_month_summary_output() {
+        let month = NaiveDate::from_ymd_opt(2025, 3, 1).unwrap();
+        let summary = MonthSummary::from_events(
+            month,
+            &[
+                event(3, 9, 90, "Work \"A\"", true),
+                event(4, 9, 30, "Work \"A\"", false),
+            ],
+        );
+
+        assert_eq!(
+            summary.to_text(),
+            "March 2025\n\
+             Meetings: 2 (2h)\n\
+             Busiest day: Mon 03 Mar (1.5h)\n\
+             Recurring: 1 meetings (1.5h, 75% of meeting time)\n\
+             By calendar:\n  Work \"A\": 2"
+        );
+        assert_eq!(
+            summary.to_json(),
+            "{\"month\":\"2025-03\",\"meetings\":2,\"meeting_hours\":2,\
+             \"by_calendar\":{\"Work \\\"A\\\"\":2},\
+             \"busiest_day\":{\"date\":\"2025-03-03\",\"meeting_hours\":1.5},\
+             \"recurring_meetings\":1,\"recurring_hours\":1.5}"
+        );
+    }
+}
@@ -0,0 +1,27 @@
+use chrono::{DateTime, Local};
+
+/// Source of the current time for everything that depends on it, so that
+/// day boundaries and DST transitions can be exercised at a fixed instant.
+pub trait Clock {
+    fn now(&self) -> DateTime<Local>;
+}
+
+/// The wall clock.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Local> {
+        Local::now()
+    }
+}
+
+/// Always reports the same instant.
+#[derive(Debug, Clone, Copy)]
+pub struct FrozenClock(pub DateTime<Local>);
+
+impl Clock for FrozenClock {
+    fn now(&self) -> DateTime<Local> {
+        self.0
+    }
+}
@@ -0,0 +1,991 @@
+use chrono::format::{Item, StrftimeItems};
+use chrono::{DateTime, Datelike, Local, NaiveDate, NaiveTime, Weekday};
+use serde::{Deserialize, Deserializer};
+use std::{collections::HashMap, fs, io::ErrorKind, path::PathBuf};
+
+use crate::calendar::ServiceInfo;
+
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct Config {
+    pub quiet_hours: Option<QuietHours>,
+    pub out_of_office: Option<OutOfOffice>,
+    /// Bundle identifier of the browser for web links, instead of the system default.
+    pub browser: Option<String>,
+    /// Chrome/Edge profile directory for meeting links, e.g. "Profile 1".
+    pub browser_profile: Option<String>,
+    /// Open events synced from Google in Google Calendar on the web rather
+    /// than Calendar.app, whose links to them are unreliable.
+    pub google_calendar_web: bool,
+    pub title: TitleConfig,
+    pub countdown: CountdownConfig,
+    pub menu: MenuConfig,
+    pub click: ClickConfig,
+    pub join: JoinConfig,
+    pub pause: PauseConfig,
+    pub sync: SyncConfig,
+    pub banners: BannerConfig,
+    pub calendars: CalendarsConfig,
+    pub text: TextConfig,
+    pub focus: FocusConfig,
+    pub prep_links: Vec<PrepLink>,
+    pub notifications: NotificationsConfig,
+}
+
+impl Config {
+    /// `config.toml` for the default instance, `<instance>.toml` for named ones.
+    pub fn path(instance: Option<&str>) -> Option<PathBuf> {
+        let home = std::env::var("HOME").ok()?;
+        Some(PathBuf::from(format!(
+            "{}/.config/eventually/{}.toml",
+            home,
+            instance.unwrap_or("config")
+        )))
+    }
+
+    /// Loads the config file, falling back to defaults when it is missing or invalid.
+    pub fn load(instance: Option<&str>) -> Self {
+        let Some(path) = Self::path(instance) else {
+            return Self::default();
+        };
+
+        match fs::read_to_string(&path) {
+            Ok(contents) => Self::parse(&contents).unwrap_or_else(|e| {
+                eprintln!("Error: Failed to parse `{}`: {}", path.display(), e);
+                Self::default()
+            }),
+            Err(e) if e.kind() == ErrorKind::NotFound => Self::default(),
+            Err(e) => {
+                eprintln!("Error: Failed to read `{}`: {}", path.display(), e);
+                Self::default()
+            }
+        }
+    }
+
+    pub fn parse(contents: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(contents)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct TitleConfig {
+    pub mode: TitleMode,
+    /// Maximum status title length in characters, including the countdown.
+    pub max_length: usize,
+    /// Show only an icon when macOS hides the status item for lack of space.
+    pub icon_only_when_hidden: bool,
+    /// Prefix the title with a glyph for the meeting medium (video call or in person).
+    pub service_glyph: bool,
+    /// Show a dot in the active event's calendar color before the title.
+    pub calendar_color: bool,
+    /// strftime-style template shown when no events remain today, e.g. "%a %d %b · W%V".
+    #[serde(deserialize_with = "deserialize_strftime")]
+    pub idle_format: Option<String>,
+    /// Before truncating a long title, drop "[EXT]"-style tags and
+    /// "Weekly"/"Biweekly", and abbreviate words like "Engineering".
+    pub smart_shorten: bool,
+    /// Extra word abbreviations for smart shortening, e.g. Infrastructure = "Infra".
+    #[serde(deserialize_with = "deserialize_abbreviations")]
+    pub abbreviations: HashMap<String, String>,
+    /// While a call is on but not yet joined from eventually, show how long
+    /// ago it started instead of the time left.
+    pub started_ago: bool,
+    /// What to count for an event in progress.
+    pub current: CurrentCountdown,
+    /// What to count for the next event.
+    pub upcoming: UpcomingCountdown,
+    /// Minutes ahead to warn, during a meeting, that the next one starts
+    /// before it ends. 0 turns the warning off.
+    pub overlap_warning: u32,
+    /// Minutes before the current event ends to nudge about wrapping up.
+    pub wrap_up: Option<u32>,
+}
+
+impl Default for TitleConfig {
+    fn default() -> Self {
+        Self {
+            mode: TitleMode::default(),
+            max_length: 50,
+            icon_only_when_hidden: false,
+            service_glyph: false,
+            calendar_color: false,
+            idle_format: None,
+            smart_shorten: false,
+            abbreviations: HashMap::new(),
+            started_ago: false,
+            current: CurrentCountdown::default(),
+            upcoming: UpcomingCountdown::default(),
+            overlap_warning: 10,
+            wrap_up: None,
+        }
+    }
+}
+
+/// Which events may become the current/next event shown in the status title.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct CountdownConfig {
+    /// Ignore events marked "Free", such as focus blocks.
+    pub exclude_free: bool,
+    /// Ignore invitations that haven't been accepted yet.
+    pub accepted_only: bool,
+    /// Ignore events longer than this many minutes, such as conferences or
+    /// on-call shifts.
+    pub max_duration: Option<i64>,
+    /// Title substrings (case-insensitive) of events that drive the countdown
+    /// whatever their length.
+    pub pinned: Vec<String>,
+    /// Keep counting down to timed events on days you're out of office.
+    pub keep_on_ooo_days: bool,
+}
+
+impl CountdownConfig {
+    pub fn is_pinned(&self, title: &str) -> bool {
+        let title = title.to_lowercase();
+        self.pinned
+            .iter()
+            .any(|pattern| title.contains(&pattern.to_lowercase()))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct MenuConfig {
+    /// How many days, starting today, to list. Past a week they roll up into
+    /// "Next week" and "Week of …" groups.
+    pub days: u32,
+}
+
+impl Default for MenuConfig {
+    fn default() -> Self {
+        Self { days: 4 }
+    }
+}
+
+impl MenuConfig {
+    /// `days`, kept between today alone and two months.
+    pub fn horizon(&self) -> u32 {
+        self.days.clamp(1, MAX_MENU_DAYS)
+    }
+}
+
+const MAX_MENU_DAYS: u32 = 62;
+
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct ClickConfig {
+    /// Action for a plain left click. Right- and control-clicks always open the menu.
+    pub primary: ClickAction,
+    /// Action for a double click, if any. Single clicks are then delayed by the
+    /// system double-click interval to tell the two apart.
+    pub double: Option<ClickAction>,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ClickAction {
+    #[default]
+    Menu,
+    /// Join the current or next meeting, falling back to the menu without a link
+    JoinNext,
+    OpenCalendar,
+    /// Open the current or next event in Calendar
+    OpenCurrentEvent,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct JoinConfig {
+    /// Which service gets the Join action when an event links several calls.
+    pub prefer: Vec<ServiceInfo>,
+    /// How links of each service open; unlisted services use `browser` or the
+    /// system default (Slack huddles go to the app).
+    pub open: HashMap<ServiceInfo, OpenWith>,
+    /// Pause Music and Spotify when a meeting with a call link starts.
+    pub pause_media: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OpenWith {
+    /// The service's desktop app, e.g. `zoommtg://` for Zoom
+    App,
+    /// The default web browser, even when an app claims the link
+    Browser,
+    /// A specific application by bundle identifier, e.g. "com.google.Chrome"
+    Application(String),
+}
+
+impl<'de> Deserialize<'de> for OpenWith {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = String::deserialize(deserializer)?;
+        match value.as_str() {
+            "app" => Ok(Self::App),
+            "browser" => Ok(Self::Browser),
+            bundle_id if bundle_id.contains('.') => Ok(Self::Application(value)),
+            _ => Err(serde::de::Error::custom(format!(
+                "expected \"app\", \"browser\", or a bundle identifier, got `{}`",
+                value
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct PauseConfig {
+    /// Minutes after which "Pause eventually" resumes on its own.
+    pub resume_after: i64,
+}
+
+impl Default for PauseConfig {
+    fn default() -> Self {
+        Self { resume_after: 60 }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct SyncConfig {
+    /// Minutes between asking EventKit to sync its accounts, for ones that
+    /// don't report changes on their own. Off unless set.
+    pub poll_minutes: Option<u32>,
+    /// Seconds between refreshes of the title's countdown.
+    pub refresh_seconds: u32,
+}
+
+impl Default for SyncConfig {
+    fn default() -> Self {
+        Self {
+            poll_minutes: None,
+            refresh_seconds: 60,
+        }
+    }
+}
+
+impl SyncConfig {
+    /// `refresh_seconds`, kept between 15 seconds and the countdown's minute.
+    pub fn refresh_interval(&self) -> u32 {
+        self.refresh_seconds
+            .clamp(MIN_REFRESH_SECONDS, MAX_REFRESH_SECONDS)
+    }
+
+    /// `poll_minutes`, at most once every five minutes.
+    pub fn poll_interval(&self) -> Option<u32> {
+        self.poll_minutes
+            .map(|minutes| minutes.max(MIN_POLL_MINUTES))
+    }
+}
+
+const MIN_POLL_MINUTES: u32 = 5;
+const MIN_REFRESH_SECONDS: u32 = 15;
+const MAX_REFRESH_SECONDS: u32 = 60;
+
+/// A link to have at hand for matching events, like the notes doc of a 1:1.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct PrepLink {
+    /// Title substring (case-insensitive) of the events it's for.
+    pub title: String,
+    pub url: String,
+    /// Shown instead of the URL.
+    pub label: Option<String>,
+}
+
+impl Config {
+    /// The preparation links configured for events titled `title`.
+    pub fn prep_links_for(&self, title: &str) -> Vec<&PrepLink> {
+        let title = title.to_lowercase();
+        self.prep_links
+            .iter()
+            .filter(|link| title.contains(&link.title.to_lowercase()))
+            .collect()
+    }
+}
+
+/// Banners posted ahead of events, off unless `minutes-before` is set.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct NotificationsConfig {
+    /// How long before each event its notification shows.
+    pub minutes_before: Option<i64>,
+}
+
+/// Gaps between meetings offered under "Suggest Focus Blocks".
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct FocusConfig {
+    /// Title of the events created.
+    pub title: String,
+    /// Blocks suggested per weekday; 0 turns suggestions off.
+    pub per_day: usize,
+    /// Shortest gap worth suggesting, in minutes.
+    pub min_minutes: i64,
+    /// Longest block, in minutes.
+    pub max_minutes: i64,
+    /// Working hours the blocks fall within.
+    #[serde(deserialize_with = "deserialize_time")]
+    pub day_start: NaiveTime,
+    #[serde(deserialize_with = "deserialize_time")]
+    pub day_end: NaiveTime,
+}
+
+impl Default for FocusConfig {
+    fn default() -> Self {
+        Self {
+            title: "Focus".to_string(),
+            per_day: 2,
+            min_minutes: 60,
+            max_minutes: 120,
+            day_start: NaiveTime::from_hms_opt(9, 0, 0).unwrap_or_default(),
+            day_end: NaiveTime::from_hms_opt(17, 0, 0).unwrap_or_default(),
+        }
+    }
+}
+
+/// All-day "Alice OOO" style events from shared calendars, collapsed into an
+/// "Out of office" submenu.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct BannerConfig {
+    /// Title substrings (case-insensitive) recognised on top of the built-in
+    /// OOO/PTO/Vacation words.
+    pub patterns: Vec<String>,
+}
+
+impl BannerConfig {
+    /// Only all-day events are candidates; a timed "PTO sync" stays a meeting.
+    pub fn matches(&self, title: &str) -> bool {
+        is_absence_title(title)
+            || self
+                .patterns
+                .iter()
+                .any(|pattern| title.to_lowercase().contains(&pattern.to_lowercase()))
+    }
+}
+
+/// Subscribed calendars (public holidays, birthdays) are muted by default:
+/// their events are collapsed per calendar and never take the title.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct CalendarsConfig {
+    /// Calendar names to mute on top of subscribed ones.
+    pub muted: Vec<String>,
+    /// Subscribed calendar names to show like any other.
+    pub unmuted: Vec<String>,
+    /// Calendar names or identifiers to fetch from, all of them when empty.
+    pub include: Vec<String>,
+    /// Calendar names or identifiers never fetched from.
+    pub exclude: Vec<String>,
+}
+
+impl CalendarsConfig {
+    /// Whether events are fetched from the calendar at all.
+    pub fn is_shown(&self, calendar_id: &str, calendar_name: &str) -> bool {
+        let matches = |names: &[String]| {
+            names
+                .iter()
+                .any(|name| name == calendar_id || name == calendar_name)
+        };
+        (self.include.is_empty() || matches(&self.include)) && !matches(&self.exclude)
+    }
+
+    pub fn is_muted(&self, calendar_name: &str, is_subscribed: bool) -> bool {
+        if self.unmuted.iter().any(|name| name == calendar_name) {
+            return false;
+        }
+        is_subscribed || self.muted.iter().any(|name| name == calendar_name)
+    }
+}
+
+/// Built-in OOO/PTO/Vacation wording, matched as whole words.
+pub fn is_absence_title(title: &str) -> bool {
+    let title = title.to_lowercase();
+    title
+        .split(|c: char| !c.is_alphanumeric())
+        .any(|word| BANNER_WORDS.contains(&word))
+        || title.contains("out of office")
+}
+
+const BANNER_WORDS: [&str; 4] = ["ooo", "pto", "vacation", "oof"];
+
+/// Placeholder texts, overridable for shorter wording or another language.
+/// An empty string leaves just the status icon.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct TextConfig {
+    pub no_events: String,
+    pub no_more_events_today: String,
+    /// Shown on days with your own all-day OOO or holiday event
+    pub ooo_today: String,
+    pub access_denied: String,
+}
+
+impl Default for TextConfig {
+    fn default() -> Self {
+        Self {
+            no_events: "No events".to_string(),
+            no_more_events_today: "No more events today".to_string(),
+            ooo_today: "OOO today".to_string(),
+            access_denied: "No calendar access".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TitleMode {
+    /// Event title followed by the countdown, e.g. "Standup • in 12m"
+    #[default]
+    Full,
+    /// Only the remaining time or start time, e.g. "12m" or "14:00"
+    TimeOnly,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CurrentCountdown {
+    /// Time until the event ends, e.g. "Standup • 12m left"
+    #[default]
+    Remaining,
+    /// Time since the event started, e.g. "Standup • 18m in"
+    Elapsed,
+    /// Time until the next event starts, e.g. "Standup • next in 38m", to
+    /// judge whether a break is coming. The time left when nothing follows.
+    NextCommitment,
+}
+
+impl CurrentCountdown {
+    pub const ALL: [Self; 3] = [Self::Remaining, Self::Elapsed, Self::NextCommitment];
+
+    /// Name in the config file
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Remaining => "remaining",
+            Self::Elapsed => "elapsed",
+            Self::NextCommitment => "next-commitment",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|countdown| countdown.name() == name)
+    }
+
+    /// Label in the settings submenu
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Remaining => "Count Down to End",
+            Self::Elapsed => "Count Up from Start",
+            Self::NextCommitment => "Count Down to Next Event",
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum UpcomingCountdown {
+    /// Time until the event starts, e.g. "Standup • in 12m"
+    #[default]
+    Until,
+    /// The start time, e.g. "Standup at 09:30"
+    StartTime,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct QuietHours {
+    #[serde(deserialize_with = "deserialize_time")]
+    pub start: NaiveTime,
+    #[serde(deserialize_with = "deserialize_time")]
+    pub end: NaiveTime,
+    #[serde(default)]
+    pub weekends: bool,
+}
+
+impl QuietHours {
+    /// Whether `now` falls inside the schedule. Ranges may wrap past midnight
+    /// (e.g. 19:00–08:00), and weekends are quiet all day when enabled.
+    pub fn contains(&self, now: DateTime<Local>) -> bool {
+        if self.weekends && matches!(now.weekday(), Weekday::Sat | Weekday::Sun) {
+            return true;
+        }
+
+        let time = now.time();
+        if self.start <= self.end {
+            self.start <= time && time < self.end
+        } else {
+            time >= self.start || time < self.end
+        }
+    }
+}
+
+/// A vacation date range, both ends inclusive.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct OutOfOffice {
+    #[serde(deserialize_with = "deserialize_date")]
+    pub start: NaiveDate,
+    #[serde(deserialize_with = "deserialize_date")]
+    pub end: NaiveDate,
+}
+
+impl OutOfOffice {
+    pub fn contains(&self, now: DateTime<Local>) -> bool {
+        (self.start..=self.end).contains(&now.date_naive())
+    }
+
+    pub fn title(&self) -> String {
+        format!("OOO until {}", self.end.format("%b %-d"))
+    }
+}
+
+fn deserialize_date<'de, D>(deserializer: D) -> Result<NaiveDate, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = String::deserialize(deserializer)?;
+    NaiveDate::parse_from_str(&value, "%Y-%m-%d").map_err(serde::de::Error::custom)
+}
+
+fn deserialize_time<'de, D>(deserializer: D) -> Result<NaiveTime, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = String::deserialize(deserializer)?;
+    NaiveTime::parse_from_str(&value, "%H:%M").map_err(serde::de::Error::custom)
+}
+
+/// Keys are matched case-insensitively, so they're stored lowercased.
+fn deserialize_abbreviations<'de, D>(deserializer: D) -> Result<HashMap<String, String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let abbreviations = HashMap::<String, String>::deserialize(deserializer)?;
+    Ok(abbreviations
+        .into_iter()
+        .map(|(word, short)| (word.to_lowercase(), short))
+        .collect())
+}
+
+fn deserialize_strftime<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = String::deserialize(deserializer)?;
+    if StrftimeItems::new(&value).any(|item| matches!(item, Item::Error)) {
+        return Err(serde::de::Error::custom(format!(
+            "invalid format string `{}`",
+            value
+        )));
+    }
+    Ok(Some(value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> DateTime<Local> {
+        Local
+            .with_ymd_and_hms(year, month, day, hour, minute, 0)
+            .single()
+            .unwrap()
+    }
+
+    fn quiet_hours(start: &str, end: &str, weekends: bool) -> QuietHours {
+        QuietHours {
+            start: NaiveTime::parse_from_str(start, "%H:%M").unwrap(),
+            end: NaiveTime::parse_from_str(end, "%H:%M").unwrap(),
+            weekends,
+        }
+    }
+
+    #[test]
+    fn test_parse_empty_config() {
+        assert_eq!(Config::parse("").unwrap(), Config::default());
+    }
+
+    #[test]
+    fn test_parse_quiet_hours() {
+        let config = Config::parse(
+            r#"
+            [quiet-hours]
+            start = "19:00"
+            end = "08:00"
+            weekends = true
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.quiet_hours,
+            Some(quiet_hours("19:00", "08:00", true))
+        );
+    }
+
+    #[test]
+    fn test_parse_title_config() {
+        let config = Config::parse(
+            r#"
+            [title]
+            mode = "time-only"
+            max-length = 24
+            icon-only-when-hidden = true
+            overlap-warning = 0
+            wrap-up = 5
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.title.mode, TitleMode::TimeOnly);
+        assert_eq!(config.title.max_length, 24);
+        assert!(config.title.icon_only_when_hidden);
+        assert_eq!(config.title.overlap_warning, 0);
+        assert_eq!(Config::default().title.overlap_warning, 10);
+        assert_eq!(config.title.wrap_up, Some(5));
+    }
+
+    #[test]
+    fn test_menu_horizon() {
+        let config = Config::parse("[menu]\ndays = 10").unwrap();
+        assert_eq!(config.menu.horizon(), 10);
+        assert_eq!(Config::default().menu.horizon(), 4);
+        assert_eq!(Config::parse("[menu]\ndays = 0").unwrap().menu.horizon(), 1);
+    }
+
+    #[test]
+    fn test_calendars_shown() {
+        let calendars = Config::parse(
+            "[calendars]\ninclude = [\"Work\", \"A1B2-C3\"]\nexclude = [\"A1B2-C3\"]",
+        )
+        .unwrap()
+        .calendars;
+        assert!(calendars.is_shown("D4E5", "Work"));
+        assert!(!calendars.is_shown("F6", "Home"));
+        // Exclusion wins
+        assert!(!calendars.is_shown("A1B2-C3", "Family"));
+        assert!(CalendarsConfig::default().is_shown("F6", "Home"));
+    }
+
+    #[test]
+    fn test_prep_links_for() {
+        let config = Config::parse(
+            r#"
+            [[prep-links]]
+            title = "1:1 with Sam"
+            url = "https://docs.example.com/sam"
+            label = "1:1 notes"
+
+            [[prep-links]]
+            title = "Planning"
+            url = "https://linear.app/team/board"
+            "#,
+        )
+        .unwrap();
+        let links = config.prep_links_for("Weekly 1:1 WITH SAM");
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].label.as_deref(), Some("1:1 notes"));
+        assert!(config.prep_links_for("Standup").is_empty());
+        assert!(Config::parse("[[prep-links]]\ntitle = \"Planning\"").is_err());
+    }
+
+    #[test]
+    fn test_focus_config() {
+        let config = Config::parse("[focus]\nper-day = 1\nday-start = \"10:30\"").unwrap();
+        assert_eq!(config.focus.per_day, 1);
+        assert_eq!(
+            config.focus.day_start,
+            NaiveTime::from_hms_opt(10, 30, 0).unwrap()
+        );
+        assert_eq!(config.focus.day_end, FocusConfig::default().day_end);
+        assert!(Config::parse("[focus]\nday-end = \"5pm\"").is_err());
+    }
+
+    #[test]
+    fn test_sync_poll_interval() {
+        assert_eq!(Config::default().sync.poll_interval(), None);
+        let config = Config::parse("[sync]\npoll-minutes = 15").unwrap();
+        assert_eq!(config.sync.poll_interval(), Some(15));
+        let config = Config::parse("[sync]\npoll-minutes = 1").unwrap();
+        assert_eq!(config.sync.poll_interval(), Some(5));
+    }
+
+    #[test]
+    fn test_sync_refresh_interval() {
+        assert_eq!(Config::default().sync.refresh_interval(), 60);
+        let config = Config::parse("[sync]\nrefresh-seconds = 30").unwrap();
+        assert_eq!(config.sync.refresh_interval(), 30);
+        let config = Config::parse("[sync]\nrefresh-seconds = 600").unwrap();
+        assert_eq!(config.sync.refresh_interval(), 60);
+    }
+
+    #[test]
+    fn test_parse_countdown_strategies() {
+        let config = Config::parse(
+            r#"
+            [title]
+            current = "elapsed"
+            upcoming = "start-time"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.title.current, CurrentCountdown::Elapsed);
+        assert_eq!(config.title.upcoming, UpcomingCountdown::StartTime);
+        assert_eq!(
+            Config::parse("").unwrap().title.current,
+            CurrentCountdown::Remaining
+        );
+
+        // Names from the settings submenu match the config file's
+        for countdown in CurrentCountdown::ALL {
+            let toml = format!("[title]\ncurrent = \"{}\"", countdown.name());
+            assert_eq!(Config::parse(&toml).unwrap().title.current, countdown);
+            assert_eq!(
+                CurrentCountdown::from_name(countdown.name()),
+                Some(countdown)
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_idle_format() {
+        let config = Config::parse(
+            r#"
+            [title]
+            idle-format = "%a %d %b · W%V"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.title.idle_format.as_deref(), Some("%a %d %b · W%V"));
+        assert!(Config::parse("[title]\nidle-format = \"%Q\"").is_err());
+    }
+
+    #[test]
+    fn test_parse_smart_shorten() {
+        let config = Config::parse(
+            r#"
+            [title]
+            smart-shorten = true
+            abbreviations = { Infrastructure = "Infra" }
+            "#,
+        )
+        .unwrap();
+
+        assert!(config.title.smart_shorten);
+        assert_eq!(
+            config
+                .title
+                .abbreviations
+                .get("infrastructure")
+                .map(String::as_str),
+            Some("Infra")
+        );
+    }
+
+    #[test]
+    fn test_parse_countdown_config() {
+        let config =
+            Config::parse("[countdown]\nexclude-free = true\naccepted-only = true").unwrap();
+        assert!(config.countdown.exclude_free);
+        assert!(config.countdown.accepted_only);
+        assert!(!Config::default().countdown.exclude_free);
+        assert_eq!(Config::default().countdown.max_duration, None);
+    }
+
+    #[test]
+    fn test_calendars_muted() {
+        let calendars =
+            Config::parse("[calendars]\nmuted = [\"Team PTO\"]\nunmuted = [\"F1 Races\"]")
+                .unwrap()
+                .calendars;
+        assert!(calendars.is_muted("US Holidays", true));
+        assert!(!calendars.is_muted("F1 Races", true));
+        assert!(calendars.is_muted("Team PTO", false));
+        assert!(!calendars.is_muted("Work", false));
+    }
+
+    #[test]
+    fn test_banner_matches() {
+        let banners = Config::parse("[banners]\npatterns = [\"Parental leave\"]")
+            .unwrap()
+            .banners;
+        assert!(banners.matches("Alice OOO"));
+        assert!(banners.matches("Bob - PTO (back Monday)"));
+        assert!(banners.matches("Out of Office: Carol"));
+        assert!(banners.matches("Dana parental leave"));
+        assert!(!banners.matches("Crypto review"));
+        assert!(!banners.matches("Vacationing plans"));
+    }
+
+    #[test]
+    fn test_parse_countdown_max_duration() {
+        let config =
+            Config::parse("[countdown]\nmax-duration = 240\npinned = [\"Offsite\"]").unwrap();
+        assert_eq!(config.countdown.max_duration, Some(240));
+        assert!(config.countdown.is_pinned("Team offsite day 1"));
+        assert!(!config.countdown.is_pinned("On-call"));
+    }
+
+    #[test]
+    fn test_parse_click_config() {
+        let config = Config::parse(
+            r#"
+            [click]
+            primary = "join-next"
+            double = "open-current-event"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.click.primary, ClickAction::JoinNext);
+        assert_eq!(config.click.double, Some(ClickAction::OpenCurrentEvent));
+        assert!(Config::parse("[click]\nprimary = \"launch\"").is_err());
+    }
+
+    #[test]
+    fn test_parse_join_config() {
+        let config = Config::parse("[join]\nprefer = [\"zoom\", \"meet\", \"teams\"]").unwrap();
+        assert_eq!(
+            config.join.prefer,
+            vec![
+                ServiceInfo::Zoom,
+                ServiceInfo::GoogleMeet,
+                ServiceInfo::MicrosoftTeams
+            ]
+        );
+        assert!(Config::parse("[join]\nprefer = [\"generic\"]").is_err());
+    }
+
+    #[test]
+    fn test_parse_browser() {
+        let config = Config::parse(
+            r#"
+            browser = "com.microsoft.edgemac"
+            browser-profile = "Profile 2"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.browser.as_deref(), Some("com.microsoft.edgemac"));
+        assert_eq!(config.browser_profile.as_deref(), Some("Profile 2"));
+        assert_eq!(Config::parse("").unwrap().browser, None);
+    }
+
+    #[test]
+    fn test_parse_google_calendar_web() {
+        assert!(
+            Config::parse("google-calendar-web = true")
+                .unwrap()
+                .google_calendar_web
+        );
+        assert!(!Config::parse("").unwrap().google_calendar_web);
+    }
+
+    #[test]
+    fn test_parse_join_open() {
+        let config = Config::parse(
+            r#"
+            [join.open]
+            zoom = "app"
+            teams = "browser"
+            meet = "com.google.Chrome"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.join.open[&ServiceInfo::Zoom], OpenWith::App);
+        assert_eq!(
+            config.join.open[&ServiceInfo::MicrosoftTeams],
+            OpenWith::Browser
+        );
+        assert_eq!(
+            config.join.open[&ServiceInfo::GoogleMeet],
+            OpenWith::Application("com.google.Chrome".to_string())
+        );
+        assert!(Config::parse("[join.open]\nzoom = \"native\"").is_err());
+    }
+
+    #[test]
+    fn test_parse_pause_config() {
+        let config = Config::parse("[pause]\nresume-after = 15").unwrap();
+        assert_eq!(config.pause.resume_after, 15);
+        assert_eq!(Config::default().pause.resume_after, 60);
+    }
+
+    #[test]
+    fn test_parse_text_config() {
+        let config = Config::parse(
+            r#"
+            [text]
+            no-more-events-today = ""
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.text.no_more_events_today, "");
+        assert_eq!(config.text.no_events, TextConfig::default().no_events);
+    }
+
+    #[test]
+    fn test_out_of_office() {
+        let config = Config::parse(
+            r#"
+            [out-of-office]
+            start = "2025-03-03"
+            end = "2025-03-14"
+            "#,
+        )
+        .unwrap();
+        let ooo = config.out_of_office.unwrap();
+
+        assert_eq!(ooo.title(), "OOO until Mar 14");
+        assert!(ooo.contains(at(2025, 3, 3, 0, 0)));
+        assert!(ooo.contains(at(2025, 3, 14, 23, 59)));
+        assert!(!ooo.contains(at(2025, 3, 15, 0, 0)));
+        assert!(Config::parse("[out-of-office]\nstart = \"3/3\"\nend = \"3/14\"").is_err());
+    }
+
+    #[test]
+    fn test_parse_invalid_time() {
+        let result = Config::parse(
+            r#"
+            [quiet-hours]
+            start = "7pm"
+            end = "08:00"
+            "#,
+        );
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_quiet_hours_same_day_range() {
+        // 2025-03-05 is a Wednesday
+        let hours = quiet_hours("12:00", "13:00", false);
+        assert!(hours.contains(at(2025, 3, 5, 12, 30)));
+        assert!(!hours.contains(at(2025, 3, 5, 13, 0)));
+        assert!(!hours.contains(at(2025, 3, 5, 11, 59)));
+    }
+
+    #[test]
+    fn test_quiet_hours_wraps_midnight() {
+        let hours = quiet_hours("19:00", "08:00", false);
+        assert!(hours.contains(at(2025, 3, 5, 22, 0)));
+        assert!(hours.contains(at(2025, 3, 5, 7, 59)));
+        assert!(!hours.contains(at(2025, 3, 5, 8, 0)));
+        assert!(!hours.contains(at(2025, 3, 5, 18, 59)));
+    }
+
+    #[test]
+    fn test_quiet_hours_weekends() {
+        // 2025-03-08 is a Saturday
+        assert!(quiet_hours("19:00", "08:00", true).contains(at(2025, 3, 8, 12, 0)));
+        assert!(!quiet_hours("19:00", "08:00", false).contains(at(2025, 3, 8, 12, 0)));
+    }
+}
@@ -1,6 +1,6 @@
 use objc2::rc::Retained;
 use objc2_app_kit::NSWorkspace;
-use objc2_foundation::{NSNotificationCenter, NSString};
+use objc2_foundation::{NSDistributedNotificationCenter, NSNotificationCenter, NSString};
 
 use crate::ffi::foundation;
 use crate::menu::MenuDelegate;
@@ -9,6 +9,7 @@ use crate::menu::MenuDelegate;
 enum NotificationCenter {
     Default,
     Workspace,
+    Distributed,
 }
 
 impl NotificationCenter {
@@ -16,6 +17,9 @@ impl NotificationCenter {
         match self {
             Self::Default => NSNotificationCenter::defaultCenter(),
             Self::Workspace => NSWorkspace::sharedWorkspace().notificationCenter(),
+            Self::Distributed => {
+                Retained::into_super(NSDistributedNotificationCenter::defaultCenter())
+            }
         }
     }
 }
@@ -44,6 +48,12 @@ impl NotificationConfig {
         match self.selector {
             "eventStoreChanged:" => objc2::sel!(eventStoreChanged:),
             "didWakeNotification:" => objc2::sel!(didWakeNotification:),
+            "occlusionChanged:" => objc2::sel!(occlusionChanged:),
+            "appearanceChanged:" => objc2::sel!(appearanceChanged:),
+            "suspendTimers:" => objc2::sel!(suspendTimers:),
+            "screensDidWake:" => objc2::sel!(screensDidWake:),
+            "screenUnlocked:" => objc2::sel!(screenUnlocked:),
+            "timeChanged:" => objc2::sel!(timeChanged:),
             _ => unreachable!("Unknown selector"),
         }
     }
@@ -62,6 +72,17 @@ impl NotificationConfig {
 pub enum SystemNotification {
     EventStoreChanged,
     WorkspaceDidWake,
+    WorkspaceWillSleep,
+    ScreensDidSleep,
+    ScreensDidWake,
+    ScreenUnlocked,
+    WindowOcclusionChanged,
+    AppearanceChanged,
+    AccentColorChanged,
+    AccessibilityDisplayChanged,
+    TimeZoneChanged,
+    ClockChanged,
+    DayChanged,
 }
 
 impl SystemNotification {
@@ -77,6 +98,67 @@ impl SystemNotification {
                 "didWakeNotification:",
                 NotificationCenter::Workspace,
             ),
+            Self::WorkspaceWillSleep => NotificationConfig::new(
+                "NSWorkspaceWillSleepNotification",
+                "suspendTimers:",
+                NotificationCenter::Workspace,
+            ),
+            Self::ScreensDidSleep => NotificationConfig::new(
+                "NSWorkspaceScreensDidSleepNotification",
+                "suspendTimers:",
+                NotificationCenter::Workspace,
+            ),
+            Self::ScreensDidWake => NotificationConfig::new(
+                "NSWorkspaceScreensDidWakeNotification",
+                "screensDidWake:",
+                NotificationCenter::Workspace,
+            ),
+            // Calendar syncs tend to land right after logging back in
+            Self::ScreenUnlocked => NotificationConfig::new(
+                "com.apple.screenIsUnlocked",
+                "screenUnlocked:",
+                NotificationCenter::Distributed,
+            ),
+            Self::WindowOcclusionChanged => NotificationConfig::new(
+                "NSWindowDidChangeOcclusionStateNotification",
+                "occlusionChanged:",
+                NotificationCenter::Default,
+            ),
+            // Posted when switching between light and dark mode
+            Self::AppearanceChanged => NotificationConfig::new(
+                "AppleInterfaceThemeChangedNotification",
+                "appearanceChanged:",
+                NotificationCenter::Distributed,
+            ),
+            // Posted when picking another accent or highlight color
+            Self::AccentColorChanged => NotificationConfig::new(
+                "AppleColorPreferencesChangedNotification",
+                "appearanceChanged:",
+                NotificationCenter::Distributed,
+            ),
+            // "Increase contrast" and the other display options changed
+            Self::AccessibilityDisplayChanged => NotificationConfig::new(
+                "NSWorkspaceAccessibilityDisplayOptionsDidChangeNotification",
+                "appearanceChanged:",
+                NotificationCenter::Workspace,
+            ),
+            Self::TimeZoneChanged => NotificationConfig::new(
+                "NSSystemTimeZoneDidChangeNotification",
+                "timeChanged:",
+                NotificationCenter::Default,
+            ),
+            // The clock was set by hand or resynced by a large step
+            Self::ClockChanged => NotificationConfig::new(
+                "NSSystemClockDidChangeNotification",
+                "clockChanged:",
+                NotificationCenter::Default,
+            ),
+            // Midnight, or a clock or time zone change that moved the date
+            Self::DayChanged => NotificationConfig::new(
+                "NSCalendarDayChangedNotification",
+                "timeChanged:",
+                NotificationCenter::Default,
+            ),
         }
     }
 
@@ -97,6 +179,17 @@ impl<'a> SystemNotificationObserver<'a> {
     pub fn register(self) -> Self {
         SystemNotification::EventStoreChanged.register(self.delegate);
         SystemNotification::WorkspaceDidWake.register(self.delegate);
+        SystemNotification::WorkspaceWillSleep.register(self.delegate);
+        SystemNotification::ScreensDidSleep.register(self.delegate);
+        SystemNotification::ScreensDidWake.register(self.delegate);
+        SystemNotification::ScreenUnlocked.register(self.delegate);
+        SystemNotification::WindowOcclusionChanged.register(self.delegate);
+        SystemNotification::AppearanceChanged.register(self.delegate);
+        SystemNotification::AccentColorChanged.register(self.delegate);
+        SystemNotification::AccessibilityDisplayChanged.register(self.delegate);
+        SystemNotification::TimeZoneChanged.register(self.delegate);
+        SystemNotification::ClockChanged.register(self.delegate);
+        SystemNotification::DayChanged.register(self.delegate);
         self
     }
 }
@@ -1,8 +1,14 @@
 use objc2::msg_send;
 use objc2::rc::Retained;
 use objc2::runtime::AnyObject;
-use objc2_app_kit::{NSColor, NSImage, NSMenu, NSMenuItem};
-use objc2_foundation::{MainThreadMarker, NSData, NSRange, NSRect, NSSize, NSString};
+use objc2_app_kit::{
+    NSAlert, NSAlertFirstButtonReturn, NSApplication, NSColor, NSControl, NSImage, NSMenu,
+    NSMenuItem, NSPasteboard, NSPasteboardTypeString, NSTextField, NSWorkspace,
+    NSWorkspaceOpenConfiguration,
+};
+use objc2_foundation::{
+    ns_string, MainThreadMarker, NSArray, NSData, NSPoint, NSRange, NSRect, NSSize, NSString, NSURL,
+};
 
 pub fn init_image_from_data(data: &NSData) -> Option<Retained<NSImage>> {
     let image: Option<Retained<NSImage>> =
@@ -87,6 +93,68 @@ pub fn init_attributed_string(string: &NSString) -> Retained<AnyObject> {
     }
 }
 
+pub fn append_string(attr_string: &AnyObject, string: &NSString) {
+    unsafe {
+        let suffix: Retained<AnyObject> = msg_send![
+            msg_send![objc2::class!(NSAttributedString), alloc],
+            initWithString: string
+        ];
+        let _: () = msg_send![attr_string, appendAttributedString: &*suffix];
+    }
+}
+
+/// Asks for a line of text in a modal alert, starting from `initial`. `None`
+/// when cancelled.
+pub fn prompt_for_text(
+    mtm: MainThreadMarker,
+    message: &str,
+    informative: &str,
+    initial: &str,
+) -> Option<String> {
+    let alert = NSAlert::new(mtm);
+    alert.setMessageText(&NSString::from_str(message));
+    alert.setInformativeText(&NSString::from_str(informative));
+    alert.addButtonWithTitle(ns_string!("Save"));
+    alert.addButtonWithTitle(ns_string!("Cancel"));
+
+    let field = NSTextField::textFieldWithString(&NSString::from_str(initial), mtm);
+    field.setFrame(NSRect::new(
+        NSPoint::new(0.0, 0.0),
+        NSSize::new(260.0, 24.0),
+    ));
+    alert.setAccessoryView(Some(&field));
+    alert.window().setInitialFirstResponder(Some(&field));
+
+    // A menu bar app isn't frontmost, so the alert would open behind other windows
+    NSApplication::sharedApplication(mtm).activate();
+    (alert.runModal() == NSAlertFirstButtonReturn).then(|| field.stringValue().to_string())
+}
+
+pub fn attributed_string_length(attr_string: &AnyObject) -> usize {
+    unsafe { msg_send![attr_string, length] }
+}
+
+/// Appends `image` inline through an NSTextAttachment.
+pub fn append_image(attr_string: &AnyObject, image: &NSImage) {
+    unsafe {
+        let attachment: Retained<AnyObject> = msg_send![objc2::class!(NSTextAttachment), new];
+        let _: () = msg_send![&*attachment, setImage: image];
+        let suffix: Retained<AnyObject> = msg_send![
+            objc2::class!(NSAttributedString),
+            attributedStringWithAttachment: &*attachment
+        ];
+        let _: () = msg_send![attr_string, appendAttributedString: &*suffix];
+    }
+}
+
+pub fn init_symbol_image(name: &NSString, description: &NSString) -> Option<Retained<NSImage>> {
+    NSImage::imageWithSystemSymbolName_accessibilityDescription(name, Some(description))
+}
+
+pub fn set_menu_item_on_state_image(item: &NSMenuItem, image: Option<&NSImage>) {
+    unsafe { item.setOnStateImage(image) }
+}
+
 pub fn set_attributed_title(item: &NSMenuItem, attr_string: &AnyObject) {
     unsafe {
         let _: () = msg_send![&*item, setAttributedTitle: attr_string];
@@ -136,6 +204,34 @@ where
     }
 }
 
+/// Sets the object sent `menuNeedsUpdate:` before `menu` opens. The menu
+/// doesn't retain it.
+pub fn set_menu_delegate<T>(menu: &NSMenu, delegate: Option<&T>)
+where
+    T: objc2::Message,
+{
+    unsafe {
+        let delegate: Option<&AnyObject> = delegate.map(|d| {
+            let ptr: *const T = d as *const T;
+            &*(ptr as *const AnyObject)
+        });
+        let _: () = msg_send![menu, setDelegate: delegate];
+    }
+}
+
+pub fn set_control_target<T>(control: &NSControl, target: Option<&T>)
+where
+    T: objc2::Message,
+{
+    unsafe {
+        let target_anyobject = target.map(|t| {
+            let ptr: *const T = t as *const T;
+            &*(ptr as *const AnyObject)
+        });
+        control.setTarget(target_anyobject);
+    }
+}
+
 pub fn set_menu_item_represented_object(
     item: &NSMenuItem,
     object: Option<&objc2::runtime::AnyObject>,
@@ -144,3 +240,87 @@ pub fn set_menu_item_represented_object(
         item.setRepresentedObject(object);
     }
 }
+
+pub fn copy_to_pasteboard(text: &NSString) {
+    let pasteboard = NSPasteboard::generalPasteboard();
+    pasteboard.clearContents();
+    pasteboard.setString_forType(text, unsafe { NSPasteboardTypeString });
+}
+
+/// Starts reading `text` aloud in the system voice. Speech stops if the
+/// returned synthesizer is released early, so hold on to it.
+pub fn speak(text: &NSString) -> Retained<AnyObject> {
+    unsafe {
+        let synthesizer: Retained<AnyObject> = msg_send![
+            msg_send![objc2::class!(NSSpeechSynthesizer), alloc],
+            initWithVoice: std::ptr::null::<NSString>()
+        ];
+        let _: bool = msg_send![&*synthesizer, startSpeakingString: text];
+        synthesizer
+    }
+}
+
+pub fn stop_speaking(synthesizer: &AnyObject) {
+    unsafe {
+        let _: () = msg_send![synthesizer, stopSpeaking];
+    }
+}
+
+/// Whether "Increase contrast" is on in the Accessibility display settings.
+pub fn should_increase_contrast() -> bool {
+    NSWorkspace::sharedWorkspace().accessibilityDisplayShouldIncreaseContrast()
+}
+
+/// The default web browser when `bundle_id` is `None`.
+pub fn application_url(bundle_id: Option<&str>) -> Option<Retained<NSURL>> {
+    let workspace = NSWorkspace::sharedWorkspace();
+    match bundle_id {
+        Some(bundle_id) => {
+            workspace.URLForApplicationWithBundleIdentifier(&NSString::from_str(bundle_id))
+        }
+        None => NSURL::URLWithString(ns_string!("https://"))
+            .and_then(|url| workspace.URLForApplicationToOpenURL(&url)),
+    }
+}
+
+/// The bundle identifier of the application at `application`.
+pub fn bundle_identifier(application: &NSURL) -> Option<String> {
+    unsafe {
+        let bundle: Option<Retained<AnyObject>> =
+            msg_send![objc2::class!(NSBundle), bundleWithURL: application];
+        let identifier: Option<Retained<NSString>> = msg_send![&*bundle?, bundleIdentifier];
+        identifier.map(|identifier| identifier.to_string())
+    }
+}
+
+pub fn open_url_with_application(url: &NSURL, application: &NSURL) {
+    let urls = NSArray::from_slice(&[url]);
+    let configuration = NSWorkspaceOpenConfiguration::configuration();
+    NSWorkspace::sharedWorkspace().openURLs_withApplicationAtURL_configuration_completionHandler(
+        &urls,
+        application,
+        &configuration,
+        None,
+    );
+}
+
+/// Launches a Chromium-based browser with `--profile-directory` so `url` opens
+/// under that profile. A fresh instance is needed for the arguments to apply;
+/// it hands the URL to the already running browser and exits.
+pub fn open_url_in_profile(url: &NSURL, application: &NSURL, profile: &str) {
+    let Some(url_string) = url.absoluteString() else {
+        return;
+    };
+    let arguments = NSArray::from_retained_slice(&[
+        NSString::from_str(&format!("--profile-directory={}", profile)),
+        url_string,
+    ]);
+    let configuration = NSWorkspaceOpenConfiguration::configuration();
+    configuration.setArguments(&arguments);
+    configuration.setCreatesNewApplicationInstance(true);
+    NSWorkspace::sharedWorkspace().openApplicationAtURL_configuration_completionHandler(
+        application,
+        &configuration,
+        None,
+    );
+}
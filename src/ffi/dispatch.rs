@@ -0,0 +1,29 @@
+use block2::RcBlock;
+use dispatch2::{
+    _dispatch_source_type_signal, DispatchObject, DispatchQueue, DispatchRetained, DispatchSource,
+};
+
+/// Runs `handler` on the main queue whenever `signal` is delivered to the process.
+///
+/// The signal's default disposition is ignored so that dispatch can pick it up
+/// instead. Keep the returned source alive for as long as the handler should fire.
+pub fn observe_signal(
+    signal: libc::c_int,
+    handler: impl Fn() + 'static,
+) -> DispatchRetained<DispatchSource> {
+    unsafe {
+        libc::signal(signal, libc::SIG_IGN);
+
+        let source = DispatchSource::new(
+            std::ptr::addr_of!(_dispatch_source_type_signal).cast_mut(),
+            signal as usize,
+            0,
+            Some(DispatchQueue::main()),
+        );
+        // dispatch copies the block, so ours can be dropped afterwards
+        let block = RcBlock::new(handler);
+        source.set_event_handler_with_block(RcBlock::as_ptr(&block));
+        source.activate();
+        source
+    }
+}
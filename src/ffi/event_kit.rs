@@ -1,6 +1,10 @@
 use objc2::rc::Retained;
-use objc2_event_kit::{EKCalendar, EKEntityType, EKEvent, EKEventStore};
-use objc2_foundation::{MainThreadMarker, NSDate};
+use objc2_app_kit::NSColorSpace;
+use objc2_event_kit::{
+    EKAuthorizationStatus, EKCalendar, EKCalendarType, EKEntityType, EKEvent, EKEventAvailability,
+    EKEventStore, EKParticipant, EKParticipantStatus, EKParticipantType, EKSourceType, EKSpan,
+};
+use objc2_foundation::{MainThreadMarker, NSArray, NSDate, NSString};
 
 pub fn init_event_store(mtm: MainThreadMarker) -> Retained<EKEventStore> {
     unsafe { EKEventStore::init(mtm.alloc::<EKEventStore>()) }
@@ -30,12 +34,52 @@ pub fn request_calendar_access(store: &EKEventStore) -> bool {
         })
 }
 
-pub fn fetch_events(store: &EKEventStore, start: &NSDate, end: &NSDate) -> Vec<Retained<EKEvent>> {
+/// "full access", "denied", and so on, for diagnostics.
+pub fn calendar_authorization_status() -> &'static str {
+    match unsafe { EKEventStore::authorizationStatusForEntityType(EKEntityType::Event) } {
+        EKAuthorizationStatus::FullAccess => "full access",
+        EKAuthorizationStatus::WriteOnly => "write only",
+        EKAuthorizationStatus::Denied => "denied",
+        EKAuthorizationStatus::Restricted => "restricted",
+        EKAuthorizationStatus::NotDetermined => "not determined",
+        _ => "unknown",
+    }
+}
+
+/// Asks the accounts behind the store to sync. Returns right away; changes
+/// land in the store later.
+pub fn refresh_sources(store: &EKEventStore) {
+    unsafe { store.refreshSourcesIfNecessary() }
+}
+
+/// Events on the calendars `shown` accepts, given each calendar's identifier
+/// and title.
+///
+/// Returns `None` when the store has no calendars at all, which is what EventKit
+/// reports while it is still syncing after login or an account re-auth.
+pub fn fetch_events(
+    store: &EKEventStore,
+    start: &NSDate,
+    end: &NSDate,
+    shown: impl Fn(&str, &str) -> bool,
+) -> Option<Vec<Retained<EKEvent>>> {
     unsafe {
         let calendars = store.calendarsForEntityType(EKEntityType::Event);
+        if calendars.count() == 0 {
+            return None;
+        }
+        let calendars: Vec<_> = calendars
+            .iter()
+            .filter(|calendar| shown(&get_calendar_id(calendar), &get_calendar_title(calendar)))
+            .collect();
+        // An empty list would mean every calendar to EventKit
+        if calendars.is_empty() {
+            return Some(Vec::new());
+        }
+        let calendars = NSArray::from_retained_slice(&calendars);
         let predicate =
             store.predicateForEventsWithStartDate_endDate_calendars(start, end, Some(&calendars));
-        store.eventsMatchingPredicate(&predicate).to_vec()
+        Some(store.eventsMatchingPredicate(&predicate).to_vec())
     }
 }
 
@@ -47,7 +91,6 @@ pub fn get_event_properties(
     Option<Retained<objc2_foundation::NSString>>,
     Retained<objc2_foundation::NSString>,
     Option<Retained<objc2_foundation::NSString>>,
-    Option<Retained<EKCalendar>>,
     bool,
 ) {
     unsafe {
@@ -57,17 +100,207 @@ pub fn get_event_properties(
             event.eventIdentifier(),
             event.title(),
             event.location(),
-            event.calendar(),
             event.hasRecurrenceRules(),
         )
     }
 }
 
-pub fn get_calendar_color(calendar: &EKCalendar) -> (f64, f64, f64) {
-    let color = unsafe { calendar.color() };
-    (
+pub fn get_event_start(event: &EKEvent) -> Retained<NSDate> {
+    unsafe { event.startDate() }
+}
+
+pub fn is_event_free(event: &EKEvent) -> bool {
+    unsafe { event.availability() == EKEventAvailability::Free }
+}
+
+/// Events without attendees, or where the current user isn't among them (as
+/// when they organized it), count as accepted.
+pub fn is_event_accepted(event: &EKEvent) -> bool {
+    unsafe {
+        event
+            .attendees()
+            .and_then(|attendees| {
+                attendees
+                    .iter()
+                    .find(|attendee| attendee.isCurrentUser())
+                    .map(|me| me.participantStatus() == EKParticipantStatus::Accepted)
+            })
+            .unwrap_or(true)
+    }
+}
+
+/// Rooms and other resources are booked as attendees but aren't counted.
+pub fn count_other_attendees(event: &EKEvent) -> usize {
+    unsafe {
+        event
+            .attendees()
+            .map(|attendees| {
+                attendees
+                    .iter()
+                    .filter(|attendee| !attendee.isCurrentUser() && !is_resource(attendee))
+                    .count()
+            })
+            .unwrap_or(0)
+    }
+}
+
+/// Names of the conference rooms booked for the event.
+pub fn get_event_rooms(event: &EKEvent) -> Vec<String> {
+    unsafe {
+        event
+            .attendees()
+            .map(|attendees| {
+                attendees
+                    .iter()
+                    .filter(|attendee| attendee.participantType() == EKParticipantType::Room)
+                    .filter_map(|room| room.name())
+                    .map(|name| name.to_string())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+fn is_resource(participant: &EKParticipant) -> bool {
+    let participant_type = unsafe { participant.participantType() };
+    participant_type == EKParticipantType::Room || participant_type == EKParticipantType::Resource
+}
+
+pub fn get_event_notes(event: &EKEvent) -> Option<Retained<NSString>> {
+    unsafe { event.notes() }
+}
+
+pub fn get_event_url(event: &EKEvent) -> Option<String> {
+    unsafe { event.URL() }
+        .and_then(|url| url.absoluteString())
+        .map(|url| url.to_string())
+}
+
+/// The iCalendar UID the event has on its server.
+pub fn get_event_external_id(event: &EKEvent) -> Option<String> {
+    unsafe { event.calendarItemExternalIdentifier() }.map(|id| id.to_string())
+}
+
+pub fn get_event_calendar(event: &EKEvent) -> Option<Retained<EKCalendar>> {
+    unsafe { event.calendar() }
+}
+
+pub fn get_calendar_id(calendar: &EKCalendar) -> String {
+    unsafe { calendar.calendarIdentifier() }.to_string()
+}
+
+pub fn get_default_calendar_id(store: &EKEventStore) -> Option<String> {
+    unsafe { store.defaultCalendarForNewEvents() }.map(|calendar| get_calendar_id(&calendar))
+}
+
+pub fn is_subscribed_calendar(calendar: &EKCalendar) -> bool {
+    unsafe {
+        calendar.isSubscribed()
+            || matches!(
+                calendar.r#type(),
+                EKCalendarType::Subscription | EKCalendarType::Birthday
+            )
+    }
+}
+
+pub fn get_calendar_title(calendar: &EKCalendar) -> String {
+    unsafe { calendar.title() }.to_string()
+}
+
+/// Name of the CalDAV account holding the calendar, usually its email address.
+pub fn get_caldav_account(calendar: &EKCalendar) -> Option<String> {
+    unsafe { calendar.source() }
+        .filter(|source| unsafe { source.sourceType() } == EKSourceType::CalDAV)
+        .map(|source| unsafe { source.title() }.to_string())
+}
+
+/// Display P3 components of the calendar's color. Colors from some CalDAV
+/// servers aren't RGB-based, and reading components off those directly raises
+/// an exception, so they're converted first; `None` if that isn't possible.
+pub fn get_calendar_color(calendar: &EKCalendar) -> Option<(f64, f64, f64)> {
+    let color =
+        unsafe { calendar.color() }.colorUsingColorSpace(&NSColorSpace::displayP3ColorSpace())?;
+    Some((
         color.redComponent(),
         color.greenComponent(),
         color.blueComponent(),
-    )
+    ))
+}
+
+/// Saves a new event in the source event's calendar, or the default calendar
+/// when that one is read-only. Returns the new event's identifier.
+///
+/// EventKit can't invite attendees, so their addresses go into the notes for
+/// adding in Calendar.
+pub fn create_follow_up(
+    store: &EKEventStore,
+    source_event_id: &str,
+    title: &str,
+    start: &NSDate,
+    end: &NSDate,
+) -> Result<String, String> {
+    unsafe {
+        let source = store.eventWithIdentifier(&NSString::from_str(source_event_id));
+        let calendar = source
+            .as_ref()
+            .and_then(|source| source.calendar())
+            .filter(|calendar| calendar.allowsContentModifications())
+            .or_else(|| store.defaultCalendarForNewEvents())
+            .ok_or("no writable calendar")?;
+
+        let attendees: Vec<String> = source
+            .as_ref()
+            .and_then(|source| source.attendees())
+            .map(|attendees| {
+                attendees
+                    .iter()
+                    .filter(|attendee| !attendee.isCurrentUser())
+                    .filter_map(|attendee| attendee.URL().absoluteString())
+                    .map(|url| url.to_string().trim_start_matches("mailto:").to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let notes = (!attendees.is_empty()).then(|| format!("Attendees: {}", attendees.join(", ")));
+        save_new_event(store, &calendar, title, start, end, notes.as_deref())
+    }
+}
+
+/// Saves a new event in the default calendar. Returns its identifier.
+pub fn create_event(
+    store: &EKEventStore,
+    title: &str,
+    start: &NSDate,
+    end: &NSDate,
+) -> Result<String, String> {
+    let calendar = unsafe { store.defaultCalendarForNewEvents() }.ok_or("no default calendar")?;
+    save_new_event(store, &calendar, title, start, end, None)
+}
+
+fn save_new_event(
+    store: &EKEventStore,
+    calendar: &EKCalendar,
+    title: &str,
+    start: &NSDate,
+    end: &NSDate,
+    notes: Option<&str>,
+) -> Result<String, String> {
+    unsafe {
+        let event = EKEvent::eventWithEventStore(store);
+        event.setTitle(Some(&NSString::from_str(title)));
+        event.setStartDate(Some(start));
+        event.setEndDate(Some(end));
+        event.setCalendar(Some(calendar));
+        if let Some(notes) = notes {
+            event.setNotes(Some(&NSString::from_str(notes)));
+        }
+
+        store
+            .saveEvent_span_error(&event, EKSpan::ThisEvent)
+            .map_err(|e| e.localizedDescription().to_string())?;
+        Ok(event
+            .eventIdentifier()
+            .map(|id| id.to_string())
+            .unwrap_or_default())
+    }
 }
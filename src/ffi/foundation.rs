@@ -1,6 +1,6 @@
 use objc2::rc::Retained;
 use objc2::runtime::AnyObject;
-use objc2_foundation::{NSNotificationCenter, NSString};
+use objc2_foundation::{NSNotificationCenter, NSProcessInfo, NSString};
 
 /// Macro to encapsulate the unsafe super init pattern required by objc2
 /// This cannot be abstracted into a function due to objc2's type system requiring
@@ -45,3 +45,10 @@ pub fn ns_menu_item_represented_object_to_string(
         (*ns_string).to_string()
     }
 }
+
+/// "Version 15.3 (Build 24D60)"
+pub fn os_version() -> String {
+    NSProcessInfo::processInfo()
+        .operatingSystemVersionString()
+        .to_string()
+}
@@ -0,0 +1,31 @@
+use objc2::runtime::AnyObject;
+use objc2_foundation::{NSDictionary, NSString};
+
+pub type OSStatus = i32;
+
+pub const ERR_SEC_SUCCESS: OSStatus = 0;
+pub const ERR_SEC_ITEM_NOT_FOUND: OSStatus = -25300;
+
+#[link(name = "Security", kind = "framework")]
+extern "C" {
+    pub static kSecClass: &'static NSString;
+    pub static kSecClassGenericPassword: &'static NSString;
+    pub static kSecAttrService: &'static NSString;
+    pub static kSecAttrAccount: &'static NSString;
+    pub static kSecValueData: &'static NSString;
+    pub static kSecReturnData: &'static NSString;
+
+    pub fn SecItemAdd(
+        attributes: &NSDictionary<NSString, AnyObject>,
+        result: *mut *mut AnyObject,
+    ) -> OSStatus;
+    pub fn SecItemCopyMatching(
+        query: &NSDictionary<NSString, AnyObject>,
+        result: *mut *mut AnyObject,
+    ) -> OSStatus;
+    pub fn SecItemUpdate(
+        query: &NSDictionary<NSString, AnyObject>,
+        attributes_to_update: &NSDictionary<NSString, AnyObject>,
+    ) -> OSStatus;
+    pub fn SecItemDelete(query: &NSDictionary<NSString, AnyObject>) -> OSStatus;
+}
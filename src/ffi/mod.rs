@@ -1,3 +1,7 @@
 pub mod app_kit;
+pub mod dispatch;
 pub mod event_kit;
 pub mod foundation;
+pub mod keychain;
+pub mod network;
+pub mod user_notifications;
@@ -0,0 +1,58 @@
+use block2::{Block, RcBlock};
+use dispatch2::DispatchQueue;
+use std::cell::Cell;
+use std::ffi::c_void;
+
+type NwPathMonitor = *mut c_void;
+type NwPath = *mut c_void;
+
+const NW_PATH_STATUS_SATISFIED: libc::c_int = 1;
+
+#[link(name = "Network", kind = "framework")]
+extern "C" {
+    fn nw_path_monitor_create() -> NwPathMonitor;
+    fn nw_path_monitor_set_queue(monitor: NwPathMonitor, queue: *const DispatchQueue);
+    fn nw_path_monitor_set_update_handler(
+        monitor: NwPathMonitor,
+        handler: *mut Block<dyn Fn(NwPath)>,
+    );
+    fn nw_path_monitor_start(monitor: NwPathMonitor);
+    fn nw_path_monitor_cancel(monitor: NwPathMonitor);
+    fn nw_path_get_status(path: NwPath) -> libc::c_int;
+    fn nw_release(object: *mut c_void);
+}
+
+/// Stops observing when dropped.
+pub struct PathMonitor(NwPathMonitor);
+
+impl Drop for PathMonitor {
+    fn drop(&mut self) {
+        unsafe {
+            nw_path_monitor_cancel(self.0);
+            nw_release(self.0);
+        }
+    }
+}
+
+/// Runs `handler` on the main queue whenever the network becomes reachable
+/// again after being down. Keep the returned monitor alive for as long as the
+/// handler should fire.
+pub fn observe_reachability(handler: impl Fn() + 'static) -> PathMonitor {
+    // The monitor reports the current state right away, which isn't a change
+    let was_reachable: Cell<Option<bool>> = Cell::new(None);
+    let block = RcBlock::new(move |path: NwPath| {
+        let reachable = unsafe { nw_path_get_status(path) } == NW_PATH_STATUS_SATISFIED;
+        if was_reachable.replace(Some(reachable)) == Some(false) && reachable {
+            handler();
+        }
+    });
+
+    unsafe {
+        let monitor = nw_path_monitor_create();
+        nw_path_monitor_set_queue(monitor, DispatchQueue::main());
+        // Network copies the block, so ours can be dropped afterwards
+        nw_path_monitor_set_update_handler(monitor, RcBlock::as_ptr(&block));
+        nw_path_monitor_start(monitor);
+        PathMonitor(monitor)
+    }
+}
@@ -0,0 +1,254 @@
+//! Banners through the UserNotifications framework, messaged by class name.
+//! The framework refuses processes without a bundle identifier, so a bare
+//! binary gets an error logged instead of notifications.
+
+use block2::RcBlock;
+use objc2::msg_send;
+use objc2::rc::Retained;
+use objc2::runtime::{AnyClass, AnyObject, Bool};
+use objc2_foundation::{NSArray, NSString};
+
+#[link(name = "UserNotifications", kind = "framework")]
+extern "C" {}
+
+const UN_AUTHORIZATION_OPTION_SOUND: usize = 1 << 1;
+const UN_AUTHORIZATION_OPTION_ALERT: usize = 1 << 2;
+const UN_NOTIFICATION_ACTION_OPTION_DESTRUCTIVE: usize = 1 << 1;
+const UN_NOTIFICATION_INTERRUPTION_LEVEL_TIME_SENSITIVE: usize = 2;
+
+/// Categories of banners, by the buttons they carry.
+const EVENT_CATEGORY: &str = "event";
+const CALL_CATEGORY: &str = "call";
+
+/// A button pressed on a banner.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationAction {
+    Join,
+    Snooze,
+    Dismiss,
+}
+
+impl NotificationAction {
+    const ALL: [Self; 3] = [Self::Join, Self::Snooze, Self::Dismiss];
+
+    fn identifier(self) -> &'static str {
+        match self {
+            Self::Join => "join",
+            Self::Snooze => "snooze",
+            Self::Dismiss => "dismiss",
+        }
+    }
+
+    fn title(self) -> &'static str {
+        match self {
+            Self::Join => "Join",
+            Self::Snooze => "Snooze",
+            Self::Dismiss => "Dismiss",
+        }
+    }
+}
+
+/// The shared center, or `None` outside an app bundle, where asking for it
+/// raises an exception.
+fn center() -> Option<Retained<AnyObject>> {
+    unsafe {
+        let bundle: Retained<AnyObject> = msg_send![objc2::class!(NSBundle), mainBundle];
+        let identifier: Option<Retained<NSString>> = msg_send![&*bundle, bundleIdentifier];
+        if identifier.is_none() {
+            eprintln!("Error: Notifications need eventually to run from an app bundle");
+            return None;
+        }
+        let class = AnyClass::get(c"UNUserNotificationCenter")?;
+        msg_send![class, currentNotificationCenter]
+    }
+}
+
+fn error_description(error: *mut AnyObject) -> Option<String> {
+    let error = unsafe { error.as_ref()? };
+    let description: Retained<NSString> = unsafe { msg_send![error, localizedDescription] };
+    Some(description.to_string())
+}
+
+fn category(identifier: &str, actions: &[NotificationAction]) -> Option<Retained<AnyObject>> {
+    let action_class = AnyClass::get(c"UNNotificationAction")?;
+    let category_class = AnyClass::get(c"UNNotificationCategory")?;
+    unsafe {
+        let actions: Vec<Retained<AnyObject>> = actions
+            .iter()
+            .map(|action| {
+                let options = match action {
+                    NotificationAction::Dismiss => UN_NOTIFICATION_ACTION_OPTION_DESTRUCTIVE,
+                    _ => 0,
+                };
+                msg_send![
+                    action_class,
+                    actionWithIdentifier: &*NSString::from_str(action.identifier()),
+                    title: &*NSString::from_str(action.title()),
+                    options: options
+                ]
+            })
+            .collect();
+        let actions = NSArray::from_retained_slice(&actions);
+        let intents = NSArray::<NSString>::new();
+        msg_send![
+            category_class,
+            categoryWithIdentifier: &*NSString::from_str(identifier),
+            actions: &*actions,
+            intentIdentifiers: &*intents,
+            options: 0usize
+        ]
+    }
+}
+
+/// Registers the banners' buttons, routes presses on them to `delegate`'s
+/// `userNotificationCenter:didReceiveNotificationResponse:withCompletionHandler:`,
+/// and asks for permission to show banners with sound. macOS prompts only
+/// the first time; after that the answer comes back right away.
+pub fn set_up<T>(delegate: &T)
+where
+    T: objc2::Message,
+{
+    let Some(center) = center() else {
+        return;
+    };
+    let categories: Vec<Retained<AnyObject>> = [
+        category(
+            EVENT_CATEGORY,
+            &[NotificationAction::Snooze, NotificationAction::Dismiss],
+        ),
+        category(CALL_CATEGORY, &NotificationAction::ALL),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    let handler = RcBlock::new(|granted: Bool, error: *mut AnyObject| {
+        if let Some(description) = error_description(error) {
+            eprintln!(
+                "Error: Failed to request notification access: {}",
+                description
+            );
+        } else if !granted.as_bool() {
+            eprintln!("Notifications are turned off for eventually in System Settings");
+        }
+    });
+
+    unsafe {
+        let delegate: &AnyObject = &*(delegate as *const T as *const AnyObject);
+        let _: () = msg_send![&*center, setDelegate: delegate];
+        let categories = NSArray::from_retained_slice(&categories);
+        let categories: Retained<AnyObject> =
+            msg_send![objc2::class!(NSSet), setWithArray: &*categories];
+        let _: () = msg_send![&*center, setNotificationCategories: &*categories];
+        let _: () = msg_send![
+            &*center,
+            requestAuthorizationWithOptions: UN_AUTHORIZATION_OPTION_ALERT | UN_AUTHORIZATION_OPTION_SOUND,
+            completionHandler: &*handler
+        ];
+    }
+}
+
+/// Shows a banner after `delay` seconds, or right away without one. Banners
+/// offer Snooze and Dismiss, plus Join `with_join`; a later one with the same
+/// `identifier` replaces it. `time_sensitive` ones break through Focus on
+/// macOS 12 and later, where the app is allowed to.
+pub fn post(
+    identifier: &str,
+    title: &str,
+    body: &str,
+    with_join: bool,
+    delay: Option<f64>,
+    time_sensitive: bool,
+) {
+    let Some(center) = center() else {
+        return;
+    };
+    let (Some(content_class), Some(sound_class), Some(request_class), Some(trigger_class)) = (
+        AnyClass::get(c"UNMutableNotificationContent"),
+        AnyClass::get(c"UNNotificationSound"),
+        AnyClass::get(c"UNNotificationRequest"),
+        AnyClass::get(c"UNTimeIntervalNotificationTrigger"),
+    ) else {
+        return;
+    };
+    let category = if with_join {
+        CALL_CATEGORY
+    } else {
+        EVENT_CATEGORY
+    };
+    let handler = RcBlock::new(|error: *mut AnyObject| {
+        if let Some(description) = error_description(error) {
+            eprintln!("Error: Failed to post notification: {}", description);
+        }
+    });
+
+    unsafe {
+        let content: Retained<AnyObject> = msg_send![msg_send![content_class, alloc], init];
+        let _: () = msg_send![&*content, setTitle: &*NSString::from_str(title)];
+        let _: () = msg_send![&*content, setBody: &*NSString::from_str(body)];
+        let _: () = msg_send![&*content, setCategoryIdentifier: &*NSString::from_str(category)];
+        let sound: Option<Retained<AnyObject>> = msg_send![sound_class, defaultSound];
+        let _: () = msg_send![&*content, setSound: sound.as_deref()];
+        let has_level: bool = msg_send![
+            &*content,
+            respondsToSelector: objc2::sel!(setInterruptionLevel:)
+        ];
+        if time_sensitive && has_level {
+            let _: () = msg_send![
+                &*content,
+                setInterruptionLevel: UN_NOTIFICATION_INTERRUPTION_LEVEL_TIME_SENSITIVE
+            ];
+        }
+
+        let trigger: Option<Retained<AnyObject>> = match delay {
+            Some(delay) => msg_send![
+                trigger_class,
+                triggerWithTimeInterval: delay.max(1.0),
+                repeats: false
+            ],
+            None => None,
+        };
+        let request: Retained<AnyObject> = msg_send![
+            request_class,
+            requestWithIdentifier: &*NSString::from_str(identifier),
+            content: &*content,
+            trigger: trigger.as_deref()
+        ];
+        let _: () = msg_send![
+            &*center,
+            addNotificationRequest: &*request,
+            withCompletionHandler: &*handler
+        ];
+    }
+}
+
+/// Drops a banner still waiting on its delay, such as a snoozed one.
+pub fn withdraw(identifier: &str) {
+    let Some(center) = center() else {
+        return;
+    };
+    let identifiers = NSArray::from_retained_slice(&[NSString::from_str(identifier)]);
+    unsafe {
+        let _: () = msg_send![
+            &*center,
+            removePendingNotificationRequestsWithIdentifiers: &*identifiers
+        ];
+    }
+}
+
+/// The identifier of the banner a response came from, and the button pressed
+/// if it was one of ours rather than the banner itself or its close button.
+pub fn response_action(response: &AnyObject) -> (String, Option<NotificationAction>) {
+    unsafe {
+        let action: Retained<NSString> = msg_send![response, actionIdentifier];
+        let notification: Retained<AnyObject> = msg_send![response, notification];
+        let request: Retained<AnyObject> = msg_send![&*notification, request];
+        let identifier: Retained<NSString> = msg_send![&*request, identifier];
+        let action = action.to_string();
+        (
+            identifier.to_string(),
+            NotificationAction::ALL
+                .into_iter()
+                .find(|candidate| candidate.identifier() == action),
+        )
+    }
+}
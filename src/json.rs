@@ -0,0 +1,201 @@
+//! Just enough JSON for the documents eventually reads and writes itself,
+//! which are flat objects of strings and numbers.
+
+use std::collections::BTreeMap;
+use std::iter::Peekable;
+use std::str::Chars;
+
+/// A value in a flat JSON object.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+}
+
+/// `value` as a quoted JSON string.
+pub fn string(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len() + 2);
+    escaped.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+/// Parses a JSON object whose values are all strings, numbers, booleans, or
+/// null. Nested objects and arrays are rejected.
+pub fn parse_flat_object(input: &str) -> Result<BTreeMap<String, Value>, String> {
+    let mut chars = input.chars().peekable();
+    let mut object = BTreeMap::new();
+
+    expect(&mut chars, '{')?;
+    if peek_past_whitespace(&mut chars) == Some('}') {
+        chars.next();
+    } else {
+        loop {
+            skip_whitespace(&mut chars);
+            let key = parse_string(&mut chars)?;
+            expect(&mut chars, ':')?;
+            let value = parse_value(&mut chars)?;
+            object.insert(key, value);
+
+            match peek_past_whitespace(&mut chars) {
+                Some(',') => {
+                    chars.next();
+                }
+                Some('}') => {
+                    chars.next();
+                    break;
+                }
+                _ => return Err("expected `,` or `}` after a value".to_string()),
+            }
+        }
+    }
+
+    if peek_past_whitespace(&mut chars).is_some() {
+        return Err("unexpected text after the object".to_string());
+    }
+    Ok(object)
+}
+
+fn skip_whitespace(chars: &mut Peekable<Chars>) {
+    while chars.next_if(|c| c.is_whitespace()).is_some() {}
+}
+
+fn peek_past_whitespace(chars: &mut Peekable<Chars>) -> Option<char> {
+    skip_whitespace(chars);
+    chars.peek().copied()
+}
+
+fn expect(chars: &mut Peekable<Chars>, expected: char) -> Result<(), String> {
+    match peek_past_whitespace(chars) {
+        Some(c) if c == expected => {
+            chars.next();
+            Ok(())
+        }
+        Some(c) => Err(format!("expected `{}`, found `{}`", expected, c)),
+        None => Err(format!("expected `{}`, found the end", expected)),
+    }
+}
+
+fn parse_value(chars: &mut Peekable<Chars>) -> Result<Value, String> {
+    match peek_past_whitespace(chars) {
+        Some('"') => parse_string(chars).map(Value::String),
+        Some('n') => parse_literal(chars, "null", Value::Null),
+        Some('t') => parse_literal(chars, "true", Value::Bool(true)),
+        Some('f') => parse_literal(chars, "false", Value::Bool(false)),
+        Some(c) if c == '-' || c.is_ascii_digit() => {
+            let mut number = String::new();
+            while let Some(c) =
+                chars.next_if(|c| c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E'))
+            {
+                number.push(c);
+            }
+            number
+                .parse()
+                .map(Value::Number)
+                .map_err(|_| format!("invalid number `{}`", number))
+        }
+        Some('{') | Some('[') => Err("nested objects and arrays aren't supported".to_string()),
+        Some(c) => Err(format!("unexpected `{}`", c)),
+        None => Err("expected a value, found the end".to_string()),
+    }
+}
+
+fn parse_literal(
+    chars: &mut Peekable<Chars>,
+    literal: &str,
+    value: Value,
+) -> Result<Value, String> {
+    for expected in literal.chars() {
+        if chars.next() != Some(expected) {
+            return Err(format!("expected `{}`", literal));
+        }
+    }
+    Ok(value)
+}
+
+fn parse_string(chars: &mut Peekable<Chars>) -> Result<String, String> {
+    expect(chars, '"')?;
+    let mut value = String::new();
+    loop {
+        match chars.next() {
+            Some('"') => return Ok(value),
+            Some('\\') => match chars.next() {
+                Some('"') => value.push('"'),
+                Some('\\') => value.push('\\'),
+                Some('/') => value.push('/'),
+                Some('b') => value.push('\u{8}'),
+                Some('f') => value.push('\u{c}'),
+                Some('n') => value.push('\n'),
+                Some('r') => value.push('\r'),
+                Some('t') => value.push('\t'),
+                Some('u') => {
+                    let high = parse_hex4(chars)?;
+                    let code = if (0xD800..0xDC00).contains(&high) {
+                        // A surrogate pair, written as two escapes
+                        if chars.next() != Some('\\') || chars.next() != Some('u') {
+                            return Err("unpaired surrogate in string".to_string());
+                        }
+                        let low = parse_hex4(chars)?;
+                        0x10000 + ((high - 0xD800) << 10) + (low.wrapping_sub(0xDC00) & 0x3FF)
+                    } else {
+                        high
+                    };
+                    value.push(
+                        char::from_u32(code).ok_or_else(|| "invalid \\u escape".to_string())?,
+                    );
+                }
+                _ => return Err("invalid escape in string".to_string()),
+            },
+            Some(c) => value.push(c),
+            None => return Err("unterminated string".to_string()),
+        }
+    }
+}
+
+fn parse_hex4(chars: &mut Peekable<Chars>) -> Result<u32, String> {
+    let hex: String = chars.by_ref().take(4).collect();
+    u32::from_str_radix(&hex, 16).map_err(|_| format!("invalid \\u escape `{}`", hex))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_string_round_trip() {
+        let text = "[title]\nmode = \"full\"\t# 会議 🎉\\";
+        let parsed = parse_flat_object(&format!("{{\"text\": {}}}", string(text))).unwrap();
+        assert_eq!(parsed["text"], Value::String(text.to_string()));
+    }
+
+    #[test]
+    fn test_parse_flat_object() {
+        let parsed =
+            parse_flat_object(r#" { "version": 1, "config": null, "ok": true, "emoji": "🎉" } "#)
+                .unwrap();
+        assert_eq!(parsed["version"], Value::Number(1.0));
+        assert_eq!(parsed["config"], Value::Null);
+        assert_eq!(parsed["ok"], Value::Bool(true));
+        assert_eq!(parsed["emoji"], Value::String("🎉".to_string()));
+        assert!(parse_flat_object("{}").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid() {
+        assert!(parse_flat_object(r#"{"a": [1]}"#).is_err());
+        assert!(parse_flat_object(r#"{"a": "unterminated}"#).is_err());
+        assert!(parse_flat_object(r#"{"a": 1} trailing"#).is_err());
+        assert!(parse_flat_object(r#"{"a" 1}"#).is_err());
+    }
+}
@@ -0,0 +1,130 @@
+//! Credentials for integrations, kept in the login keychain rather than the
+//! config file. Each is a generic password under the app's identifier, with
+//! the integration's name (e.g. "slack") as the account.
+
+use objc2::rc::Retained;
+use objc2::runtime::AnyObject;
+use objc2_foundation::{NSData, NSDictionary, NSNumber, NSString};
+use std::io::{Error, Result};
+
+use crate::ffi::keychain::{
+    kSecAttrAccount, kSecAttrService, kSecClass, kSecClassGenericPassword, kSecReturnData,
+    kSecValueData, OSStatus, SecItemAdd, SecItemCopyMatching, SecItemDelete, SecItemUpdate,
+    ERR_SEC_ITEM_NOT_FOUND, ERR_SEC_SUCCESS,
+};
+use crate::launchd::ID;
+
+/// The token stored for `name`, if any.
+pub fn get_secret(name: &str) -> Result<Option<String>> {
+    let return_data = NSNumber::new_bool(true);
+    let query = item_query(name, &[(unsafe { kSecReturnData }, return_data.as_ref())]);
+
+    let mut result: *mut AnyObject = std::ptr::null_mut();
+    match unsafe { SecItemCopyMatching(&query, &mut result) } {
+        ERR_SEC_SUCCESS => {
+            // Copied out with a +1 retain count, which Retained takes over
+            let Some(data) = (unsafe { Retained::from_raw(result.cast::<NSData>()) }) else {
+                return Ok(None);
+            };
+            String::from_utf8(data.to_vec())
+                .map(Some)
+                .map_err(|_| Error::other(format!("Keychain item for {} isn't text", name)))
+        }
+        ERR_SEC_ITEM_NOT_FOUND => Ok(None),
+        status => Err(keychain_error("read", name, status)),
+    }
+}
+
+/// Stores `secret` for `name`, replacing any earlier one. The item is updated
+/// in place, so a failure, such as access being denied, keeps the earlier one.
+pub fn set_secret(name: &str, secret: &str) -> Result<()> {
+    let data = NSData::with_bytes(secret.as_bytes());
+    let value: (&NSString, &AnyObject) = (unsafe { kSecValueData }, data.as_ref());
+    let update = NSDictionary::from_slices(&[value.0], &[value.1]);
+    match unsafe { SecItemUpdate(&item_query(name, &[]), &update) } {
+        ERR_SEC_SUCCESS => return Ok(()),
+        ERR_SEC_ITEM_NOT_FOUND => {}
+        status => return Err(keychain_error("save", name, status)),
+    }
+
+    let attributes = item_query(name, &[value]);
+    match unsafe { SecItemAdd(&attributes, std::ptr::null_mut()) } {
+        ERR_SEC_SUCCESS => Ok(()),
+        status => Err(keychain_error("save", name, status)),
+    }
+}
+
+/// Deletes the token for `name`, returning whether there was one.
+pub fn remove_secret(name: &str) -> Result<bool> {
+    match unsafe { SecItemDelete(&item_query(name, &[])) } {
+        ERR_SEC_SUCCESS => Ok(true),
+        ERR_SEC_ITEM_NOT_FOUND => Ok(false),
+        status => Err(keychain_error("remove", name, status)),
+    }
+}
+
+fn item_query(
+    name: &str,
+    extra: &[(&NSString, &AnyObject)],
+) -> Retained<NSDictionary<NSString, AnyObject>> {
+    let service = NSString::from_str(ID);
+    let account = NSString::from_str(name);
+    let mut keys: Vec<&NSString> = unsafe { vec![kSecClass, kSecAttrService, kSecAttrAccount] };
+    let mut values: Vec<&AnyObject> = vec![
+        unsafe { kSecClassGenericPassword }.as_ref(),
+        service.as_ref(),
+        account.as_ref(),
+    ];
+    for (key, value) in extra {
+        keys.push(key);
+        values.push(value);
+    }
+    NSDictionary::from_slices(&keys, &values)
+}
+
+fn keychain_error(action: &str, name: &str, status: OSStatus) -> Error {
+    Error::other(format!(
+        "Failed to {} keychain item for {} (OSStatus {})",
+        action, name, status
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn query_value(query: &NSDictionary<NSString, AnyObject>, key: &NSString) -> Option<String> {
+        let value = query.objectForKey(key)?.downcast::<NSString>().ok()?;
+        Some(value.to_string())
+    }
+
+    #[test]
+    fn test_item_query() {
+        let query = item_query("slack", &[]);
+        assert_eq!(query.count(), 3);
+        assert_eq!(
+            query_value(&query, unsafe { kSecClass }),
+            Some(unsafe { kSecClassGenericPassword }.to_string())
+        );
+        assert_eq!(
+            query_value(&query, unsafe { kSecAttrService }).as_deref(),
+            Some(ID)
+        );
+        assert_eq!(
+            query_value(&query, unsafe { kSecAttrAccount }).as_deref(),
+            Some("slack")
+        );
+    }
+
+    #[test]
+    fn test_item_query_with_extra_attributes() {
+        let return_data = NSNumber::new_bool(true);
+        let query = item_query(
+            "slack",
+            &[(unsafe { kSecReturnData }, return_data.as_ref())],
+        );
+        assert_eq!(query.count(), 4);
+        assert!(query.objectForKey(unsafe { kSecReturnData }).is_some());
+        assert!(query.objectForKey(unsafe { kSecValueData }).is_none());
+    }
+}
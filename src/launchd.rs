@@ -11,6 +11,7 @@ pub const ID: &str = "io.calrichards.eventually";
 pub struct Service {
     pub name: String,
     pub bin_path: PathBuf,
+    pub instance: Option<String>,
 }
 
 impl Service {
@@ -18,9 +19,22 @@ impl Service {
         Ok(Self {
             name: name.to_string(),
             bin_path: std::env::current_exe()?,
+            instance: None,
         })
     }
 
+    /// Service for a named instance, labelled `<ID>.<instance>` so that several
+    /// agents with their own configs can run side by side.
+    pub fn for_instance(instance: Option<&str>) -> Result<Self> {
+        match instance {
+            Some(instance) => Ok(Self {
+                instance: Some(instance.to_string()),
+                ..Self::try_new(&format!("{}.{}", ID, instance))?
+            }),
+            None => Self::try_new(ID),
+        }
+    }
+
     pub fn plist_path(&self) -> Result<PathBuf> {
         let home = std::env::var("HOME")
             .map_err(|_| Error::new(ErrorKind::NotFound, "HOME environment variable not set"))?;
@@ -33,16 +47,34 @@ impl Service {
     pub fn log_path(&self, kind: &str) -> Result<PathBuf> {
         let home = std::env::var("HOME")
             .map_err(|_| Error::new(ErrorKind::NotFound, "HOME environment variable not set"))?;
+        let file_name = match &self.instance {
+            Some(instance) => format!("eventually.{}.{}", instance, kind),
+            None => format!("eventually.{}", kind),
+        };
         Ok(PathBuf::from(format!(
-            "{}/Library/Logs/eventually.{}",
-            home, kind
+            "{}/Library/Logs/{}",
+            home, file_name
         )))
     }
 
+    /// Whether this process is the one launchd started for the service.
+    pub fn is_current_process(&self) -> bool {
+        std::env::var("XPC_SERVICE_NAME").is_ok_and(|name| name == self.name)
+    }
+
     pub fn is_installed(&self) -> bool {
         self.plist_path().map(|p| p.exists()).unwrap_or(false)
     }
 
+    pub fn install_dry_run(&self) -> Result<()> {
+        eprintln!(
+            "would install launch agent to `{}`",
+            self.plist_path()?.display()
+        );
+        print!("{}", self.launchd_plist()?);
+        Ok(())
+    }
+
     pub fn install(&self) -> Result<()> {
         let plist_path = self.plist_path()?;
         if self.is_installed() {
@@ -186,6 +218,14 @@ impl Service {
     }
 
     pub fn launchd_plist(&self) -> Result<String> {
+        let instance_args = match &self.instance {
+            Some(instance) => format!(
+                "\n        <string>--instance</string>\n        <string>{}</string>",
+                instance
+            ),
+            None => String::new(),
+        };
+
         Ok(format!(
             r#"<?xml version="1.0" encoding="UTF-8"?>
 <!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
@@ -195,12 +235,15 @@ impl Service {
     <string>{}</string>
     <key>ProgramArguments</key>
     <array>
-        <string>{}</string>
+        <string>{}</string>{}
     </array>
     <key>RunAtLoad</key>
     <true/>
     <key>KeepAlive</key>
-    <true/>
+    <dict>
+        <key>SuccessfulExit</key>
+        <false/>
+    </dict>
     <key>StandardOutPath</key>
     <string>{}</string>
     <key>StandardErrorPath</key>
@@ -210,6 +253,7 @@ impl Service {
 "#,
             self.name,
             self.bin_path.display(),
+            instance_args,
             self.log_path("log")?.display(),
             self.log_path("err")?.display(),
         ))
@@ -277,11 +321,35 @@ mod tests {
             assert!(plist.contains("<key>ProgramArguments</key>"));
             assert!(plist.contains("<key>RunAtLoad</key>"));
             assert!(plist.contains("<key>KeepAlive</key>"));
+            assert!(plist.contains("<key>SuccessfulExit</key>"));
             assert!(plist.contains("<key>StandardOutPath</key>"));
             assert!(plist.contains("<key>StandardErrorPath</key>"));
         }
     }
 
+    #[test]
+    fn test_service_for_instance() {
+        let service = Service::for_instance(Some("work")).unwrap();
+        assert_eq!(service.name, format!("{}.work", ID));
+        assert_eq!(service.instance.as_deref(), Some("work"));
+
+        let service = Service::for_instance(None).unwrap();
+        assert_eq!(service.name, ID);
+        assert!(service.instance.is_none());
+    }
+
+    #[test]
+    fn test_launchd_plist_instance() {
+        let service = Service::for_instance(Some("work")).unwrap();
+
+        if env::var("HOME").is_ok() {
+            let plist = service.launchd_plist().unwrap();
+            assert!(plist.contains("<string>--instance</string>"));
+            assert!(plist.contains("<string>work</string>"));
+            assert!(plist.contains("eventually.work.log"));
+        }
+    }
+
     #[test]
     fn test_plist_path_no_home() {
         env::remove_var("HOME");
@@ -1,3 +1,10 @@
 pub mod calendar;
+pub mod clock;
+pub mod config;
 pub mod ffi;
+pub mod json;
+pub mod keychain;
 pub mod launchd;
+pub mod local_notes;
+pub mod state;
+pub mod watch;
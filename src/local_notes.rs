@@ -0,0 +1,208 @@
+use chrono::{DateTime, Duration, Local};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::{ErrorKind, Result};
+use std::{fs, path::PathBuf};
+
+use crate::calendar::{EventInfo, OccurrenceRef};
+
+/// Notes are kept this long after their event ends, then dropped the next
+/// time one is set.
+const KEEP_AFTER_END_DAYS: i64 = 30;
+
+/// Notes attached to events from the menu ("bring charger"), keyed by
+/// occurrence, and the events marked important. They stay on this Mac and
+/// are never written to the calendar.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LocalNotes {
+    /// Where `save` writes to; unset for notes that aren't backed by a file
+    #[serde(skip)]
+    path: Option<PathBuf>,
+    #[serde(default)]
+    notes: BTreeMap<OccurrenceRef, LocalNote>,
+    /// Event IDs, which recurring events share, so marking one occurrence
+    /// marks the series
+    #[serde(default)]
+    important: BTreeSet<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct LocalNote {
+    text: String,
+    /// Unix timestamp of the event's end, for dropping old notes
+    ends: i64,
+}
+
+impl LocalNotes {
+    /// `config.notes.toml` next to the config file, `<instance>.notes.toml`
+    /// for named instances.
+    pub fn path(instance: Option<&str>) -> Option<PathBuf> {
+        let home = std::env::var("HOME").ok()?;
+        Some(PathBuf::from(format!(
+            "{}/.config/eventually/{}.notes.toml",
+            home,
+            instance.unwrap_or("config")
+        )))
+    }
+
+    /// Loads the saved notes, starting over when the file is missing or invalid.
+    pub fn load(instance: Option<&str>) -> Self {
+        let Some(path) = Self::path(instance) else {
+            return Self::default();
+        };
+
+        let notes = match fs::read_to_string(&path) {
+            Ok(contents) => toml::from_str(&contents).unwrap_or_else(|e| {
+                eprintln!("Error: Failed to parse `{}`: {}", path.display(), e);
+                Self::default()
+            }),
+            Err(e) if e.kind() == ErrorKind::NotFound => Self::default(),
+            Err(e) => {
+                eprintln!("Error: Failed to read `{}`: {}", path.display(), e);
+                Self::default()
+            }
+        };
+        Self {
+            path: Some(path),
+            ..notes
+        }
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let contents = toml::to_string(self).map_err(std::io::Error::other)?;
+        fs::write(path, contents)
+    }
+
+    pub fn get(&self, occurrence: &OccurrenceRef) -> Option<&str> {
+        self.notes.get(occurrence).map(|note| note.text.as_str())
+    }
+
+    pub fn is_important(&self, event: &EventInfo) -> bool {
+        self.important.contains(&event.event_id)
+    }
+
+    pub fn set_important(&mut self, event: &EventInfo, important: bool) {
+        if important {
+            self.important.insert(event.event_id.clone());
+        } else {
+            self.important.remove(&event.event_id);
+        }
+    }
+
+    /// Attaches `text` to `event`, or removes its note when `text` is blank.
+    /// Also forgets notes on events that ended a while before `now`.
+    pub fn set(&mut self, event: &EventInfo, text: &str, now: DateTime<Local>) {
+        let text = text.trim();
+        if text.is_empty() {
+            self.notes.remove(&event.occurrence);
+        } else {
+            self.notes.insert(
+                event.occurrence.clone(),
+                LocalNote {
+                    text: text.to_string(),
+                    ends: event.end.timestamp(),
+                },
+            );
+        }
+
+        let cutoff = (now - Duration::days(KEEP_AFTER_END_DAYS)).timestamp();
+        self.notes.retain(|_, note| note.ends >= cutoff);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendar::parse_local_time;
+
+    fn event(key: &str, end: &str) -> EventInfo {
+        let end = parse_local_time(end).unwrap();
+        EventInfo {
+            title: key.to_string(),
+            start: end - Duration::hours(1),
+            end,
+            event_id: key.to_string(),
+            occurrence: OccurrenceRef::new(key, end.timestamp()),
+            has_recurrence: false,
+            location: None,
+            calendar_color: (0.5, 0.5, 0.5),
+            is_free: false,
+            is_accepted: true,
+            other_attendees: 0,
+            notes: None,
+            url: None,
+            is_own: false,
+            calendar_name: String::new(),
+            calendar_id: String::new(),
+            is_subscribed: false,
+            rooms: Vec::new(),
+            google_url: None,
+        }
+    }
+
+    #[test]
+    fn test_set_and_remove() {
+        let now = parse_local_time("2025-03-07 09:00").unwrap();
+        let standup = event("standup", "2025-03-07 09:15");
+        let mut notes = LocalNotes::default();
+
+        notes.set(&standup, "  bring charger ", now);
+        assert_eq!(notes.get(&standup.occurrence), Some("bring charger"));
+
+        let saved = toml::to_string(&notes).unwrap();
+        assert_eq!(toml::from_str::<LocalNotes>(&saved).unwrap(), notes);
+
+        notes.set(&standup, "", now);
+        assert_eq!(notes.get(&standup.occurrence), None);
+    }
+
+    #[test]
+    fn test_important_marks_series() {
+        let monday = event("review", "2025-03-03 11:00");
+        let friday = EventInfo {
+            occurrence: OccurrenceRef::new("review", 1741341600),
+            ..monday.clone()
+        };
+        let mut notes = LocalNotes::default();
+
+        notes.set_important(&monday, true);
+        assert!(notes.is_important(&friday));
+
+        let saved = toml::to_string(&notes).unwrap();
+        assert_eq!(toml::from_str::<LocalNotes>(&saved).unwrap(), notes);
+
+        notes.set_important(&friday, false);
+        assert!(!notes.is_important(&monday));
+    }
+
+    #[test]
+    fn test_load_legacy_keys() {
+        let notes: LocalNotes = toml::from_str(
+            "[notes.\"standup|||1741338000\"]\ntext = \"bring charger\"\nends = 0\n",
+        )
+        .unwrap();
+        assert_eq!(
+            notes.get(&OccurrenceRef::new("standup", 1741338000)),
+            Some("bring charger")
+        );
+    }
+
+    #[test]
+    fn test_old_notes_are_dropped() {
+        let now = parse_local_time("2025-03-07 09:00").unwrap();
+        let old = event("old", "2025-01-20 10:00");
+        let recent = event("recent", "2025-03-01 10:00");
+        let mut notes = LocalNotes::default();
+        notes.set(&old, "prep doc", now);
+        notes.set(&recent, "prep doc", now);
+
+        assert_eq!(notes.get(&old.occurrence), None);
+        assert_eq!(notes.get(&recent.occurrence), Some("prep doc"));
+    }
+}
@@ -1,25 +1,40 @@
 mod args;
+mod backoff;
 mod calendar;
+mod clock;
+mod config;
 mod event_observers;
 mod ffi;
+mod json;
+mod keychain;
 mod launchd;
+mod local_notes;
+mod media;
 mod menu;
+mod modes;
+mod quiet;
+mod state;
+mod watch;
 
 use args::Cli;
-use calendar::EventCollection;
+use calendar::Icon;
+use clap::Parser;
 use event_observers::SystemNotificationObserver;
-use menu::{MenuBuilder, MenuDelegate};
+use local_notes::LocalNotes;
+use menu::MenuDelegate;
 use objc2_app_kit::{
     NSApplication, NSApplicationActivationPolicy, NSStatusBar, NSVariableStatusItemLength,
 };
 use objc2_foundation::{MainThreadMarker, NSString};
 use std::collections::HashSet;
 use std::sync::{Arc, Mutex};
+use watch::WatchSocket;
 
 fn main() {
     use crate::ffi::event_kit;
 
-    match Cli::parse_and_execute() {
+    let cli = Cli::parse();
+    match cli.execute() {
         Some(Ok(())) => return,
         Some(Err(e)) => {
             eprintln!("Command failed: {e}");
@@ -28,6 +43,33 @@ fn main() {
         None => {}
     }
 
+    // One copy per instance, holding a lock next to the watch socket. Run by
+    // hand while the launch agent is up, the second copy opens the first one's
+    // menu; started by the agent while a copy run by hand is up, it exits
+    // successfully, which launchd doesn't restart.
+    let watch_path = WatchSocket::path(cli.instance.as_deref());
+    let _instance_lock = match watch_path.as_deref().map(WatchSocket::lock) {
+        Some(Ok(Some(lock))) => Some(lock),
+        Some(Ok(None)) => {
+            let pid = watch_path.as_deref().and_then(WatchSocket::running_pid);
+            match pid {
+                Some(pid) => eprintln!("eventually is already running (pid {})", pid),
+                None => eprintln!("eventually is already running"),
+            }
+            let is_agent = launchd::Service::for_instance(cli.instance.as_deref())
+                .is_ok_and(|service| service.is_current_process());
+            if let Some(pid) = pid.filter(|_| !is_agent) {
+                unsafe { libc::kill(pid, libc::SIGUSR1) };
+            }
+            return;
+        }
+        Some(Err(e)) => {
+            eprintln!("Error: Failed to lock the instance: {}", e);
+            None
+        }
+        None => None,
+    };
+
     let mtm = match MainThreadMarker::new() {
         Some(mtm) => mtm,
         None => {
@@ -39,43 +81,76 @@ fn main() {
     let app = NSApplication::sharedApplication(mtm);
     app.setActivationPolicy(NSApplicationActivationPolicy::Accessory);
 
+    let config = cli.load_config();
+
+    let status_item =
+        NSStatusBar::systemStatusBar().statusItemWithLength(NSVariableStatusItemLength);
+
+    let Some(button) = status_item.button(mtm) else {
+        eprintln!("Error: Status item button is unavailable");
+        std::process::exit(1);
+    };
+
     let event_store = event_kit::init_event_store(mtm);
     if let Err(e) = calendar::request_access(&event_store) {
         eprintln!("Error: Calendar access required but denied - {}", e);
         eprintln!("Please grant calendar access in:");
         eprintln!("  System Settings > Privacy & Security > Calendars");
-        std::process::exit(1);
+
+        // Stay in the menu bar rather than exiting, which would have the launch
+        // agent restart us in a loop
+        button.setTitle(&NSString::from_str(&config.text.access_denied));
+        if config.text.access_denied.is_empty() {
+            button.setImage(Icon::CircleX.load().as_deref());
+        }
+        status_item.setMenu(Some(&menu::access_denied_menu(mtm)));
+        app.run();
+        return;
     }
 
-    let events = EventCollection::fetch(&event_store);
     let dismissed_events = Arc::new(Mutex::new(HashSet::new()));
 
-    let status_item =
-        NSStatusBar::systemStatusBar().statusItemWithLength(NSVariableStatusItemLength);
+    let notes = LocalNotes::load(cli.instance.as_deref());
 
-    if let Some(button) = status_item.button(mtm) {
-        let title = match dismissed_events.lock() {
-            Ok(dismissed_set) => events.get_title(&dismissed_set),
-            Err(e) => {
-                eprintln!("Error: Failed to acquire lock on dismissed events: {}", e);
-                "Calendar".to_string()
-            }
-        };
-        button.setTitle(&NSString::from_str(&title));
-    } else {
-        eprintln!("Error: Status item button is unavailable");
-        std::process::exit(1);
-    }
+    let watch = watch_path.and_then(|path| {
+        WatchSocket::bind(path)
+            .map_err(|e| eprintln!("Error: Failed to open the watch socket: {}", e))
+            .ok()
+    });
 
     let delegate = MenuDelegate::new(
         mtm,
-        dismissed_events.clone(),
-        event_store.clone(),
-        status_item.clone(),
+        dismissed_events,
+        event_store,
+        status_item,
+        config,
+        notes,
+        watch,
     );
+    match cli.load_fixture() {
+        Ok(fixture) if cli.at.is_some() || fixture.is_some() => delegate.simulate(cli.at, fixture),
+        Ok(_) => delegate.refresh_menu(),
+        Err(e) => {
+            eprintln!("Error: Failed to load fixture: {e}");
+            std::process::exit(1);
+        }
+    }
+
+    let _sighup = ffi::dispatch::observe_signal(libc::SIGHUP, {
+        let delegate = delegate.clone();
+        move || delegate.reload_config(cli.load_config())
+    });
+
+    let _sigusr1 = ffi::dispatch::observe_signal(libc::SIGUSR1, {
+        let delegate = delegate.clone();
+        move || delegate.show_menu()
+    });
 
-    let menu = MenuBuilder::new(events.into_vec(), &delegate, &dismissed_events, mtm).build();
-    status_item.setMenu(Some(&menu));
+    // Calendar syncs tend to land right after the network comes back
+    let _network = ffi::network::observe_reachability({
+        let delegate = delegate.clone();
+        move || delegate.refresh_menu()
+    });
 
     SystemNotificationObserver::new(&delegate).register();
 
@@ -0,0 +1,54 @@
+//! Pausing music players as a call starts, by AppleScript. The first time,
+//! macOS asks whether eventually may control each player.
+
+use std::process::Command;
+use std::thread;
+
+/// Players told to pause, by application name.
+const PLAYERS: [&str; 2] = ["Music", "Spotify"];
+
+/// Lines of AppleScript pausing each of `players` that's running, without
+/// launching the others.
+fn pause_script(players: &[&str]) -> Vec<String> {
+    players
+        .iter()
+        .map(|player| {
+            format!(
+                "if application \"{0}\" is running then tell application \"{0}\" to pause",
+                player
+            )
+        })
+        .collect()
+}
+
+/// Pauses whatever Music or Spotify is playing. Runs in the background, as
+/// players can take a moment to answer.
+pub fn pause_players() {
+    let mut command = Command::new("osascript");
+    for line in pause_script(&PLAYERS) {
+        command.arg("-e").arg(line);
+    }
+    thread::spawn(move || match command.output() {
+        Ok(output) if !output.status.success() => eprintln!(
+            "Error: Failed to pause media: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ),
+        Ok(_) => {}
+        Err(e) => eprintln!("Error: Failed to execute osascript: {}", e),
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pause_script() {
+        assert_eq!(
+            pause_script(&["Spotify"]),
+            vec![
+                "if application \"Spotify\" is running then tell application \"Spotify\" to pause"
+            ]
+        );
+    }
+}
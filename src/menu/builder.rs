@@ -1,299 +1,989 @@
-use chrono::{Duration, Local};
+use chrono::{DateTime, Duration, Local};
 use objc2::rc::Retained;
-use objc2_app_kit::{NSColor, NSMenu, NSMenuItem};
-use objc2_foundation::{ns_string, MainThreadMarker, NSRange, NSString};
+use objc2_app_kit::NSMenu;
+use objc2_foundation::{ns_string, MainThreadMarker};
+use std::cell::RefCell;
 use std::collections::HashSet;
 use std::sync::{Arc, Mutex};
 
 use crate::calendar::{
-    extract_url, format_time, is_all_day, EventInfo, EventStatus, Icon, ServiceInfo,
+    format_duration, format_time, format_total_duration, is_all_day, link_label, listed_in_day,
+    EventCollection, EventInfo, EventStatus, FocusBlock, Icon, MeetingMedium, OccurrenceRef,
+    PhoneJoin, ServiceInfo,
 };
-use crate::ffi::app_kit;
-
-use super::delegate::MenuDelegate;
-use super::text;
-
+use crate::clock::{Clock, SystemClock};
+use crate::config::{Config, CurrentCountdown};
+use crate::ffi::{app_kit, event_kit, foundation};
+use crate::local_notes::LocalNotes;
+use crate::modes::Modes;
+use crate::quiet::{self, QUIET_DURATIONS};
+
+use super::days::{day_groups, DayGroup};
+use super::formatting::TextRole;
+use super::health::FetchHealth;
+use super::model::{Action, Image, MenuModel, Row, StyledText};
+use super::render::MenuRenderer;
+use super::report::ProblemReport;
+
+/// Rows of meetings starting this soon get an inline "Join".
+pub(super) const JOIN_SOON_MINUTES: i64 = 10;
+
+/// Lays out the status item's menu as a `MenuModel`, leaving drawing it to
+/// `MenuRenderer`.
 pub struct MenuBuilder<'a> {
-    events: Vec<EventInfo>,
-    delegate: &'a MenuDelegate,
-    dismissed: &'a Arc<Mutex<HashSet<String>>>,
-    mtm: MainThreadMarker,
+    events: EventCollection,
+    dismissed: &'a Arc<Mutex<HashSet<OccurrenceRef>>>,
+    modes: Modes,
+    config: &'a Config,
+    joined: Option<&'a OccurrenceRef>,
+    clock: &'a dyn Clock,
+    health: Option<&'a FetchHealth>,
+    notes: Option<&'a LocalNotes>,
 }
 
 impl<'a> MenuBuilder<'a> {
     pub fn new(
-        events: Vec<EventInfo>,
-        delegate: &'a MenuDelegate,
-        dismissed: &'a Arc<Mutex<HashSet<String>>>,
-        mtm: MainThreadMarker,
+        events: EventCollection,
+        dismissed: &'a Arc<Mutex<HashSet<OccurrenceRef>>>,
+        modes: Modes,
+        config: &'a Config,
     ) -> Self {
         Self {
             events,
-            delegate,
             dismissed,
-            mtm,
+            modes,
+            config,
+            joined: None,
+            clock: &SystemClock,
+            health: None,
+            notes: None,
         }
     }
 
-    pub fn build(self) -> Retained<NSMenu> {
-        let menu = app_kit::init_menu(self.mtm, ns_string!(""));
+    /// The meeting last joined from eventually.
+    pub fn joined(mut self, occurrence: Option<&'a OccurrenceRef>) -> Self {
+        self.joined = occurrence;
+        self
+    }
+
+    /// Builds the menu as of `clock`'s time rather than the wall clock's.
+    pub fn clock(mut self, clock: &'a dyn Clock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// How the events were fetched, shown in a footer above Quit.
+    pub fn health(mut self, health: &'a FetchHealth) -> Self {
+        self.health = Some(health);
+        self
+    }
+
+    /// Notes attached to events from the menu.
+    pub fn notes(mut self, notes: &'a LocalNotes) -> Self {
+        self.notes = Some(notes);
+        self
+    }
+
+    pub fn build(&self) -> MenuModel {
+        let mut menu = MenuModel::new();
 
-        let collection = crate::calendar::EventCollection::from(self.events.clone());
         let current_or_next: Option<EventStatus> = match self.dismissed.lock() {
-            Ok(dismissed_set) => collection.find_cur_or_next(&dismissed_set),
+            Ok(dismissed_set) => {
+                self.events
+                    .find_cur_or_next(self.clock, &dismissed_set, self.config)
+            }
             Err(e) => {
                 eprintln!("Error: Failed to acquire lock in build_menu: {}", e);
                 None
             }
         };
 
+        if let Some(problem) = self.health.and_then(FetchHealth::problem) {
+            self.add_problem_banner(&mut menu, &problem);
+            menu.separate();
+        }
+
         if let Some(ref event_status) = current_or_next {
-            self.add_quick_actions(&menu, event_status);
-            menu.addItem(&NSMenuItem::separatorItem(self.mtm));
+            self.add_quick_actions(&mut menu, event_status);
+            menu.separate();
         }
 
         if self.events.is_empty() {
-            self.add_empty_state(&menu);
+            self.add_empty_state(&mut menu);
         } else {
-            self.add_event_groups(&menu, &current_or_next);
+            self.add_event_groups(&mut menu, &current_or_next);
         }
 
-        self.add_quit_item(&menu);
+        self.add_dismissed_menu(&mut menu);
+        self.add_focus_menu(&mut menu);
+        self.add_agenda_item(&mut menu);
+        self.add_mode_items(&mut menu);
+        self.add_settings_menu(&mut menu);
+        if let Some(health) = self.health {
+            self.add_health_footer(&mut menu, health);
+        }
+        menu.push(quit_row());
         menu
     }
 
-    fn add_quick_actions(&self, menu: &NSMenu, event_status: &EventStatus) {
+    fn add_quick_actions(&self, menu: &mut MenuModel, event_status: &EventStatus) {
         let event = event_status.event();
-        if let Some(url) = extract_url(event.location.as_deref()) {
-            self.add_join_video_item(menu, url);
+        if let Some(url) = event.join_url(&self.config.join.prefer) {
+            menu.push(join_video_row(url));
         }
-        self.add_open_calendar_item(menu, event);
-        self.add_dismiss_item(menu, event);
+        let passcode = PhoneJoin::from_event(event.location.as_deref(), event.notes.as_deref())
+            .and_then(|join| join.passcode);
+        if let Some(passcode) = passcode {
+            menu.push(copy_row("Copy Passcode", &passcode));
+        }
+        menu.push(self.open_calendar_row(event));
+        self.add_prep_link_items(menu, event);
+        menu.push(self.note_row(event));
+        menu.push(self.important_row(event));
+        menu.push(
+            Row::new("Dismiss Event")
+                .action(Action::DismissEvent)
+                .value(event.occurrence.to_token())
+                .image(Image::Icon(Icon::CircleX)),
+        );
     }
 
-    fn add_join_video_item(&self, menu: &NSMenu, url: &str) {
-        let service_info = ServiceInfo::from_url(url);
-        let join_title = format!("Join {} Event", service_info.name());
-        let join_item = app_kit::init_menu_item(
-            self.mtm,
-            &NSString::from_str(&join_title),
-            Some(objc2::sel!(openURL:)),
-            ns_string!(""),
-        );
-        if let Some(icon) = service_info.icon().load() {
-            join_item.setImage(Some(&icon));
+    fn note(&self, event: &EventInfo) -> Option<&'a str> {
+        self.notes?.get(&event.occurrence)
+    }
+
+    fn is_important(&self, event: &EventInfo) -> bool {
+        self.notes.is_some_and(|notes| notes.is_important(event))
+    }
+
+    /// `[[prep-links]]` matching the event's title, opened like its other links.
+    fn add_prep_link_items(&self, menu: &mut MenuModel, event: &EventInfo) {
+        for link in self.config.prep_links_for(&event.title) {
+            let title = link.label.clone().unwrap_or_else(|| link_label(&link.url));
+            menu.push(
+                Row::new(title)
+                    .action(Action::OpenUrl)
+                    .value(link.url.clone())
+                    .image(Image::Symbol("doc.text", "Preparation")),
+            );
         }
-        app_kit::set_menu_item_target(&join_item, Some(self.delegate));
-        app_kit::set_menu_item_represented_object(&join_item, Some(&*NSString::from_str(url)));
-        menu.addItem(&join_item);
     }
 
-    fn add_open_calendar_item(&self, menu: &NSMenu, event: &EventInfo) {
-        let calendar_item = app_kit::init_menu_item(
-            self.mtm,
-            ns_string!("Open in Calendar"),
-            Some(objc2::sel!(openEvent:)),
-            ns_string!(""),
-        );
-        if let Some(icon) = Icon::Calendar.load() {
-            calendar_item.setImage(Some(&icon));
-        }
-        app_kit::set_menu_item_target(&calendar_item, Some(self.delegate));
-        let open_data = format!("{}|||{}", event.event_id, event.has_recurrence);
-        app_kit::set_menu_item_represented_object(
-            &calendar_item,
-            Some(&*NSString::from_str(&open_data)),
-        );
-        menu.addItem(&calendar_item);
+    /// Checked while the event's series is marked important.
+    fn important_row(&self, event: &EventInfo) -> Row {
+        Row::new("Important")
+            .action(Action::ToggleImportant)
+            .value(event.occurrence.to_token())
+            .checked(self.is_important(event))
     }
 
-    fn add_dismiss_item(&self, menu: &NSMenu, event: &EventInfo) {
-        let dismiss_item = app_kit::init_menu_item(
-            self.mtm,
-            ns_string!("Dismiss Event"),
-            Some(objc2::sel!(dismissEvent:)),
-            ns_string!(""),
-        );
-        if let Some(icon) = Icon::CircleX.load() {
-            dismiss_item.setImage(Some(&icon));
+    fn note_row(&self, event: &EventInfo) -> Row {
+        let title = if self.note(event).is_some() {
+            "Edit Note…"
+        } else {
+            "Add Note…"
+        };
+        Row::new(title)
+            .action(Action::EditNote)
+            .value(event.occurrence.to_token())
+    }
+
+    /// Google-synced events open on the web when configured, as a link would;
+    /// the rest go to Calendar.app.
+    fn open_event_action(&self, event: &EventInfo) -> (Action, String) {
+        match &event.google_url {
+            Some(url) if self.config.google_calendar_web => (Action::OpenUrl, url.clone()),
+            _ => (Action::OpenEvent, event.occurrence.to_token()),
         }
-        app_kit::set_menu_item_target(&dismiss_item, Some(self.delegate));
-        app_kit::set_menu_item_represented_object(
-            &dismiss_item,
-            Some(&*NSString::from_str(&event.occurrence_key)),
-        );
-        menu.addItem(&dismiss_item);
     }
 
-    fn add_empty_state(&self, menu: &NSMenu) {
-        let item = app_kit::init_menu_item(self.mtm, ns_string!("No events"), None, ns_string!(""));
-        item.setEnabled(false);
-        menu.addItem(&item);
+    fn open_calendar_row(&self, event: &EventInfo) -> Row {
+        let (action, open_data) = self.open_event_action(event);
+        Row::new("Open in Calendar")
+            .action(action)
+            .value(open_data)
+            .image(Image::Icon(Icon::Calendar))
+    }
+
+    fn add_empty_state(&self, menu: &mut MenuModel) {
+        menu.push(Row::new(self.config.text.no_events.as_str()).disabled());
     }
 
-    fn add_event_groups(&self, menu: &NSMenu, current_or_next: &Option<EventStatus>) {
-        let now = Local::now();
+    fn add_event_groups(&self, menu: &mut MenuModel, current_or_next: &Option<EventStatus>) {
+        let now = self.clock.now();
         let today = now.date_naive();
-        let tomorrow = today + Duration::days(1);
-        let day_after_tomorrow = today + Duration::days(2);
-        let three_days_out = today + Duration::days(3);
-
-        let groups = [
-            (
-                today,
-                "Today".to_string(),
-                format!("{} {}", today.format("%d"), today.format("%b")),
-            ),
-            (
-                tomorrow,
-                "Tomorrow".to_string(),
-                format!("{} {}", tomorrow.format("%d"), tomorrow.format("%b")),
-            ),
-            (
-                day_after_tomorrow,
-                day_after_tomorrow.format("%A").to_string(),
-                format!(
-                    "{} {}",
-                    day_after_tomorrow.format("%d"),
-                    day_after_tomorrow.format("%b")
-                ),
-            ),
-            (
-                three_days_out,
-                three_days_out.format("%A").to_string(),
-                format!(
-                    "{} {}",
-                    three_days_out.format("%d"),
-                    three_days_out.format("%b")
-                ),
-            ),
-        ];
-
-        for (date, day_name, date_str) in &groups {
-            let day_events: Vec<_> = self
+        let groups = day_groups(today, self.config.menu.horizon());
+
+        let mut listed = 0;
+        for group in &groups {
+            let with_day = group.spans_days();
+            let (muted, day_events): (Vec<_>, Vec<_>) = self
                 .events
                 .iter()
-                .filter(|e| e.start.date_naive() == *date)
-                .collect();
+                .filter(|e| group.contains(e.start.date_naive()))
+                .partition(|e| e.is_muted(&self.config.calendars));
+            let (banners, day_events): (Vec<_>, Vec<_>) = day_events
+                .into_iter()
+                .partition(|e| e.is_ooo_banner(&self.config.banners));
+
+            if !day_events.is_empty() || !banners.is_empty() || !muted.is_empty() {
+                let unmuted: Vec<_> = self
+                    .events
+                    .iter()
+                    .filter(|e| group.contains(e.start.date_naive()))
+                    .filter(|e| !e.is_muted(&self.config.calendars))
+                    .collect();
+                menu.push(self.day_header_row(group, &unmuted));
+                if group.first == today {
+                    self.add_meeting_time_item(menu, &day_events);
+                }
+                if !banners.is_empty() {
+                    self.add_collapsed_menu(
+                        menu,
+                        "Out of office",
+                        &banners,
+                        current_or_next,
+                        now,
+                        with_day,
+                    );
+                }
+                let mut calendars: Vec<&str> = Vec::new();
+                for event in &muted {
+                    if !calendars.contains(&event.calendar_name.as_str()) {
+                        calendars.push(&event.calendar_name);
+                    }
+                }
+                for calendar in calendars {
+                    let events: Vec<_> = muted
+                        .iter()
+                        .copied()
+                        .filter(|e| e.calendar_name == calendar)
+                        .collect();
+                    self.add_collapsed_menu(
+                        menu,
+                        calendar,
+                        &events,
+                        current_or_next,
+                        now,
+                        with_day,
+                    );
+                }
 
-            if !day_events.is_empty() {
-                self.add_day_header(menu, day_name, date_str);
+                let day_listed = listed_in_day(day_events.len(), listed);
+                for event in &day_events[..day_listed] {
+                    self.add_event_item(menu, event, current_or_next, now, with_day);
+                }
+                listed += day_listed;
 
-                for event in day_events {
-                    self.add_event_item(menu, event, current_or_next, now);
+                let hidden = day_events.len() - day_listed;
+                if hidden > 0 {
+                    menu.push(Row::new(format!("…and {} more", hidden)).disabled());
                 }
 
-                menu.addItem(&NSMenuItem::separatorItem(self.mtm));
+                menu.separate();
             }
         }
     }
 
-    fn add_day_header(&self, menu: &NSMenu, day_name: &str, date_str: &str) {
-        let header_text = format!("{}, {}", day_name, date_str);
-        let attr_string = text::AttributedString::new(&header_text);
+    /// A single "<title> (N)" row holding the events in a submenu.
+    fn add_collapsed_menu(
+        &self,
+        menu: &mut MenuModel,
+        title: &str,
+        events: &[&EventInfo],
+        current_or_next: &Option<EventStatus>,
+        now: chrono::DateTime<Local>,
+        with_day: bool,
+    ) {
+        let mut submenu = MenuModel::new();
+        for event in events {
+            self.add_event_item(&mut submenu, event, current_or_next, now, with_day);
+        }
+        menu.push(Row::new(format!("{} ({})", title, events.len())).submenu(submenu));
+    }
 
-        let day_name_ns = NSString::from_str(day_name);
-        let day_name_range = NSRange::new(0, day_name_ns.length());
-        attr_string.apply_bold(day_name_range);
+    fn add_meeting_time_item(&self, menu: &mut MenuModel, day_events: &[&EventInfo]) {
+        let total = day_events
+            .iter()
+            .filter(|e| e.is_meeting())
+            .fold(Duration::zero(), |total, e| {
+                total + e.end.signed_duration_since(e.start)
+            });
+        if total <= Duration::zero() {
+            return;
+        }
+        menu.push(Row::new(format!("{} in meetings", format_total_duration(total))).disabled());
+    }
 
-        let header_item = app_kit::init_menu_item(self.mtm, ns_string!(""), None, ns_string!(""));
-        app_kit::set_attributed_title(&header_item, attr_string.as_objc());
-        header_item.setEnabled(false);
-        menu.addItem(&header_item);
+    /// "Today, 07 Mar", with a submenu acting on all of the day's events
+    /// except those on muted calendars.
+    fn day_header_row(&self, group: &DayGroup, events: &[&EventInfo]) -> Row {
+        let mut title = StyledText::new(format!("{}, {}", group.name, group.dates));
+        title.bold(0..group.name.len());
+
+        let row = Row::new(title);
+        if events.is_empty() {
+            row.disabled()
+        } else {
+            row.submenu(self.day_actions_menu(group, events))
+        }
+    }
+
+    fn day_actions_menu(&self, group: &DayGroup, events: &[&EventInfo]) -> MenuModel {
+        let mut submenu = MenuModel::new();
+
+        // Tokens never contain newlines
+        let tokens: Vec<String> = events.iter().map(|e| e.occurrence.to_token()).collect();
+        submenu.push(
+            Row::new("Dismiss All")
+                .action(Action::DismissEvents)
+                .value(tokens.join("\n"))
+                .image(Image::Icon(Icon::CircleX)),
+        );
+        submenu.push(copy_row("Copy Day as Markdown", &group.markdown(events)));
+        submenu.push(
+            Row::new("Open Day in Calendar")
+                .action(Action::OpenDay)
+                .value(group.first.to_string())
+                .image(Image::Icon(Icon::Calendar)),
+        );
+
+        submenu
     }
 
     fn add_event_item(
         &self,
-        menu: &NSMenu,
+        menu: &mut MenuModel,
         event: &EventInfo,
         current_or_next: &Option<EventStatus>,
         now: chrono::DateTime<Local>,
+        with_day: bool,
     ) {
         let is_dismissed = self
             .dismissed
             .lock()
-            .map(|set| set.contains(&event.occurrence_key))
+            .map(|set| set.contains(&event.occurrence))
             .unwrap_or_else(|e| {
                 eprintln!("Error: Failed to check if event is dismissed: {}", e);
                 false
             });
         let is_all_day = is_all_day(&event.start, &event.end);
+        let item_title = row_title(event, with_day);
+
+        // Personal blocks are listed plainly even when they're up next
+        let is_current_or_next = event.needs_join_urgency()
+            && current_or_next
+                .as_ref()
+                .map(|status| status.event().occurrence == event.occurrence)
+                .unwrap_or(false);
+        // Muted calendars share the dimmed style of past events
+        let is_past = event.end < now || is_dismissed || event.is_muted(&self.config.calendars);
+
+        let join_url = join_soon_url(event, now, &self.config.join.prefer);
+        let note = self.note(event);
+        let is_important = self.is_important(event);
+
+        let mut title = style_event_title(
+            item_title,
+            event,
+            is_all_day,
+            is_current_or_next,
+            is_important,
+            is_past,
+        );
+        if join_url.is_some() {
+            title.append(" Join", TextRole::Urgent);
+        }
+        if note.is_some() {
+            title.symbol("note.text", "Has a note");
+        }
 
-        let time_prefix = if is_all_day {
-            "All day:".to_string()
-        } else {
-            let start_time = format_time(&event.start);
-            let end_time = format_time(&event.end);
-            format!("{} - {}", start_time, end_time)
+        // Clicking a meeting about to start joins it rather than opening it
+        let (action, open_data) = match join_url {
+            Some(url) => (Action::OpenUrl, url.to_string()),
+            None => self.open_event_action(event),
         };
 
-        let item_title = format!("{} {}", time_prefix, event.title);
-        let attr_string = text::AttributedString::new(&item_title);
+        // Video calls show their service's icon in place of the calendar dot
+        let image = match event.medium(&self.config.join.prefer) {
+            MeetingMedium::Video(service) => Image::Icon(service.icon()),
+            _ => Image::Dot(event.calendar_color),
+        };
 
-        let is_current_or_next = current_or_next
+        // The row owning the status title gets a checkmark, and the joined
+        // meeting a video symbol in its place
+        let owns_title = current_or_next
             .as_ref()
-            .map(|status| status.event().occurrence_key == event.occurrence_key)
-            .unwrap_or(false);
+            .is_some_and(|status| status.event().occurrence == event.occurrence);
+        let is_joined = self.joined == Some(&event.occurrence);
+
+        let mut row = Row::new(title)
+            .action(action)
+            .value(open_data)
+            .image(image)
+            .checked(owns_title || is_joined);
+        if is_joined {
+            row = row.checked_image(Image::Symbol("video.fill", "Joined"));
+        }
 
-        if is_current_or_next {
-            let full_range = NSRange::new(0, NSString::from_str(&item_title).length());
-            attr_string.apply_bold(full_range);
+        if let Some(size) = event.meeting_size() {
+            row = row.tool_tip(size.label(event.other_attendees));
         }
 
-        if !is_all_day {
-            let start_time_len = format_time(&event.start).chars().count();
-            let dash_and_end_start = start_time_len + 1;
-            let end_time_with_dash_len = 2 + format_time(&event.end).chars().count();
-            let end_time_range = NSRange::new(dash_and_end_start, end_time_with_dash_len);
-            attr_string.apply_secondary_color(end_time_range);
+        let join = PhoneJoin::from_event(event.location.as_deref(), event.notes.as_deref());
+        let links = event.links();
+        // The meeting link alone is already covered by the Join quick action
+        let join_link = event.join_url(&self.config.join.prefer);
+        let has_extra_links = links.iter().any(|link| Some(*link) != join_link);
+        let can_follow_up = event.end < now && event.other_attendees > 0 && !is_all_day;
+        let has_prep_links = !self.config.prep_links_for(&event.title).is_empty();
+        if join.is_some()
+            || has_extra_links
+            || has_prep_links
+            || can_follow_up
+            || note.is_some()
+            || is_important
+        {
+            row = row.submenu(self.event_details_menu(
+                event,
+                join_url,
+                join.as_ref(),
+                &links,
+                can_follow_up,
+            ));
+        }
+
+        menu.push(row);
+
+        // Holding Option swaps the row for one copying what a bug report needs
+        menu.push(
+            copy_row(
+                &format!("Copy Debug Info: {}", event.title),
+                &event.debug_info(),
+            )
+            .alternate(),
+        );
+    }
+
+    /// Rows with a submenu no longer open on click, so it leads with what a
+    /// click would have done: joining a meeting about to start, or the calendar
+    /// action, before the phone-joining details.
+    fn event_details_menu(
+        &self,
+        event: &EventInfo,
+        join_url: Option<&str>,
+        join: Option<&PhoneJoin>,
+        links: &[&str],
+        can_follow_up: bool,
+    ) -> MenuModel {
+        let mut submenu = MenuModel::new();
+        if let Some(url) = join_url {
+            submenu.push(join_video_row(url));
+        }
+        submenu.push(self.open_calendar_row(event));
+        self.add_prep_link_items(&mut submenu, event);
+        if can_follow_up {
+            submenu.push(
+                Row::new("Schedule Follow-up")
+                    .action(Action::ScheduleFollowUp)
+                    .value(event.occurrence.to_token()),
+            );
         }
+        if let Some(note) = self.note(event) {
+            submenu.push(
+                Row::new(note)
+                    .image(Image::Symbol("note.text", "Note"))
+                    .disabled(),
+            );
+        }
+        submenu.push(self.note_row(event));
+        submenu.push(self.important_row(event));
+        submenu.separate();
 
-        let is_past = event.end < now || is_dismissed;
-        if is_past {
-            let full_range = NSRange::new(0, NSString::from_str(&item_title).length());
-            attr_string.apply_secondary_color(full_range);
+        if !links.is_empty() {
+            submenu.push(links_row(links));
+        }
 
-            if !is_all_day {
-                let start_time_len = format_time(&event.start).chars().count();
-                let dash_and_end_start = start_time_len + 1;
-                let end_time_with_dash_len = 2 + format_time(&event.end).chars().count();
-                let end_time_range = NSRange::new(dash_and_end_start, end_time_with_dash_len);
-                attr_string.apply_tertiary_color(end_time_range);
+        if let Some(join) = join {
+            if let Some(meeting_id) = &join.meeting_id {
+                submenu.push(copy_row(
+                    &format!("Copy Meeting ID ({})", meeting_id),
+                    meeting_id,
+                ));
+            }
+            if let Some(passcode) = &join.passcode {
+                submenu.push(copy_row(&format!("Copy Passcode ({})", passcode), passcode));
+            }
+            if !join.dial_in_numbers.is_empty() {
+                submenu.push(call_in_row(&join.dial_in_numbers));
             }
         }
 
-        let item = app_kit::init_menu_item(
-            self.mtm,
-            ns_string!(""),
-            Some(objc2::sel!(openEvent:)),
-            ns_string!(""),
+        submenu
+    }
+
+    /// Reads the rest of today out loud; choosing it again while it speaks
+    /// starts over.
+    fn add_agenda_item(&self, menu: &mut MenuModel) {
+        menu.push(
+            Row::new("Read Today's Agenda")
+                .action(Action::ReadAgenda)
+                .image(Image::Symbol("speaker.wave.2", "Read aloud")),
         );
-        app_kit::set_attributed_title(&item, attr_string.as_objc());
+    }
+
+    /// Dismissed events yet to end, each restored on its own or all at once.
+    fn add_dismissed_menu(&self, menu: &mut MenuModel) {
+        let now = self.clock.now();
+        let dismissed: Vec<&EventInfo> = match self.dismissed.lock() {
+            Ok(dismissed_set) => self
+                .events
+                .iter()
+                .filter(|e| e.end > now && dismissed_set.contains(&e.occurrence))
+                .collect(),
+            Err(e) => {
+                eprintln!("Error: Failed to acquire lock in build_menu: {}", e);
+                return;
+            }
+        };
+        if dismissed.is_empty() {
+            return;
+        }
 
-        let calendar_color = NSColor::colorWithSRGBRed_green_blue_alpha(
-            event.calendar_color.0,
-            event.calendar_color.1,
-            event.calendar_color.2,
-            1.0,
+        let mut submenu = MenuModel::new();
+        for event in &dismissed {
+            submenu.push(restore_row(&row_title(event, true), &[event]));
+        }
+        if dismissed.len() > 1 {
+            submenu.separate();
+            submenu.push(restore_row(
+                &format!("Restore All {}", dismissed.len()),
+                &dismissed,
+            ));
+        }
+        menu.push(Row::new("Dismissed Events").submenu(submenu));
+    }
+
+    /// Gaps to block for focus, each added on its own or all at once.
+    fn add_focus_menu(&self, menu: &mut MenuModel) {
+        let blocks = self.events.focus_blocks(
+            self.clock.now(),
+            self.config.menu.horizon(),
+            &self.config.focus,
+        );
+        if blocks.is_empty() {
+            return;
+        }
+
+        let mut submenu = MenuModel::new();
+        for block in &blocks {
+            let title = format!(
+                "{} {} - {}",
+                block.start.format("%a"),
+                format_time(&block.start),
+                format_time(&block.end)
+            );
+            submenu.push(focus_row(&title, &[*block]));
+        }
+        if blocks.len() > 1 {
+            submenu.separate();
+            submenu.push(focus_row(&format!("Add All {}", blocks.len()), &blocks));
+        }
+        menu.push(Row::new("Suggest Focus Blocks").submenu(submenu));
+    }
+
+    fn add_mode_items(&self, menu: &mut MenuModel) {
+        let (title, action) = match self.modes.pause.remaining(self.clock.now()) {
+            Some(remaining) => {
+                let status = format!("Paused · resumes in {}", format_duration(remaining));
+                menu.push(Row::new(status).disabled());
+                ("Resume eventually", Action::ResumeApp)
+            }
+            None => ("Pause eventually", Action::PauseApp),
+        };
+        menu.push(Row::new(title).action(action));
+
+        let (title, action) = if self.modes.is_hidden(self.clock.now()) {
+            ("Show Title Again", Action::ShowTitle)
+        } else {
+            ("Hide for Today", Action::HideForToday)
+        };
+        menu.push(Row::new(title).action(action));
+    }
+
+    /// The last fetch's failure in red, with a Retry below. The events shown
+    /// are the ones fetched before it.
+    fn add_problem_banner(&self, menu: &mut MenuModel, problem: &str) {
+        let mut title = StyledText::new(problem);
+        title.role(TextRole::Error, 0..problem.len());
+        menu.push(
+            Row::new(title)
+                .image(Image::Symbol("exclamationmark.triangle.fill", "Problem"))
+                .disabled(),
+        );
+        menu.push(Row::new("Retry").action(Action::RefreshNow));
+    }
+
+    /// A muted "Updated 14:32 · EventKit OK" line; clicking it refreshes.
+    fn add_health_footer(&self, menu: &mut MenuModel, health: &FetchHealth) {
+        menu.separate();
+        let footer = health.footer();
+        let mut title = StyledText::new(footer.as_str());
+        title.role(TextRole::Secondary, 0..footer.len());
+        menu.push(
+            Row::new(title)
+                .action(Action::RefreshNow)
+                .tool_tip("Refresh now"),
         );
-        if let Some(circle_icon) = Icon::load_colored(&calendar_color) {
-            item.setImage(Some(&circle_icon));
+    }
+
+    fn add_settings_menu(&self, menu: &mut MenuModel) {
+        let mut submenu = MenuModel::new();
+        let now = self.clock.now();
+
+        match self.modes.quiet.remaining(now) {
+            Some(remaining) => {
+                let status = format!("Quiet · resumes in {}", format_duration(remaining));
+                submenu.push(Row::new(status).disabled());
+                submenu.push(Row::new("Resume Now").action(Action::ResumeReminders));
+            }
+            None => {
+                let in_quiet_hours = self
+                    .config
+                    .quiet_hours
+                    .as_ref()
+                    .is_some_and(|hours| hours.contains(now));
+                if in_quiet_hours {
+                    submenu.push(Row::new("Quiet hours active").disabled());
+                }
+
+                for minutes in QUIET_DURATIONS {
+                    let title = format!("Quiet for {}", quiet::duration_label(minutes));
+                    submenu.push(
+                        Row::new(title)
+                            .action(Action::QuietFor)
+                            .value(minutes.to_string()),
+                    );
+                }
+            }
+        }
+
+        submenu.separate();
+        for countdown in CurrentCountdown::ALL {
+            submenu.push(
+                Row::new(countdown.label())
+                    .action(Action::SetCurrentCountdown)
+                    .value(countdown.name())
+                    .checked(self.config.title.current == countdown),
+            );
+        }
+
+        if let Some(health) = self.health {
+            submenu.separate();
+            let report = ProblemReport {
+                os_version: foundation::os_version(),
+                calendar_access: event_kit::calendar_authorization_status(),
+                health,
+                events: &self.events,
+                config: self.config,
+            };
+            submenu.push(copy_row("Copy Problem Report", &report.text()));
+        }
+
+        menu.push(Row::new("Settings").submenu(submenu));
+    }
+}
+
+fn style_event_title(
+    item_title: String,
+    event: &EventInfo,
+    is_all_day: bool,
+    is_current_or_next: bool,
+    is_important: bool,
+    is_past: bool,
+) -> StyledText {
+    let full_range = 0..item_title.len();
+
+    // Covers "- 10:00" in "09:00 - 10:00 Standup"
+    let end_time_range = (!is_all_day).then(|| {
+        let start = format_time(&event.start).len() + 1;
+        start..start + 2 + format_time(&event.end).len()
+    });
+    // Covers " · Room 4A" at the end
+    let rooms_range = event
+        .room_label()
+        .map(|rooms| item_title.len() - rooms.len() - " · ".len()..item_title.len());
+
+    let mut title = StyledText::new(item_title);
+    if is_current_or_next || is_important {
+        title.bold(full_range.clone());
+    }
+    if is_important {
+        title.role(TextRole::Urgent, full_range.clone());
+    }
+
+    if let Some(range) = &end_time_range {
+        title.role(TextRole::Secondary, range.clone());
+    }
+
+    if let Some(range) = rooms_range {
+        title.role(TextRole::Secondary, range);
+    }
+
+    if is_past {
+        title.role(TextRole::Secondary, full_range);
+
+        if let Some(range) = end_time_range {
+            title.role(TextRole::Tertiary, range);
         }
+    }
+
+    if event.has_recurrence {
+        title.symbol("repeat", "Recurring event");
+    }
+
+    title
+}
+
+fn join_video_row(url: &str) -> Row {
+    let service_info = ServiceInfo::from_url(url);
+    Row::new(format!("Join {} Event", service_info.name()))
+        .action(Action::OpenUrl)
+        .value(url)
+        .image(Image::Icon(service_info.icon()))
+}
+
+fn copy_row(title: &str, value: &str) -> Row {
+    Row::new(title).action(Action::CopyText).value(value)
+}
+
+/// Links open through `openURL:`, so Slack huddles still go to the app.
+fn links_row(links: &[&str]) -> Row {
+    let mut submenu = MenuModel::new();
+    for link in links {
+        let mut row = Row::new(link_label(link))
+            .action(Action::OpenUrl)
+            .value(*link);
+        let service = ServiceInfo::from_url(link);
+        if service != ServiceInfo::Generic {
+            row = row.image(Image::Icon(service.icon()));
+        }
+        submenu.push(row);
+    }
+    Row::new("Links").submenu(submenu)
+}
+
+/// Each number opens its `tel:` link, which macOS hands to FaceTime or a
+/// paired iPhone.
+fn call_in_row(numbers: &[String]) -> Row {
+    let mut submenu = MenuModel::new();
+    for uri in numbers {
+        submenu.push(
+            Row::new(uri.trim_start_matches("tel:"))
+                .action(Action::OpenUrl)
+                .value(uri.as_str()),
+        );
+    }
+    Row::new("Call In…").submenu(submenu)
+}
 
-        app_kit::set_menu_item_target(&item, Some(self.delegate));
-        let open_data = format!("{}|||{}", event.event_id, event.has_recurrence);
-        app_kit::set_menu_item_represented_object(&item, Some(&*NSString::from_str(&open_data)));
+fn restore_row(title: &str, events: &[&EventInfo]) -> Row {
+    let tokens: Vec<String> = events.iter().map(|e| e.occurrence.to_token()).collect();
+    Row::new(title)
+        .action(Action::RestoreEvents)
+        .value(tokens.join("\n"))
+}
+
+/// The blocks go along as "<start> <end>" lines of Unix timestamps.
+fn focus_row(title: &str, blocks: &[FocusBlock]) -> Row {
+    let lines: Vec<String> = blocks
+        .iter()
+        .map(|block| format!("{} {}", block.start.timestamp(), block.end.timestamp()))
+        .collect();
+    Row::new(title)
+        .action(Action::CreateFocusBlocks)
+        .value(lines.join("\n"))
+}
 
-        menu.addItem(&item);
+fn quit_row() -> Row {
+    Row::new("Quit").action(Action::Quit).key_equivalent("q")
+}
+
+/// The call link of a meeting starting within the next few minutes.
+pub(super) fn join_soon_url<'e>(
+    event: &'e EventInfo,
+    now: DateTime<Local>,
+    prefer: &[ServiceInfo],
+) -> Option<&'e str> {
+    let starts_in = event.start.signed_duration_since(now);
+    if starts_in < Duration::zero() || starts_in > Duration::minutes(JOIN_SOON_MINUTES) {
+        return None;
     }
+    event.join_url(prefer)
+}
+
+/// "09:00 - 09:30 Standup · Room 4A", or "All day: Offsite". `with_day`
+/// prefixes the weekday, e.g. "Tue 09:00 - 09:30 Standup", for groups covering
+/// several days.
+pub(super) fn row_title(event: &EventInfo, with_day: bool) -> String {
+    let time_prefix = if is_all_day(&event.start, &event.end) {
+        "All day:".to_string()
+    } else {
+        format!(
+            "{} - {}",
+            format_time(&event.start),
+            format_time(&event.end)
+        )
+    };
+    let time_prefix = if with_day {
+        format!("{} {}", event.start.format("%a"), time_prefix)
+    } else {
+        time_prefix
+    };
+    match event.room_label() {
+        Some(rooms) => format!("{} {} · {}", time_prefix, event.title, rooms),
+        None => format!("{} {}", time_prefix, event.title),
+    }
+}
+
+/// Menu shown when calendar access was denied: nothing to list, but the app
+/// stays put so the status title can explain what's wrong.
+pub fn access_denied_menu(mtm: MainThreadMarker) -> Retained<NSMenu> {
+    let mut model = MenuModel::new();
+    model.push(
+        Row::new("Grant access in System Settings > Privacy & Security > Calendars").disabled(),
+    );
+    model.separate();
+    model.push(quit_row());
+
+    let menu = app_kit::init_menu(mtm, ns_string!(""));
+    MenuRenderer::new(None, &RefCell::default(), mtm).render(&model, &menu);
+    menu
+}
 
-    fn add_quit_item(&self, menu: &NSMenu) {
-        let quit_item = app_kit::init_menu_item(
-            self.mtm,
-            ns_string!("Quit"),
-            Some(objc2::sel!(terminate:)),
-            ns_string!("q"),
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendar::parse_local_time;
+    use crate::clock::FrozenClock;
+    use crate::menu::model::Run;
+
+    fn events() -> EventCollection {
+        EventCollection::from_fixture(
+            r#"
+            [[event]]
+            title = "Standup"
+            start = "2025-03-07 09:00"
+            end = "2025-03-07 09:15"
+
+            [[event]]
+            title = "Review"
+            start = "2025-03-07 10:00"
+            end = "2025-03-07 11:00"
+            location = "https://meet.google.com/abc-defg-hij"
+
+            [[event]]
+            title = "Planning"
+            start = "2025-03-08 14:00"
+            end = "2025-03-08 15:00"
+            "#,
+        )
+        .unwrap()
+    }
+
+    fn build(events: EventCollection, dismissed: HashSet<OccurrenceRef>) -> MenuModel {
+        let dismissed = Arc::new(Mutex::new(dismissed));
+        let config = Config::default();
+        let clock = FrozenClock(parse_local_time("2025-03-07 09:55").unwrap());
+        MenuBuilder::new(events, &dismissed, Modes::default(), &config)
+            .clock(&clock)
+            .build()
+    }
+
+    #[test]
+    fn test_build_lists_events_by_day() {
+        let menu = build(events(), HashSet::new());
+        let snapshot = menu.to_string();
+        let events_part: Vec<&str> = snapshot.lines().take(23).collect();
+
+        assert_eq!(
+            events_part.join("\n"),
+            "Join Google Meet Event → OpenUrl\n\
+             Open in Calendar → OpenEvent\n\
+             Add Note… → EditNote\n\
+             Important → ToggleImportant\n\
+             Dismiss Event → DismissEvent\n\
+             ---\n\
+             Today, 07 Mar\n  \
+             Dismiss All → DismissEvents\n  \
+             Copy Day as Markdown → CopyText\n  \
+             Open Day in Calendar → OpenDay\n\
+             1h 15m in meetings (disabled)\n\
+             09:00 - 09:15 Standup → OpenEvent\n\
+             ⌥ Copy Debug Info: Standup → CopyText\n\
+             ✓ 10:00 - 11:00 Review Join → OpenUrl\n\
+             ⌥ Copy Debug Info: Review → CopyText\n\
+             ---\n\
+             Tomorrow, 08 Mar\n  \
+             Dismiss All → DismissEvents\n  \
+             Copy Day as Markdown → CopyText\n  \
+             Open Day in Calendar → OpenDay\n\
+             14:00 - 15:00 Planning → OpenEvent\n\
+             ⌥ Copy Debug Info: Planning → CopyText\n\
+             ---"
+        );
+        // Up next: bold, with the end time muted and an inline Join
+        assert_eq!(
+            menu.sections()[1][4].title.runs(),
+            [
+                Run::Bold(0..20),
+                Run::Role(TextRole::Secondary, 6..13),
+                Run::Append(" Join".to_string(), TextRole::Urgent),
+            ]
         );
-        menu.addItem(&quit_item);
+    }
+
+    #[test]
+    fn test_build_offers_restoring_dismissed_events() {
+        let events = events();
+        let review = events
+            .iter()
+            .find(|e| e.title == "Review")
+            .unwrap()
+            .occurrence
+            .clone();
+        let snapshot = build(events, HashSet::from([review])).to_string();
+
+        assert!(
+            snapshot.contains("\nDismissed Events\n  Fri 10:00 - 11:00 Review → RestoreEvents\n")
+        );
+    }
+
+    #[test]
+    fn test_build_finds_call_link_in_notes() {
+        let events = EventCollection::from_fixture(
+            r#"
+            [[event]]
+            title = "Deep work"
+            start = "2025-03-07 10:00"
+            end = "2025-03-07 11:00"
+            location = "Home office"
+            notes = "Call: https://meet.google.com/abc-defg-hij"
+            "#,
+        )
+        .unwrap();
+        let menu = build(events, HashSet::new());
+        let row = menu
+            .sections()
+            .iter()
+            .flatten()
+            .find(|row| row.title.text().contains("Deep work"))
+            .unwrap();
+
+        // A solo event is urgent with a call link anywhere, shown by its
+        // service's icon, and the link alone needs no submenu
+        assert_eq!(row.title.to_string(), "10:00 - 11:00 Deep work Join");
+        assert_eq!(row.image, Some(Image::Icon(Icon::Google)));
+        assert!(row.submenu.is_none());
     }
 }
@@ -0,0 +1,74 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Keeps values built during one menu rebuild around for the next one.
+///
+/// Entries not requested during a rebuild are dropped by `sweep`, so the cache
+/// only ever holds what the current menu shows.
+pub struct RebuildCache<K, V> {
+    previous: HashMap<K, V>,
+    current: HashMap<K, V>,
+}
+
+impl<K: Hash + Eq, V: Clone> RebuildCache<K, V> {
+    pub fn get_or_insert_with(&mut self, key: K, build: impl FnOnce() -> V) -> V {
+        if let Some(value) = self.current.get(&key) {
+            return value.clone();
+        }
+        let value = self.previous.remove(&key).unwrap_or_else(build);
+        self.current.insert(key, value.clone());
+        value
+    }
+
+    /// Drops everything, for when cached values are stale regardless of key.
+    pub fn clear(&mut self) {
+        self.previous.clear();
+        self.current.clear();
+    }
+
+    /// Ends a rebuild, dropping entries that weren't used by it.
+    pub fn sweep(&mut self) {
+        self.previous = std::mem::take(&mut self.current);
+    }
+}
+
+impl<K, V> Default for RebuildCache<K, V> {
+    fn default() -> Self {
+        Self {
+            previous: HashMap::new(),
+            current: HashMap::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    #[test]
+    fn test_reuses_value_across_rebuilds() {
+        let builds = Cell::new(0);
+        let mut cache = RebuildCache::default();
+        let build = || {
+            builds.set(builds.get() + 1);
+            "value".to_string()
+        };
+
+        cache.get_or_insert_with("key", build);
+        cache.sweep();
+        cache.get_or_insert_with("key", build);
+
+        assert_eq!(builds.get(), 1);
+    }
+
+    #[test]
+    fn test_sweep_drops_unused_entries() {
+        let mut cache = RebuildCache::default();
+        cache.get_or_insert_with("a", || 1);
+        cache.sweep();
+        cache.sweep();
+
+        assert_eq!(cache.get_or_insert_with("a", || 2), 2);
+    }
+}
@@ -0,0 +1,164 @@
+use chrono::{Datelike, Duration, NaiveDate};
+
+use crate::calendar::EventInfo;
+
+use super::builder::row_title;
+
+const WEEK: i64 = 7;
+
+/// A header in the menu and the days whose events are listed under it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DayGroup {
+    pub first: NaiveDate,
+    pub last: NaiveDate,
+    /// "Today", "Thursday", "Next week"
+    pub name: String,
+    /// "07 Mar", or "10 Mar – 14 Mar" for a week
+    pub dates: String,
+}
+
+impl DayGroup {
+    fn day(date: NaiveDate, name: String) -> Self {
+        Self {
+            first: date,
+            last: date,
+            name,
+            dates: date.format("%d %b").to_string(),
+        }
+    }
+
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        self.first <= date && date <= self.last
+    }
+
+    /// Rows in a group covering several days say which day they're on.
+    pub fn spans_days(&self) -> bool {
+        self.first != self.last
+    }
+
+    /// The group's events as a Markdown list under a heading, for pasting
+    /// into notes or chat.
+    pub fn markdown(&self, events: &[&EventInfo]) -> String {
+        let mut lines = vec![format!("## {}, {}", self.name, self.dates), String::new()];
+        lines.extend(
+            events
+                .iter()
+                .map(|event| format!("- {}", row_title(event, self.spans_days()))),
+        );
+        lines.join("\n")
+    }
+}
+
+/// The groups for `days` days starting `today`. The coming week gets a header
+/// per day: "Today", "Tomorrow", then weekday names, which can't repeat within
+/// it. Days after that roll up by calendar week, into "Next week" and then
+/// "Week of 24 Mar".
+pub fn day_groups(today: NaiveDate, days: u32) -> Vec<DayGroup> {
+    let rollup_from = today + Duration::days(WEEK);
+    let mut groups: Vec<DayGroup> = Vec::new();
+    for offset in 0..days {
+        let date = today + Duration::days(offset as i64);
+        let name = match offset {
+            0 => "Today".to_string(),
+            1 => "Tomorrow".to_string(),
+            _ if date < rollup_from => date.format("%A").to_string(),
+            _ => {
+                let monday = week_start(date);
+                if let Some(week) = groups
+                    .last_mut()
+                    .filter(|g| g.first >= rollup_from && week_start(g.first) == monday)
+                {
+                    week.last = date;
+                    week.dates =
+                        format!("{} – {}", week.first.format("%d %b"), date.format("%d %b"));
+                    continue;
+                }
+                if monday == week_start(today) + Duration::days(WEEK) {
+                    "Next week".to_string()
+                } else {
+                    format!("Week of {}", monday.format("%d %b"))
+                }
+            }
+        };
+        groups.push(DayGroup::day(date, name));
+    }
+    groups
+}
+
+fn week_start(date: NaiveDate) -> NaiveDate {
+    date - Duration::days(date.weekday().num_days_from_monday() as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendar::EventCollection;
+
+    fn names(groups: &[DayGroup]) -> Vec<(&str, &str)> {
+        groups
+            .iter()
+            .map(|g| (g.name.as_str(), g.dates.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn test_day_groups_default_horizon() {
+        // A Friday
+        let today = NaiveDate::from_ymd_opt(2025, 3, 7).unwrap();
+        assert_eq!(
+            names(&day_groups(today, 4)),
+            vec![
+                ("Today", "07 Mar"),
+                ("Tomorrow", "08 Mar"),
+                ("Sunday", "09 Mar"),
+                ("Monday", "10 Mar"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_day_groups_roll_up_weeks() {
+        // A Wednesday: day names up to Tuesday, then the rest of next week,
+        // then the week after
+        let today = NaiveDate::from_ymd_opt(2025, 3, 5).unwrap();
+        let groups = day_groups(today, 14);
+
+        assert_eq!(groups.len(), 9);
+        assert_eq!(groups[6].name, "Tuesday");
+        assert_eq!(
+            names(&groups[7..]),
+            vec![
+                ("Next week", "12 Mar – 16 Mar"),
+                ("Week of 17 Mar", "17 Mar – 18 Mar"),
+            ]
+        );
+        assert!(groups[7].contains(NaiveDate::from_ymd_opt(2025, 3, 14).unwrap()));
+        assert!(groups[7].spans_days());
+        assert!(!groups[6].spans_days());
+    }
+
+    #[test]
+    fn test_markdown() {
+        let events = EventCollection::from_fixture(
+            r#"
+            [[event]]
+            title = "Standup"
+            start = "2025-03-07 09:00"
+            end = "2025-03-07 09:15"
+
+            [[event]]
+            title = "Offsite"
+            start = "2025-03-07 00:00"
+            end = "2025-03-08 00:00"
+            "#,
+        )
+        .unwrap();
+        let today = NaiveDate::from_ymd_opt(2025, 3, 7).unwrap();
+        let events: Vec<_> = events.iter().collect();
+
+        assert_eq!(
+            day_groups(today, 1)[0].markdown(&events),
+            "## Today, 07 Mar\n\n- All day: Offsite\n- 09:00 - 09:15 Standup"
+        );
+    }
+}
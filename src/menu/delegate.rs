@@ -1,23 +1,95 @@
+use block2::Block;
+use chrono::{DateTime, Duration, Local};
 use objc2::rc::Retained;
+use objc2::runtime::AnyObject;
 use objc2::{define_class, DeclaredClass};
-use objc2_app_kit::{NSMenuItem, NSStatusItem, NSWorkspace};
+use objc2_app_kit::{
+    NSApplication, NSCellImagePosition, NSColor, NSEvent, NSEventMask, NSEventModifierFlags,
+    NSEventType, NSMenu, NSMenuItem, NSStatusBarButton, NSStatusItem, NSWindowOcclusionState,
+    NSWorkspace,
+};
 use objc2_event_kit::EKEventStore;
-use objc2_foundation::{MainThreadMarker, NSNotification, NSObject, NSString, NSTimer, NSURL};
-use std::cell::Cell;
+use objc2_foundation::{
+    ns_string, MainThreadMarker, NSDate, NSNotification, NSObject, NSProcessInfo, NSString,
+    NSTimeZone, NSTimer, NSURL,
+};
+use std::cell::{Cell, RefCell};
 use std::collections::HashSet;
 use std::sync::{Arc, Mutex};
 
-use crate::calendar::{EventCollection, SlackHuddleUrl};
+use crate::backoff::Backoff;
+use crate::calendar::{
+    format_duration, format_time, is_all_day, EventCollection, EventInfo, Icon, OccurrenceRef,
+    ServiceInfo,
+};
+use crate::clock::{Clock, FrozenClock, SystemClock};
+use crate::config::{ClickAction, ClickConfig, Config, CurrentCountdown, OpenWith, TitleMode};
 use crate::ffi::foundation::ns_menu_item_represented_object_to_string;
+use crate::ffi::user_notifications::{self, NotificationAction};
+use crate::ffi::{app_kit, event_kit};
 use crate::init_objc_super;
+use crate::local_notes::LocalNotes;
+use crate::media;
 use crate::menu::MenuBuilder;
+use crate::watch::{WatchEvent, WatchSocket};
+
+use super::health::FetchHealth;
+use super::render::{MenuRenderer, RowTitleCache};
+use crate::modes::Modes;
+
+const FOLLOW_UP_MINUTES: i64 = 30;
+/// Days fetched for refreshing the title, which only looks at today, with
+/// tomorrow for notifications due around midnight. Opening the menu fetches
+/// all of `menu.days`.
+const TITLE_FETCH_DAYS: u32 = 2;
+/// How long Snooze on a notification puts it off.
+const SNOOZE_MINUTES: i64 = 5;
 
 pub struct Ivars {
-    dismissed_events: Arc<Mutex<HashSet<String>>>,
+    dismissed_events: Arc<Mutex<HashSet<OccurrenceRef>>>,
     mtm: MainThreadMarker,
     event_store: Retained<EKEventStore>,
     status_item: Retained<NSStatusItem>,
     timer: Cell<Option<Retained<NSTimer>>>,
+    retry_timer: Cell<Option<Retained<NSTimer>>>,
+    /// Set by `sync.poll-minutes`
+    poll_timer: Cell<Option<Retained<NSTimer>>>,
+    backoff: Cell<Backoff>,
+    /// Last successful fetch, shown while the store is unavailable
+    events: RefCell<EventCollection>,
+    modes: Cell<Modes>,
+    config: RefCell<Config>,
+    title: RefCell<StatusTitle>,
+    /// Filled in by `menuNeedsUpdate:` as it opens, not on every refresh
+    menu: Retained<NSMenu>,
+    pending_click: Cell<Option<Retained<NSTimer>>>,
+    row_titles: RefCell<RowTitleCache>,
+    /// Occurrence key of the meeting last joined, marked in the menu
+    joined: RefCell<Option<OccurrenceRef>>,
+    hidden_title_length: Cell<Option<usize>>,
+    /// Set by `--at`: the app shows this instant instead of the current time
+    simulated_at: Cell<Option<DateTime<Local>>>,
+    /// Set by `--fixture`: shown instead of the calendar's events
+    fixture: RefCell<Option<EventCollection>>,
+    /// Between the displays (or the whole Mac) going to sleep and waking up
+    screens_asleep: Cell<bool>,
+    health: RefCell<FetchHealth>,
+    notes: RefCell<LocalNotes>,
+    /// Reading the agenda aloud, kept so the speech isn't cut off
+    speech: RefCell<Option<Retained<AnyObject>>>,
+    /// Unset when the socket couldn't be bound
+    watch: Option<WatchSocket>,
+    /// Events starting, or due a notification, after this and up to the next
+    /// refresh are acted on then
+    last_refresh: Cell<Option<DateTime<Local>>>,
+}
+
+#[derive(Default)]
+struct StatusTitle {
+    text: String,
+    tooltip: Option<String>,
+    calendar_color: Option<(f64, f64, f64)>,
+    paused: bool,
 }
 
 define_class!(
@@ -36,89 +108,477 @@ define_class!(
         fn did_wake_notification(&self, _notification: &NSNotification) {
             // Delay slightly to ensure system time has stabilized after wake
             std::thread::sleep(std::time::Duration::from_millis(100));
+            // Every window reads as occluded while the display sleeps, so give
+            // the full title another chance once we're awake again
+            self.ivars().hidden_title_length.set(None);
+            self.resume_timers();
+        }
+
+        /// Nothing on screen needs updating while the displays sleep or the
+        /// Mac is going to sleep, so the timers stop until they wake.
+        #[unsafe(method(suspendTimers:))]
+        fn suspend_timers_notification(&self, _notification: &NSNotification) {
+            self.ivars().screens_asleep.set(true);
+            self.suspend_timers();
+        }
+
+        #[unsafe(method(screensDidWake:))]
+        fn screens_did_wake(&self, _notification: &NSNotification) {
+            self.ivars().hidden_title_length.set(None);
+            self.resume_timers();
+        }
+
+        /// The screens are on by the time the session unlocks, even if their
+        /// wake notification went missing.
+        #[unsafe(method(screenUnlocked:))]
+        fn screen_unlocked(&self, _notification: &NSNotification) {
+            self.resume_timers();
+        }
+
+        #[unsafe(method(timeChanged:))]
+        fn time_changed(&self, _notification: &NSNotification) {
+            self.handle_time_change();
+        }
+
+        /// Events between the last refresh and now are acted on at the next
+        /// one, which across a clock jump would notify of a whole stretch at
+        /// once or of some events twice. The window starts over at the new
+        /// time instead.
+        #[unsafe(method(clockChanged:))]
+        fn clock_changed(&self, _notification: &NSNotification) {
+            self.ivars().last_refresh.set(None);
+            self.handle_time_change();
+        }
+
+        #[unsafe(method(occlusionChanged:))]
+        fn occlusion_changed(&self, _notification: &NSNotification) {
+            self.update_status_button();
+        }
+
+        /// Calendar dots and styled row titles are drawn for the appearance at
+        /// the time, so switching light/dark mode, the accent color, or
+        /// "Increase contrast" redraws them.
+        #[unsafe(method(appearanceChanged:))]
+        fn appearance_changed(&self, _notification: &NSNotification) {
+            self.ivars().row_titles.borrow_mut().clear();
             self.refresh_menu();
         }
 
+        /// Fetches every day the menu lists and builds it just before it shows,
+        /// so refreshes in the background only fetch what the title needs.
+        #[unsafe(method(menuNeedsUpdate:))]
+        fn menu_needs_update(&self, menu: &NSMenu) {
+            let clock = FrozenClock(self.ivars().simulated_at.get().unwrap_or_else(Local::now));
+            let paused = self
+                .ivars()
+                .modes
+                .get()
+                .pause
+                .remaining(clock.now())
+                .is_some();
+            let events = match self.ivars().fixture.borrow().as_ref() {
+                Some(fixture) => fixture.clone(),
+                None if paused => self.ivars().events.borrow().clone(),
+                None => {
+                    let horizon = self.ivars().config.borrow().menu.horizon();
+                    self.fetch_events(&clock, horizon)
+                }
+            };
+            let config = self.ivars().config.borrow();
+            let model = MenuBuilder::new(
+                events,
+                &self.ivars().dismissed_events,
+                self.ivars().modes.get(),
+                &config,
+            )
+            .joined(self.ivars().joined.borrow().as_ref())
+            .clock(&clock)
+            .health(&self.ivars().health.borrow())
+            .notes(&self.ivars().notes.borrow())
+            .build();
+            MenuRenderer::new(Some(self), &self.ivars().row_titles, self.ivars().mtm)
+                .render(&model, menu);
+        }
+
+        #[unsafe(method(userNotificationCenter:didReceiveNotificationResponse:withCompletionHandler:))]
+        fn did_receive_notification_response(
+            &self,
+            _center: &AnyObject,
+            response: &AnyObject,
+            completion_handler: &Block<dyn Fn()>,
+        ) {
+            // Responses arrive on the main thread, which the ivars rely on
+            if MainThreadMarker::new().is_none() {
+                eprintln!("Error: Notification response arrived off the main thread");
+            } else if let (token, Some(action)) = user_notifications::response_action(response) {
+                match OccurrenceRef::from_token(&token) {
+                    Some(occurrence) => self.handle_notification_action(occurrence, action),
+                    None => eprintln!("Error: Invalid occurrence: {}", token),
+                }
+            }
+            completion_handler.call(());
+        }
+
         #[unsafe(method(timerFired:))]
-        fn timer_fired(&self, _timer: &NSTimer) {
+        fn timer_fired(&self, timer: &NSTimer) {
+            // Low power mode can be toggled at any time, so it's checked per tick
+            timer.setTolerance(timer_tolerance());
+            self.refresh_menu();
+        }
+
+        #[unsafe(method(refreshNow:))]
+        fn refresh_now(&self, _sender: &NSMenuItem) {
+            self.refresh_menu();
+        }
+
+        /// The minute timer only re-reads EventKit's local copy, which some
+        /// accounts never update without being asked to sync.
+        #[unsafe(method(pollSources:))]
+        fn poll_sources(&self, _timer: &NSTimer) {
+            if self.ivars().fixture.borrow().is_none() {
+                event_kit::refresh_sources(&self.ivars().event_store);
+            }
+            self.refresh_menu();
+        }
+
+        #[unsafe(method(retryFetch:))]
+        fn retry_fetch(&self, _timer: &NSTimer) {
+            self.ivars().retry_timer.set(None);
             self.refresh_menu();
         }
 
         #[unsafe(method(openEvent:))]
         fn open_event(&self, sender: &NSMenuItem) {
+            let Some(occurrence) = represented_occurrence(sender) else {
+                return;
+            };
+            // Opening the whole calendar is the safe guess for an event that's
+            // gone, since recurring events can't be opened directly
+            let has_recurrence = self
+                .find_event(&occurrence)
+                .is_none_or(|event| event.has_recurrence);
+            open_url_string(&calendar_url(&occurrence.event_id, has_recurrence));
+        }
+
+        #[unsafe(method(openURL:))]
+        fn open_url(&self, sender: &NSMenuItem) {
             if let Some(obj) = sender.representedObject() {
-                let data = ns_menu_item_represented_object_to_string(&obj);
+                self.join_meeting(ns_menu_item_represented_object_to_string(&obj));
+            }
+        }
 
-                let parts: Vec<&str> = data.split("|||").collect();
-                if parts.is_empty() {
-                    eprintln!("Error: Invalid event data format");
-                    return;
+        #[unsafe(method(copyText:))]
+        fn copy_text(&self, sender: &NSMenuItem) {
+            if let Some(obj) = sender.representedObject() {
+                let text = ns_menu_item_represented_object_to_string(&obj);
+                app_kit::copy_to_pasteboard(&NSString::from_str(&text));
+            }
+        }
+
+        #[unsafe(method(statusItemClicked:))]
+        fn status_item_clicked(&self, _sender: &NSStatusBarButton) {
+            let Some(event) = NSApplication::sharedApplication(self.ivars().mtm).currentEvent()
+            else {
+                return;
+            };
+            let is_secondary = event.r#type() == NSEventType::RightMouseUp
+                || event.modifierFlags().contains(NSEventModifierFlags::Control);
+            let (primary, double) = {
+                let config = self.ivars().config.borrow();
+                (config.click.primary, config.click.double)
+            };
+
+            match double {
+                _ if is_secondary => {
+                    self.cancel_pending_click();
+                    self.perform_click_action(ClickAction::Menu);
                 }
-                let event_id = parts[0];
-                let has_recurrence = parts.get(1).map(|s| *s == "true").unwrap_or(false);
+                None => self.perform_click_action(primary),
+                Some(double) if event.clickCount() >= 2 => {
+                    self.cancel_pending_click();
+                    self.perform_click_action(double);
+                }
+                Some(_) => {
+                    // Hold the single click until we know no second one follows
+                    self.cancel_pending_click();
+                    let timer = unsafe {
+                        NSTimer::scheduledTimerWithTimeInterval_target_selector_userInfo_repeats(
+                            NSEvent::doubleClickInterval(),
+                            self,
+                            objc2::sel!(singleClickTimerFired:),
+                            None,
+                            false,
+                        )
+                    };
+                    self.ivars().pending_click.set(Some(timer));
+                }
+            }
+        }
 
-                let url_string = if has_recurrence {
-                    "ical://".to_string()
-                } else {
-                    format!("ical://ekevent/{}", event_id)
-                };
+        #[unsafe(method(singleClickTimerFired:))]
+        fn single_click_timer_fired(&self, _timer: &NSTimer) {
+            self.ivars().pending_click.set(None);
+            let primary = self.ivars().config.borrow().click.primary;
+            self.perform_click_action(primary);
+        }
 
-                if let Some(url) = NSURL::URLWithString(&NSString::from_str(&url_string)) {
-                    NSWorkspace::sharedWorkspace().openURL(&url);
+        #[unsafe(method(dismissEvent:))]
+        fn dismiss_event(&self, sender: &NSMenuItem) {
+            if let Some(occurrence) = represented_occurrence(sender) {
+                self.dismiss(occurrence);
+            }
+        }
+
+        /// Brings back dismissed events, given as newline-separated tokens.
+        #[unsafe(method(restoreEvents:))]
+        fn restore_events(&self, sender: &NSMenuItem) {
+            let Some(occurrences) = represented_occurrences(sender) else {
+                return;
+            };
+            if let Ok(mut dismissed) = self.ivars().dismissed_events.lock() {
+                for occurrence in &occurrences {
+                    dismissed.remove(occurrence);
+                }
+            } else {
+                eprintln!("Error: Failed to acquire lock when restoring events");
+                return;
+            }
+
+            self.refresh_menu();
+        }
+
+        /// Dismisses every event of a day, given as newline-separated tokens.
+        #[unsafe(method(dismissEvents:))]
+        fn dismiss_events(&self, sender: &NSMenuItem) {
+            if let Some(occurrences) = represented_occurrences(sender) {
+                for occurrence in &occurrences {
+                    self.notify_watchers(WatchEvent::Dismissed(
+                        occurrence,
+                        self.find_event(occurrence).as_ref(),
+                    ));
+                }
+
+                if let Ok(mut dismissed) = self.ivars().dismissed_events.lock() {
+                    dismissed.extend(occurrences);
                 } else {
-                    eprintln!("Error: Failed to create URL for event: {}", url_string);
+                    eprintln!("Error: Failed to acquire lock when dismissing events");
+                    return;
                 }
+
+                self.refresh_menu();
             }
         }
 
-        #[unsafe(method(openURL:))]
-        fn open_url(&self, sender: &NSMenuItem) {
+        #[unsafe(method(openDay:))]
+        fn open_day(&self, sender: &NSMenuItem) {
             if let Some(obj) = sender.representedObject() {
-                let url_string = ns_menu_item_represented_object_to_string(&obj);
+                let date = ns_menu_item_represented_object_to_string(&obj);
+                open_url_string(&format!("ical://showdate/{}", date));
+            }
+        }
+
+        #[unsafe(method(editNote:))]
+        fn edit_note(&self, sender: &NSMenuItem) {
+            let Some(occurrence) = represented_occurrence(sender) else {
+                return;
+            };
+            let Some(event) = self.find_event(&occurrence) else {
+                eprintln!("Error: Event not found for note: {}", occurrence);
+                return;
+            };
+
+            let current = self
+                .ivars()
+                .notes
+                .borrow()
+                .get(&occurrence)
+                .unwrap_or_default()
+                .to_string();
+            let Some(text) = app_kit::prompt_for_text(
+                self.ivars().mtm,
+                &format!("Note for {}", event.title),
+                "Only kept on this Mac. Leave empty to remove it.",
+                &current,
+            ) else {
+                return;
+            };
+
+            let mut notes = self.ivars().notes.borrow_mut();
+            notes.set(&event, &text, Local::now());
+            if let Err(e) = notes.save() {
+                eprintln!("Error: Failed to save notes: {}", e);
+            }
+            drop(notes);
+            self.refresh_menu();
+        }
+
+        #[unsafe(method(toggleImportant:))]
+        fn toggle_important(&self, sender: &NSMenuItem) {
+            let Some(occurrence) = represented_occurrence(sender) else {
+                return;
+            };
+            let Some(event) = self.find_event(&occurrence) else {
+                eprintln!("Error: Event not found to mark important: {}", occurrence);
+                return;
+            };
 
-                let final_url = if url_string.contains("slack") {
-                    if let Some(huddle) = SlackHuddleUrl::parse(&url_string) {
-                        huddle.to_native_url()
-                    } else {
-                        url_string
+            let mut notes = self.ivars().notes.borrow_mut();
+            let important = !notes.is_important(&event);
+            notes.set_important(&event, important);
+            if let Err(e) = notes.save() {
+                eprintln!("Error: Failed to save notes: {}", e);
+            }
+            drop(notes);
+            self.refresh_menu();
+        }
+
+        #[unsafe(method(scheduleFollowUp:))]
+        fn schedule_follow_up(&self, sender: &NSMenuItem) {
+            if let Some(occurrence) = represented_occurrence(sender) {
+                self.create_follow_up(&occurrence);
+            }
+        }
+
+        /// Adds the focus blocks given as "<start> <end>" lines of Unix
+        /// timestamps to the default calendar.
+        #[unsafe(method(createFocusBlocks:))]
+        fn create_focus_blocks(&self, sender: &NSMenuItem) {
+            if let Some(obj) = sender.representedObject() {
+                let blocks = ns_menu_item_represented_object_to_string(&obj);
+                let title = self.ivars().config.borrow().focus.title.clone();
+                for line in blocks.lines() {
+                    let Some((start, end)) = line.split_once(' ').and_then(|(start, end)| {
+                        Some((start.parse::<f64>().ok()?, end.parse::<f64>().ok()?))
+                    }) else {
+                        eprintln!("Error: Invalid focus block: {}", line);
+                        continue;
+                    };
+                    if let Err(e) = event_kit::create_event(
+                        &self.ivars().event_store,
+                        &title,
+                        &NSDate::dateWithTimeIntervalSince1970(start),
+                        &NSDate::dateWithTimeIntervalSince1970(end),
+                    ) {
+                        eprintln!("Error: Failed to create focus block: {}", e);
                     }
-                } else {
-                    url_string
-                };
+                }
+                self.refresh_menu();
+            }
+        }
 
-                if let Some(url) = NSURL::URLWithString(&NSString::from_str(&final_url)) {
-                    let workspace = NSWorkspace::sharedWorkspace();
-                    workspace.openURL(&url);
-                } else {
-                    eprintln!("Error: Failed to create URL from: {}", final_url);
+        #[unsafe(method(readAgenda:))]
+        fn read_agenda(&self, _sender: &NSMenuItem) {
+            let clock = FrozenClock(self.ivars().simulated_at.get().unwrap_or_else(Local::now));
+            let speech = {
+                let Ok(dismissed) = self.ivars().dismissed_events.lock() else {
+                    eprintln!("Error: Failed to acquire lock when reading the agenda");
+                    return;
+                };
+                let config = self.ivars().config.borrow();
+                match self.ivars().fixture.borrow().as_ref() {
+                    Some(fixture) => fixture.agenda_speech(&clock, &dismissed, &config),
+                    None => self
+                        .ivars()
+                        .events
+                        .borrow()
+                        .agenda_speech(&clock, &dismissed, &config),
                 }
+            };
+            if let Some(previous) = self.ivars().speech.take() {
+                app_kit::stop_speaking(&previous);
             }
+            let synthesizer = app_kit::speak(&NSString::from_str(&speech));
+            self.ivars().speech.replace(Some(synthesizer));
         }
 
-        #[unsafe(method(dismissEvent:))]
-        fn dismiss_event(&self, sender: &NSMenuItem) {
+        #[unsafe(method(quietFor:))]
+        fn quiet_for(&self, sender: &NSMenuItem) {
             if let Some(obj) = sender.representedObject() {
-                let event_id_string = ns_menu_item_represented_object_to_string(&obj);
+                let minutes_string = ns_menu_item_represented_object_to_string(&obj);
+                let Ok(minutes) = minutes_string.parse::<i64>() else {
+                    eprintln!("Error: Invalid quiet duration: {}", minutes_string);
+                    return;
+                };
 
-                if let Ok(mut dismissed) = self.ivars().dismissed_events.lock() {
-                    dismissed.insert(event_id_string.clone());
-                } else {
-                    eprintln!("Error: Failed to acquire lock when dismissing event");
+                let mut modes = self.ivars().modes.get();
+                modes.quiet.start(Local::now(), Duration::minutes(minutes));
+                self.ivars().modes.set(modes);
+                self.refresh_menu();
+            }
+        }
+
+        /// Applies until the config file is next loaded.
+        #[unsafe(method(setCurrentCountdown:))]
+        fn set_current_countdown(&self, sender: &NSMenuItem) {
+            if let Some(obj) = sender.representedObject() {
+                let name = ns_menu_item_represented_object_to_string(&obj);
+                let Some(countdown) = CurrentCountdown::from_name(&name) else {
+                    eprintln!("Error: Invalid countdown: {}", name);
                     return;
-                }
+                };
 
+                self.ivars().config.borrow_mut().title.current = countdown;
                 self.refresh_menu();
             }
         }
+
+        #[unsafe(method(resumeReminders:))]
+        fn resume_reminders(&self, _sender: &NSMenuItem) {
+            let mut modes = self.ivars().modes.get();
+            modes.quiet.resume();
+            self.ivars().modes.set(modes);
+            self.refresh_menu();
+        }
+
+        #[unsafe(method(pauseApp:))]
+        fn pause_app(&self, _sender: &NSMenuItem) {
+            // Refreshes only fetch the title's days, and nothing is fetched
+            // while paused, so keep every day the menu lists until it resumes
+            let clock = FrozenClock(self.ivars().simulated_at.get().unwrap_or_else(Local::now));
+            let horizon = self.ivars().config.borrow().menu.horizon();
+            self.fetch_events(&clock, horizon);
+            let resume_after = self.ivars().config.borrow().pause.resume_after;
+            let mut modes = self.ivars().modes.get();
+            modes.pause.start(Local::now(), Duration::minutes(resume_after));
+            self.ivars().modes.set(modes);
+            self.refresh_menu();
+        }
+
+        #[unsafe(method(hideForToday:))]
+        fn hide_for_today(&self, _sender: &NSMenuItem) {
+            let mut modes = self.ivars().modes.get();
+            modes.hide_for_today(Local::now());
+            self.ivars().modes.set(modes);
+            self.refresh_menu();
+        }
+
+        #[unsafe(method(showTitle:))]
+        fn show_title(&self, _sender: &NSMenuItem) {
+            let mut modes = self.ivars().modes.get();
+            modes.unhide();
+            self.ivars().modes.set(modes);
+            self.refresh_menu();
+        }
+
+        #[unsafe(method(resumeApp:))]
+        fn resume_app(&self, _sender: &NSMenuItem) {
+            let mut modes = self.ivars().modes.get();
+            modes.pause.resume();
+            self.ivars().modes.set(modes);
+            self.refresh_menu();
+        }
     }
 );
 
 impl MenuDelegate {
     pub fn new(
         mtm: MainThreadMarker,
-        dismissed_events: Arc<Mutex<HashSet<String>>>,
+        dismissed_events: Arc<Mutex<HashSet<OccurrenceRef>>>,
         event_store: Retained<EKEventStore>,
         status_item: Retained<NSStatusItem>,
+        config: Config,
+        notes: LocalNotes,
+        watch: Option<WatchSocket>,
     ) -> Retained<Self> {
         let this = mtm.alloc();
         let this = this.set_ivars(Ivars {
@@ -127,49 +587,827 @@ impl MenuDelegate {
             event_store,
             status_item,
             timer: Cell::new(None),
+            retry_timer: Cell::new(None),
+            poll_timer: Cell::new(None),
+            backoff: Cell::new(Backoff::default()),
+            events: RefCell::new(EventCollection::default()),
+            modes: Cell::new(Modes::default()),
+            config: RefCell::new(config),
+            title: RefCell::new(StatusTitle::default()),
+            menu: app_kit::init_menu(mtm, ns_string!("")),
+            pending_click: Cell::new(None),
+            row_titles: RefCell::new(RowTitleCache::default()),
+            joined: RefCell::new(None),
+            hidden_title_length: Cell::new(None),
+            simulated_at: Cell::new(None),
+            fixture: RefCell::new(None),
+            screens_asleep: Cell::new(false),
+            health: RefCell::new(FetchHealth::default()),
+            notes: RefCell::new(notes),
+            speech: RefCell::new(None),
+            watch,
+            last_refresh: Cell::new(None),
         });
         let delegate: Retained<Self> = init_objc_super!(this);
-        
-        // Create a timer that fires every 60 seconds to refresh the title
-        unsafe {
-            let timer = NSTimer::scheduledTimerWithTimeInterval_target_selector_userInfo_repeats(
-                60.0,
-                &delegate,
+        if delegate
+            .ivars()
+            .config
+            .borrow()
+            .notifications
+            .minutes_before
+            .is_some()
+        {
+            user_notifications::set_up(&*delegate);
+        }
+        app_kit::set_menu_delegate(&delegate.ivars().menu, Some(&*delegate));
+        delegate.install_menu(&delegate.ivars().config.borrow().click);
+        delegate.start_timer();
+        delegate.start_poll_timer();
+        delegate
+    }
+
+    /// Starts the timer refreshing the title every `sync.refresh-seconds`,
+    /// unless it's already running at that interval.
+    fn start_timer(&self) {
+        let interval = self.ivars().config.borrow().sync.refresh_interval() as f64;
+        if let Some(timer) = self.ivars().timer.take() {
+            if timer.timeInterval() == interval {
+                self.ivars().timer.set(Some(timer));
+                return;
+            }
+            timer.invalidate();
+        }
+
+        let timer = unsafe {
+            NSTimer::scheduledTimerWithTimeInterval_target_selector_userInfo_repeats(
+                interval,
+                self,
                 objc2::sel!(timerFired:),
                 None,
                 true,
+            )
+        };
+        timer.setTolerance(timer_tolerance());
+        self.ivars().timer.set(Some(timer));
+    }
+
+    /// (Re)schedules the sync poll per the config, or stops it when disabled.
+    fn start_poll_timer(&self) {
+        if let Some(timer) = self.ivars().poll_timer.take() {
+            timer.invalidate();
+        }
+        let Some(minutes) = self.ivars().config.borrow().sync.poll_interval() else {
+            return;
+        };
+
+        let timer = unsafe {
+            NSTimer::scheduledTimerWithTimeInterval_target_selector_userInfo_repeats(
+                minutes as f64 * 60.0,
+                self,
+                objc2::sel!(pollSources:),
+                None,
+                true,
+            )
+        };
+        timer.setTolerance(60.0);
+        self.ivars().poll_timer.set(Some(timer));
+    }
+
+    fn suspend_timers(&self) {
+        let timers = [
+            self.ivars().timer.take(),
+            self.ivars().retry_timer.take(),
+            self.ivars().poll_timer.take(),
+        ];
+        for timer in timers.into_iter().flatten() {
+            timer.invalidate();
+        }
+    }
+
+    /// Restarts the timers after sleep, refreshing right away since the title
+    /// is stale by now. A failed fetch schedules its own retry.
+    fn resume_timers(&self) {
+        self.ivars().screens_asleep.set(false);
+        self.start_timer();
+        self.start_poll_timer();
+        self.refresh_menu();
+    }
+
+    /// Refetches the next days' events and updates the title. The menu is
+    /// rebuilt, with all its days, once it next opens.
+    pub fn refresh_menu(&self) {
+        // One instant for the whole refresh, so the title and menu agree on it
+        // even across a minute or day boundary
+        let clock = FrozenClock(self.ivars().simulated_at.get().unwrap_or_else(Local::now));
+        // While paused the events fetched as it started and the title stay
+        // frozen; the regular timer keeps calling in here, which is what resumes
+        // once the pause lapses
+        let paused = self
+            .ivars()
+            .modes
+            .get()
+            .pause
+            .remaining(clock.now())
+            .is_some();
+        let events = if paused {
+            self.ivars().events.borrow().clone()
+        } else {
+            let horizon = self.ivars().config.borrow().menu.horizon();
+            self.fetch_events(&clock, horizon.min(TITLE_FETCH_DAYS))
+        };
+
+        let config = self.ivars().config.borrow();
+        // Advanced while paused too, so what started during a pause isn't
+        // acted on once it ends
+        let now = clock.now();
+        let last_refresh = self.ivars().last_refresh.replace(Some(now));
+        if !paused {
+            let title = self.build_title(&clock, &events, &config);
+            if self.ivars().title.borrow().text != title.text {
+                self.notify_watchers(WatchEvent::TitleChanged(&title.text));
+            }
+            self.ivars().title.replace(title);
+            if let Some(last_refresh) = last_refresh {
+                // After sleep, or with the timers stopped while the displays
+                // slept, the gap since the last refresh can be hours long.
+                // Looking back a couple of refreshes at most keeps the first one
+                // from notifying of, and pausing music for, everything that
+                // began meanwhile.
+                let interval = config.sync.refresh_interval() as i64;
+                let since = last_refresh.max(now - Duration::seconds(2 * interval));
+                self.handle_started(since, now, &events, &config);
+                self.post_notifications(since, now, &events, &config);
+            }
+        }
+        self.ivars().title.borrow_mut().paused = paused;
+
+        self.update_status_button();
+    }
+
+    /// Previews the app at `at` rather than the current time and, given a
+    /// fixture, with its events rather than the calendar's.
+    pub fn simulate(&self, at: Option<DateTime<Local>>, fixture: Option<EventCollection>) {
+        self.ivars().simulated_at.set(at);
+        self.ivars().fixture.replace(fixture);
+        self.refresh_menu();
+    }
+
+    /// Re-reads the time zone and refreshes, after the zone, the clock, or
+    /// the date changed.
+    fn handle_time_change(&self) {
+        // Foundation caches the zone until told otherwise; chrono picks
+        // up the new one by itself
+        NSTimeZone::resetSystemTimeZone();
+        let events = self.ivars().events.borrow().in_current_time_zone();
+        self.ivars().events.replace(events);
+        let fixture = self
+            .ivars()
+            .fixture
+            .borrow()
+            .as_ref()
+            .map(EventCollection::in_current_time_zone);
+        self.ivars().fixture.replace(fixture);
+        let simulated_at = self.ivars().simulated_at.get();
+        self.ivars()
+            .simulated_at
+            .set(simulated_at.map(|at| at.with_timezone(&Local)));
+        self.refresh_menu();
+    }
+
+    fn notify_watchers(&self, event: WatchEvent) {
+        if let Some(watch) = &self.ivars().watch {
+            let now = self.ivars().simulated_at.get().unwrap_or_else(Local::now);
+            watch.send(event, now);
+        }
+    }
+
+    /// Reacts to the timed events that began since the last refresh and are
+    /// still going: watchers hear of each, and music pauses for calls that
+    /// weren't dismissed and that `alerts_for` lets interrupt.
+    fn handle_started(
+        &self,
+        since: DateTime<Local>,
+        now: DateTime<Local>,
+        events: &EventCollection,
+        config: &Config,
+    ) {
+        let mut started_call = false;
+        for event in events.iter().filter(|e| {
+            since < e.start
+                && e.start <= now
+                && e.end > now
+                && !is_all_day(&e.start, &e.end)
+                && !e.is_muted(&config.calendars)
+        }) {
+            self.notify_watchers(WatchEvent::EventStarted(event));
+            started_call |= event.join_url(&config.join.prefer).is_some()
+                && events.alerts_for(event, config)
+                && self
+                    .ivars()
+                    .dismissed_events
+                    .lock()
+                    .is_ok_and(|dismissed| !dismissed.contains(&event.occurrence));
+        }
+        if started_call && config.join.pause_media && !self.is_silenced(now, config) {
+            media::pause_players();
+        }
+    }
+
+    /// Hidden for today from the menu, or within the `[out-of-office]` range:
+    /// nothing should interrupt, whatever the event.
+    fn is_silenced(&self, now: DateTime<Local>, config: &Config) -> bool {
+        self.ivars().modes.get().is_hidden(now)
+            || config
+                .out_of_office
+                .as_ref()
+                .is_some_and(|ooo| ooo.contains(now))
+    }
+
+    /// Notifies of the events `[notifications]` says are coming up, and of a
+    /// next meeting about to overlap the current one, for the events that
+    /// could drive the countdown. Quiet mode and `[quiet-hours]` hold back all
+    /// but important events, which are also sent as time-sensitive; a
+    /// silenced day holds back everything.
+    fn post_notifications(
+        &self,
+        since: DateTime<Local>,
+        now: DateTime<Local>,
+        events: &EventCollection,
+        config: &Config,
+    ) {
+        let Some(minutes_before) = config.notifications.minutes_before else {
+            return;
+        };
+        if self.is_silenced(now, config) {
+            return;
+        }
+        let quiet = self.ivars().modes.get().quiet.remaining(now).is_some()
+            || config
+                .quiet_hours
+                .as_ref()
+                .is_some_and(|hours| hours.contains(now));
+        let Ok(dismissed) = self.ivars().dismissed_events.lock() else {
+            eprintln!("Error: Failed to acquire lock when posting notifications");
+            return;
+        };
+        let notes = self.ivars().notes.borrow();
+        let post = |event: &EventInfo, title: &str, body: &str| {
+            let important = notes.is_important(event);
+            if quiet && !important {
+                return;
+            }
+            // Sharing the event's identifier, the usual banner replaces an
+            // overlap warning once it's due
+            user_notifications::post(
+                &event.occurrence.to_token(),
+                title,
+                body,
+                event.join_url(&config.join.prefer).is_some(),
+                None,
+                important,
             );
-            delegate.ivars().timer.set(Some(timer));
+        };
+
+        for event in events.due_notifications(since, now, minutes_before, &dismissed, config) {
+            post(event, &event.title, &notification_body(event, now));
         }
-        
-        delegate
+        for (current, next) in events.due_overlap_warnings(since, now, &dismissed, config) {
+            post(
+                next,
+                &format!("⚠ {}", next.title),
+                &format!(
+                    "{} · before {} ends",
+                    notification_body(next, now),
+                    current.title
+                ),
+            );
+        }
+    }
+
+    /// Acts on a button pressed on one of `post_notifications`' banners.
+    fn handle_notification_action(&self, occurrence: OccurrenceRef, action: NotificationAction) {
+        let Some(event) = self.find_event(&occurrence) else {
+            eprintln!("Error: Notified event is no longer listed");
+            return;
+        };
+        match action {
+            NotificationAction::Join => {
+                let prefer = self.ivars().config.borrow().join.prefer.clone();
+                if let Some(url) = event.join_url(&prefer) {
+                    self.join_meeting(url.to_string());
+                }
+            }
+            NotificationAction::Snooze => {
+                let prefer = &self.ivars().config.borrow().join.prefer;
+                let at = Local::now() + Duration::minutes(SNOOZE_MINUTES);
+                user_notifications::post(
+                    &occurrence.to_token(),
+                    &event.title,
+                    &notification_body(&event, at),
+                    event.join_url(prefer).is_some(),
+                    Some((SNOOZE_MINUTES * 60) as f64),
+                    self.ivars().notes.borrow().is_important(&event),
+                );
+            }
+            NotificationAction::Dismiss => self.dismiss(occurrence),
+        }
+    }
+
+    /// Hides the occurrence from the title and menu, along with a snoozed
+    /// notification of it.
+    fn dismiss(&self, occurrence: OccurrenceRef) {
+        self.notify_watchers(WatchEvent::Dismissed(
+            &occurrence,
+            self.find_event(&occurrence).as_ref(),
+        ));
+        if self
+            .ivars()
+            .config
+            .borrow()
+            .notifications
+            .minutes_before
+            .is_some()
+        {
+            user_notifications::withdraw(&occurrence.to_token());
+        }
+        if let Ok(mut dismissed) = self.ivars().dismissed_events.lock() {
+            dismissed.insert(occurrence);
+        } else {
+            eprintln!("Error: Failed to acquire lock when dismissing event");
+            return;
+        }
+
+        self.refresh_menu();
     }
 
-    fn refresh_menu(&self) {
-        let events = EventCollection::fetch(&self.ivars().event_store);
+    /// The occurrence among the events on display, fixture ones included.
+    fn find_event(&self, occurrence: &OccurrenceRef) -> Option<EventInfo> {
+        let find = |events: &EventCollection| events.find_occurrence(occurrence).cloned();
+        match self.ivars().fixture.borrow().as_ref() {
+            Some(fixture) => find(fixture),
+            None => find(&self.ivars().events.borrow()),
+        }
+    }
 
-        let title = match self.ivars().dismissed_events.lock() {
-            Ok(dismissed_set) => events.get_title(&dismissed_set),
+    /// Today's and the following `days - 1` days' events, or the fixture's.
+    fn fetch_events(&self, clock: &dyn Clock, days: u32) -> EventCollection {
+        if let Some(fixture) = self.ivars().fixture.borrow().as_ref() {
+            self.ivars()
+                .health
+                .borrow_mut()
+                .succeeded("Fixture", clock.now());
+            return fixture.clone();
+        }
+        let config = self.ivars().config.borrow();
+        let fetched = EventCollection::fetch(&self.ivars().event_store, clock, days, &config);
+        drop(config);
+        match fetched {
+            Ok(events) => {
+                self.ivars()
+                    .health
+                    .borrow_mut()
+                    .succeeded("EventKit", clock.now());
+                let mut backoff = self.ivars().backoff.get();
+                backoff.reset();
+                self.ivars().backoff.set(backoff);
+                if let Some(retry_timer) = self.ivars().retry_timer.take() {
+                    retry_timer.invalidate();
+                }
+                self.ivars().events.replace(events.clone());
+                events
+            }
+            Err(e) => {
+                eprintln!("Error: Failed to fetch events: {}", e);
+                self.ivars().health.borrow_mut().failed("EventKit", &e);
+                self.schedule_retry();
+                self.ivars().events.borrow().clone()
+            }
+        }
+    }
+
+    fn build_title(
+        &self,
+        clock: &dyn Clock,
+        events: &EventCollection,
+        config: &Config,
+    ) -> StatusTitle {
+        if let Some(ooo) = config
+            .out_of_office
+            .as_ref()
+            .filter(|ooo| ooo.contains(clock.now()))
+        {
+            return StatusTitle {
+                text: ooo.title(),
+                ..Default::default()
+            };
+        }
+
+        let title_config = &config.title;
+        let joined = self.ivars().joined.borrow();
+        match self.ivars().dismissed_events.lock() {
+            Ok(dismissed_set) => {
+                let full_title = events.get_title(clock, &dismissed_set, joined.as_ref(), config);
+                let glance = events.today_summary(clock, &dismissed_set, config);
+                let calendar_color = title_config
+                    .calendar_color
+                    .then(|| events.find_cur_or_next(clock, &dismissed_set, config))
+                    .flatten()
+                    .map(|status| status.event().calendar_color);
+                match title_config.mode {
+                    TitleMode::Full => StatusTitle {
+                        text: full_title,
+                        tooltip: Some(glance),
+                        calendar_color,
+                        ..Default::default()
+                    },
+                    TitleMode::TimeOnly => StatusTitle {
+                        text: events.get_time_only_title(
+                            clock,
+                            &dismissed_set,
+                            joined.as_ref(),
+                            config,
+                        ),
+                        tooltip: Some(format!("{}\n{}", full_title, glance)),
+                        calendar_color,
+                        ..Default::default()
+                    },
+                }
+            }
             Err(e) => {
                 eprintln!("Error: Failed to acquire lock in refresh_menu: {}", e);
-                "Calendar".to_string()
+                StatusTitle {
+                    text: "Calendar".to_string(),
+                    ..Default::default()
+                }
             }
+        }
+    }
+
+    /// Attaches the menu to the status item when clicking should open it, and
+    /// otherwise routes clicks through `statusItemClicked:`.
+    fn install_menu(&self, click: &ClickConfig) {
+        let status_item = &self.ivars().status_item;
+        let Some(button) = status_item.button(self.ivars().mtm) else {
+            return;
         };
 
-        let menu = MenuBuilder::new(
-            events.into_vec(),
-            self,
-            &self.ivars().dismissed_events,
-            self.ivars().mtm,
-        )
-        .build();
+        if click.primary == ClickAction::Menu && click.double.is_none() {
+            status_item.setMenu(Some(&self.ivars().menu));
+            unsafe { button.setAction(None) };
+        } else {
+            status_item.setMenu(None);
+            app_kit::set_control_target(&button, Some(self));
+            unsafe { button.setAction(Some(objc2::sel!(statusItemClicked:))) };
+            button.sendActionOn(NSEventMask::LeftMouseUp | NSEventMask::RightMouseUp);
+        }
+    }
+
+    fn perform_click_action(&self, action: ClickAction) {
+        match action {
+            ClickAction::Menu => self.show_menu(),
+            ClickAction::JoinNext => match self.next_meeting_url() {
+                Some(url) => self.join_meeting(url),
+                None => self.show_menu(),
+            },
+            ClickAction::OpenCalendar => open_url_string("ical://"),
+            ClickAction::OpenCurrentEvent => {
+                let google_web = self.ivars().config.borrow().google_calendar_web;
+                let url = self.with_cur_or_next(|event| match &event.google_url {
+                    Some(google_url) if google_web => google_url.clone(),
+                    _ => calendar_url(&event.event_id, event.has_recurrence),
+                });
+                // Goes through the link handling so the web link opens in the
+                // configured browser
+                self.join_meeting(url.unwrap_or_else(|| "ical://".to_string()));
+            }
+        }
+    }
+
+    /// Opens a link the way `[join.open]` says for its service. Slack huddles
+    /// go to the app unless configured otherwise, and other web links go to the
+    /// configured browser, in `browser-profile` for meetings. Call links mark
+    /// their meeting as joined.
+    fn join_meeting(&self, url_string: String) {
+        let joined_url = url_string.clone();
+        let config = self.ivars().config.borrow();
+        let service = ServiceInfo::from_url(&url_string);
+        let open_with = config
+            .join
+            .open
+            .get(&service)
+            .cloned()
+            .or((service == ServiceInfo::Slack).then_some(OpenWith::App));
+        let browser = config.browser.as_deref();
+        let profile = (service != ServiceInfo::Generic)
+            .then_some(config.browser_profile.as_deref())
+            .flatten();
+
+        match open_with {
+            Some(OpenWith::App) => {
+                open_url_string(&service.native_url(&url_string).unwrap_or(url_string))
+            }
+            Some(OpenWith::Browser) => open_url_in(&url_string, browser, profile),
+            Some(OpenWith::Application(bundle_id)) => {
+                open_url_in(&url_string, Some(&bundle_id), None)
+            }
+            None if (browser.is_some() || profile.is_some()) && is_web_url(&url_string) => {
+                open_url_in(&url_string, browser, profile)
+            }
+            None => open_url_string(&url_string),
+        }
+        drop(config);
+        self.mark_joined(&joined_url);
+    }
+
+    fn cancel_pending_click(&self) {
+        if let Some(timer) = self.ivars().pending_click.take() {
+            timer.invalidate();
+        }
+    }
 
+    /// Pops the menu up from the status item, whether or not clicking it
+    /// would.
+    pub fn show_menu(&self) {
         let status_item = &self.ivars().status_item;
+        let Some(button) = status_item.button(self.ivars().mtm) else {
+            return;
+        };
+        status_item.setMenu(Some(&self.ivars().menu));
+        unsafe { button.performClick(None) };
+        self.install_menu(&self.ivars().config.borrow().click);
+    }
+
+    /// Remembers the current or next meeting as joined when `url` is one of
+    /// its call links.
+    fn mark_joined(&self, url: &str) {
+        if ServiceInfo::from_url(url) == ServiceInfo::Generic {
+            return;
+        }
+        let Some(occurrence) = self
+            .with_cur_or_next(|event| {
+                event
+                    .links()
+                    .contains(&url)
+                    .then(|| event.occurrence.clone())
+            })
+            .flatten()
+        else {
+            return;
+        };
+        if self.ivars().joined.replace(Some(occurrence.clone())) != Some(occurrence.clone()) {
+            if let Some(event) = self.find_event(&occurrence) {
+                self.notify_watchers(WatchEvent::Joined(&event, url));
+            }
+            self.refresh_menu();
+        }
+    }
+
+    fn next_meeting_url(&self) -> Option<String> {
+        let prefer = self.ivars().config.borrow().join.prefer.clone();
+        self.with_cur_or_next(|event| event.join_url(&prefer).map(str::to_string))
+            .flatten()
+    }
+
+    /// Saves the follow-up and opens it in Calendar, where attendees can be
+    /// invited.
+    fn create_follow_up(&self, occurrence: &OccurrenceRef) {
+        let created = {
+            let events = self.ivars().events.borrow();
+            let Some(event) = events.find_occurrence(occurrence) else {
+                eprintln!("Error: Event to follow up is no longer listed");
+                return;
+            };
+            let length = Duration::minutes(FOLLOW_UP_MINUTES);
+            let start = events.next_free_slot(Local::now(), length);
+            let end = start + length;
+            event_kit::create_follow_up(
+                &self.ivars().event_store,
+                &event.event_id,
+                &format!("Follow-up: {}", event.title),
+                &NSDate::dateWithTimeIntervalSince1970(start.timestamp() as f64),
+                &NSDate::dateWithTimeIntervalSince1970(end.timestamp() as f64),
+            )
+        };
+
+        match created {
+            Ok(event_id) => open_url_string(&calendar_url(&event_id, false)),
+            Err(e) => eprintln!("Error: Failed to create follow-up: {}", e),
+        }
+    }
+
+    fn with_cur_or_next<T>(&self, f: impl FnOnce(&EventInfo) -> T) -> Option<T> {
+        let events = self.ivars().events.borrow();
+        let dismissed = self.ivars().dismissed_events.lock().ok()?;
+        let config = self.ivars().config.borrow();
+        let status = events.find_cur_or_next(&SystemClock, &dismissed, &config)?;
+        Some(f(status.event()))
+    }
+
+    /// Schedules a one-shot refetch unless one is already pending, backing off
+    /// exponentially while the store stays unavailable. There's no polling while
+    /// asleep, nor in low power mode, where the minute timer's refetch has to do.
+    fn schedule_retry(&self) {
+        if self.ivars().screens_asleep.get() || is_low_power_mode() {
+            return;
+        }
+        let retry_timer = self.ivars().retry_timer.take();
+        if retry_timer.is_some() {
+            self.ivars().retry_timer.set(retry_timer);
+            return;
+        }
+
+        let mut backoff = self.ivars().backoff.get();
+        let delay = backoff.next_delay();
+        self.ivars().backoff.set(backoff);
+
+        let timer = unsafe {
+            NSTimer::scheduledTimerWithTimeInterval_target_selector_userInfo_repeats(
+                delay.as_secs_f64(),
+                self,
+                objc2::sel!(retryFetch:),
+                None,
+                false,
+            )
+        };
+        self.ivars().retry_timer.set(Some(timer));
+    }
+
+    pub fn reload_config(&self, config: Config) {
+        if config.notifications.minutes_before.is_some() {
+            user_notifications::set_up(self);
+        }
+        self.ivars().config.replace(config);
+        self.ivars().hidden_title_length.set(None);
+        self.install_menu(&self.ivars().config.borrow().click);
+        self.start_timer();
+        self.start_poll_timer();
+        self.refresh_menu();
+    }
+
+    fn update_status_button(&self) {
+        let Some(button) = self.ivars().status_item.button(self.ivars().mtm) else {
+            return;
+        };
+        let title = self.ivars().title.borrow();
+        let text = if title.paused {
+            format!("⏸ {}", title.text).trim_end().to_string()
+        } else {
+            title.text.clone()
+        };
+
+        let icon_only = text.is_empty()
+            || self.ivars().modes.get().is_hidden(Local::now())
+            || self.ivars().config.borrow().title.icon_only_when_hidden
+                && self.is_title_hidden(&button, text.chars().count());
+
+        if icon_only {
+            button.setTitle(ns_string!(""));
+            button.setImage(Icon::Calendar.load().as_deref());
+        } else {
+            let dot = title.calendar_color.and_then(|(red, green, blue)| {
+                Icon::load_colored(&NSColor::colorWithDisplayP3Red_green_blue_alpha(
+                    red, green, blue, 1.0,
+                ))
+            });
+            button.setImage(dot.as_deref());
+            button.setImagePosition(NSCellImagePosition::ImageLeft);
+            button.setTitle(&NSString::from_str(&text));
+        }
+
+        button.setToolTip(title.tooltip.as_deref().map(NSString::from_str).as_deref());
+    }
+
+    /// macOS silently hides status items that don't fit in a crowded menu bar; the
+    /// only signal is the button's window losing its visible occlusion state. The
+    /// hidden title length is remembered so that shorter titles get shown again.
+    fn is_title_hidden(&self, button: &NSStatusBarButton, title_length: usize) -> bool {
+        let hidden_title_length = &self.ivars().hidden_title_length;
+        let occluded = button.window().is_some_and(|window| {
+            !window
+                .occlusionState()
+                .contains(NSWindowOcclusionState::Visible)
+        });
 
-        if let Some(button) = status_item.button(self.ivars().mtm) {
-            button.setTitle(&NSString::from_str(&title));
+        if occluded && button.title().length() > 0 {
+            hidden_title_length.set(Some(title_length));
         }
 
-        status_item.setMenu(Some(&menu));
+        hidden_title_length
+            .get()
+            .is_some_and(|hidden_length| title_length >= hidden_length)
+    }
+}
+
+/// Lets macOS coalesce the minute timer with other wakeups in low power mode,
+/// at the cost of the countdown lagging by up to half a minute.
+fn timer_tolerance() -> f64 {
+    if is_low_power_mode() {
+        30.0
+    } else {
+        0.0
+    }
+}
+
+fn is_low_power_mode() -> bool {
+    NSProcessInfo::processInfo().isLowPowerModeEnabled()
+}
+
+/// "10:00–10:30 · in 5m", as of `at`.
+fn notification_body(event: &EventInfo, at: DateTime<Local>) -> String {
+    let times = format!("{}–{}", format_time(&event.start), format_time(&event.end));
+    if event.start > at {
+        format!("{} · in {}", times, format_duration(event.start - at))
+    } else {
+        format!("{} · now", times)
+    }
+}
+
+/// The occurrences of a menu item carrying newline-separated tokens.
+fn represented_occurrences(sender: &NSMenuItem) -> Option<Vec<OccurrenceRef>> {
+    let tokens = ns_menu_item_represented_object_to_string(&sender.representedObject()?);
+    let occurrences = tokens
+        .lines()
+        .filter_map(|token| {
+            let occurrence = OccurrenceRef::from_token(token);
+            if occurrence.is_none() {
+                eprintln!("Error: Invalid occurrence: {}", token);
+            }
+            occurrence
+        })
+        .collect();
+    Some(occurrences)
+}
+
+/// The occurrence a menu item was built for, carried as its token.
+fn represented_occurrence(sender: &NSMenuItem) -> Option<OccurrenceRef> {
+    let token = ns_menu_item_represented_object_to_string(&sender.representedObject()?);
+    let occurrence = OccurrenceRef::from_token(&token);
+    if occurrence.is_none() {
+        eprintln!("Error: Invalid occurrence: {}", token);
+    }
+    occurrence
+}
+
+pub(super) fn calendar_url(event_id: &str, has_recurrence: bool) -> String {
+    // Calendar.app can't open a single occurrence of a recurring series
+    if has_recurrence {
+        "ical://".to_string()
+    } else {
+        format!("ical://ekevent/{}", event_id)
+    }
+}
+
+fn open_url_string(url_string: &str) {
+    if let Some(url) = NSURL::URLWithString(&NSString::from_str(url_string)) {
+        NSWorkspace::sharedWorkspace().openURL(&url);
+    } else {
+        eprintln!("Error: Failed to create URL from: {}", url_string);
+    }
+}
+
+/// Chrome, Edge, and the other browsers that take `--profile-directory`.
+fn is_chromium(application: &NSURL) -> bool {
+    const CHROMIUM_BUNDLE_PREFIXES: &[&str] = &[
+        "com.google.Chrome",
+        "com.microsoft.edgemac",
+        "com.brave.Browser",
+        "com.vivaldi.Vivaldi",
+        "org.chromium.Chromium",
+    ];
+    app_kit::bundle_identifier(application).is_some_and(|bundle_id| {
+        CHROMIUM_BUNDLE_PREFIXES
+            .iter()
+            .any(|prefix| bundle_id.starts_with(prefix))
+    })
+}
+
+fn is_web_url(url_string: &str) -> bool {
+    let scheme = url_string.split_once("://").map(|(scheme, _)| scheme);
+    scheme.is_some_and(|scheme| {
+        scheme.eq_ignore_ascii_case("https") || scheme.eq_ignore_ascii_case("http")
+    })
+}
+
+/// Opens `url_string` in a specific application, or the default browser when
+/// `bundle_id` is `None`, falling back to the system handler if it's missing.
+/// `profile` selects a profile directory when that's a Chromium browser, and
+/// is left out otherwise.
+fn open_url_in(url_string: &str, bundle_id: Option<&str>, profile: Option<&str>) {
+    let Some(url) = NSURL::URLWithString(&NSString::from_str(url_string)) else {
+        eprintln!("Error: Failed to create URL from: {}", url_string);
+        return;
+    };
+    match app_kit::application_url(bundle_id) {
+        Some(application) => match profile.filter(|_| is_chromium(&application)) {
+            Some(profile) => app_kit::open_url_in_profile(&url, &application, profile),
+            None => app_kit::open_url_with_application(&url, &application),
+        },
+        None => {
+            eprintln!(
+                "Error: No application found for {}",
+                bundle_id.unwrap_or("web links")
+            );
+            NSWorkspace::sharedWorkspace().openURL(&url);
+        }
     }
 }
@@ -0,0 +1,109 @@
+use objc2_foundation::NSRange;
+use std::ops::Range;
+
+/// Length of `text` in UTF-16 code units, the unit NSString ranges are measured in.
+pub fn utf16_len(text: &str) -> usize {
+    text.encode_utf16().count()
+}
+
+/// Converts a byte range of `text` into an NSRange over the same characters.
+///
+/// Emoji and other non-BMP characters take two UTF-16 code units, so neither
+/// byte offsets nor `chars().count()` can be used for NSRange directly.
+pub fn utf16_range(text: &str, range: Range<usize>) -> NSRange {
+    NSRange::new(utf16_len(&text[..range.start]), utf16_len(&text[range]))
+}
+
+/// What a stretch of row text is for, which decides how it's colored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TextRole {
+    /// End times, room names, past events
+    Secondary,
+    /// The end time of a past event
+    Tertiary,
+    /// Needs attention now, like the "Join" on a meeting about to start
+    Urgent,
+    /// Something went wrong, like a failed refresh
+    Error,
+}
+
+/// The system color a `TextRole` is drawn in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shade {
+    SecondaryLabel,
+    TertiaryLabel,
+    Accent,
+    /// Full-contrast label color, set in bold so it stands out without hue
+    BoldLabel,
+    /// Adjusts for "Increase contrast" by itself
+    Red,
+}
+
+impl TextRole {
+    /// With "Increase contrast" on, tertiary text is too faint to read and a
+    /// light accent color can wash out against the menu, so text steps up to
+    /// secondary and urgency is shown by weight instead.
+    pub fn shade(self, increase_contrast: bool) -> Shade {
+        match (self, increase_contrast) {
+            (Self::Secondary, _) => Shade::SecondaryLabel,
+            (Self::Tertiary, false) => Shade::TertiaryLabel,
+            (Self::Tertiary, true) => Shade::SecondaryLabel,
+            (Self::Urgent, false) => Shade::Accent,
+            (Self::Urgent, true) => Shade::BoldLabel,
+            (Self::Error, _) => Shade::Red,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_utf16_len() {
+        assert_eq!(utf16_len("Standup"), 7);
+        assert_eq!(utf16_len("会議"), 2);
+        assert_eq!(utf16_len("🎉"), 2);
+    }
+
+    #[test]
+    fn test_text_role_shades() {
+        assert_eq!(TextRole::Secondary.shade(false), Shade::SecondaryLabel);
+        assert_eq!(TextRole::Tertiary.shade(false), Shade::TertiaryLabel);
+        assert_eq!(TextRole::Urgent.shade(false), Shade::Accent);
+        assert_eq!(TextRole::Error.shade(false), Shade::Red);
+    }
+
+    #[test]
+    fn test_text_role_shades_increase_contrast() {
+        assert_eq!(TextRole::Secondary.shade(true), Shade::SecondaryLabel);
+        assert_eq!(TextRole::Tertiary.shade(true), Shade::SecondaryLabel);
+        assert_eq!(TextRole::Urgent.shade(true), Shade::BoldLabel);
+        assert_eq!(TextRole::Error.shade(true), Shade::Red);
+    }
+
+    #[test]
+    fn test_utf16_range_ascii() {
+        let range = utf16_range("09:00 - 10:00 Standup", 6..13);
+        assert_eq!((range.location, range.length), (6, 7));
+    }
+
+    #[test]
+    fn test_utf16_range_after_emoji() {
+        let text = "🎉 Party";
+        let range = utf16_range(text, text.find("Party").unwrap()..text.len());
+        assert_eq!((range.location, range.length), (3, 5));
+    }
+
+    #[test]
+    fn test_utf16_range_cjk() {
+        let text = "All day: 会議 🗓️ review";
+        let range = utf16_range(text, 0..text.len());
+        assert_eq!((range.location, range.length), (0, utf16_len(text)));
+
+        let start = text.find("review").unwrap();
+        let range = utf16_range(text, start..text.len());
+        assert_eq!(range.location, 16);
+        assert_eq!(range.length, 6);
+    }
+}
@@ -0,0 +1,93 @@
+use chrono::{DateTime, Local};
+
+use crate::calendar::format_time;
+
+/// How the last fetch went, so a stale menu can be told apart from an empty
+/// calendar.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FetchHealth {
+    /// "EventKit", or "Fixture" while previewing one
+    source: &'static str,
+    updated: Option<DateTime<Local>>,
+    error: Option<String>,
+}
+
+impl Default for FetchHealth {
+    fn default() -> Self {
+        Self {
+            source: "EventKit",
+            updated: None,
+            error: None,
+        }
+    }
+}
+
+impl FetchHealth {
+    pub fn succeeded(&mut self, source: &'static str, at: DateTime<Local>) {
+        self.source = source;
+        self.updated = Some(at);
+        self.error = None;
+    }
+
+    /// Keeps the time of the last success, which the menu still shows.
+    pub fn failed(&mut self, source: &'static str, error: impl ToString) {
+        self.source = source;
+        self.error = Some(error.to_string());
+    }
+
+    /// "Couldn't refresh: Calendar store unavailable" from the failure of the
+    /// last fetch, until one succeeds.
+    pub fn problem(&self) -> Option<String> {
+        self.error
+            .as_ref()
+            .map(|error| format!("Couldn't refresh: {}", error))
+    }
+
+    /// "Updated 14:32 · EventKit OK", or the error once a fetch failed.
+    pub fn footer(&self) -> String {
+        let updated = match self.updated {
+            Some(at) => format!("Updated {}", format_time(&at)),
+            None => "Not updated yet".to_string(),
+        };
+        match &self.error {
+            Some(error) => format!("{} · {}: {}", updated, self.source, error),
+            None => format!("{} · {} OK", updated, self.source),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendar::parse_local_time;
+
+    #[test]
+    fn test_footer() {
+        let mut health = FetchHealth::default();
+        assert_eq!(health.footer(), "Not updated yet · EventKit OK");
+
+        health.succeeded("EventKit", parse_local_time("2025-03-07 14:32").unwrap());
+        assert_eq!(health.footer(), "Updated 14:32 · EventKit OK");
+
+        health.failed("EventKit", "Calendar store unavailable");
+        assert_eq!(
+            health.footer(),
+            "Updated 14:32 · EventKit: Calendar store unavailable"
+        );
+    }
+
+    #[test]
+    fn test_problem_clears_on_success() {
+        let mut health = FetchHealth::default();
+        assert_eq!(health.problem(), None);
+
+        health.failed("EventKit", "Calendar store unavailable");
+        assert_eq!(
+            health.problem().as_deref(),
+            Some("Couldn't refresh: Calendar store unavailable")
+        );
+
+        health.succeeded("EventKit", parse_local_time("2025-03-07 14:32").unwrap());
+        assert_eq!(health.problem(), None);
+    }
+}
@@ -1,10 +1,17 @@
 use objc2::rc::Retained;
 use objc2_app_kit::{NSColor, NSImage};
 use objc2_foundation::NSData;
+use std::cell::RefCell;
+use std::collections::HashMap;
 
 use crate::calendar::Icon;
 use crate::ffi::app_kit;
 
+thread_local! {
+    /// Icons are decoded from SVG once and shared by every menu rebuild.
+    static ICON_CACHE: RefCell<HashMap<Icon, Retained<NSImage>>> = RefCell::new(HashMap::new());
+}
+
 impl Icon {
     fn data(self) -> &'static [u8] {
         match self {
@@ -18,6 +25,16 @@ impl Icon {
     }
 
     pub fn load(self) -> Option<Retained<NSImage>> {
+        if let Some(image) = ICON_CACHE.with_borrow(|cache| cache.get(&self).cloned()) {
+            return Some(image);
+        }
+
+        let image = self.decode()?;
+        ICON_CACHE.with_borrow_mut(|cache| cache.insert(self, image.clone()));
+        Some(image)
+    }
+
+    fn decode(self) -> Option<Retained<NSImage>> {
         let data = NSData::with_bytes(self.data());
         let image = app_kit::init_image_from_data(&data).or_else(|| {
             eprintln!("Error: Failed to create image from icon data: {:?}", self);
@@ -0,0 +1,200 @@
+use chrono::{DateTime, Local};
+
+use crate::calendar::{format_duration, format_time, is_all_day, EventCollection, EventInfo};
+use crate::config::Config;
+use crate::json;
+
+use super::days::day_groups;
+use super::delegate::calendar_url;
+
+/// An event that hasn't ended, as a launcher shows it.
+struct LauncherItem<'a> {
+    event: &'a EventInfo,
+    /// "Today · 10:00 - 11:00 · Work"
+    subtitle: String,
+    /// "in 5m", "now"
+    relative: String,
+    join_url: Option<&'a str>,
+    open_url: String,
+}
+
+/// Events from `clock`'s time through the menu's horizon, leaving out ones
+/// already over and those on muted calendars.
+fn launcher_items<'a>(
+    events: &'a EventCollection,
+    now: DateTime<Local>,
+    config: &Config,
+) -> Vec<LauncherItem<'a>> {
+    let mut items = Vec::new();
+    for group in day_groups(now.date_naive(), config.menu.horizon()) {
+        for event in events.iter().filter(|e| {
+            group.contains(e.start.date_naive()) && e.end > now && !e.is_muted(&config.calendars)
+        }) {
+            let time = if is_all_day(&event.start, &event.end) {
+                "All day".to_string()
+            } else if group.spans_days() {
+                format!(
+                    "{} {} - {}",
+                    event.start.format("%a"),
+                    format_time(&event.start),
+                    format_time(&event.end)
+                )
+            } else {
+                format!(
+                    "{} - {}",
+                    format_time(&event.start),
+                    format_time(&event.end)
+                )
+            };
+            let subtitle = match event.calendar_name.as_str() {
+                "" => format!("{} · {}", group.name, time),
+                calendar => format!("{} · {} · {}", group.name, time, calendar),
+            };
+            let relative = if event.start <= now {
+                "now".to_string()
+            } else {
+                format!("in {}", format_duration(event.start - now))
+            };
+            let open_url = match &event.google_url {
+                Some(url) if config.google_calendar_web => url.clone(),
+                _ => calendar_url(&event.event_id, event.has_recurrence),
+            };
+            items.push(LauncherItem {
+                event,
+                subtitle,
+                relative,
+                join_url: event.join_url(&config.join.prefer),
+                open_url,
+            });
+        }
+    }
+    items
+}
+
+/// Alfred's Script Filter JSON. Actioning an item joins its call, or opens
+/// it in Calendar when there's none; ⌘ always opens it in Calendar.
+pub fn render_alfred(events: &EventCollection, now: DateTime<Local>, config: &Config) -> String {
+    let items: Vec<String> = launcher_items(events, now, config)
+        .iter()
+        .map(|item| {
+            let (arg, action) = match item.join_url {
+                Some(url) => (url, "Join"),
+                None => (item.open_url.as_str(), "Open in Calendar"),
+            };
+            format!(
+                "{{\"uid\":{},\"title\":{},\"subtitle\":{},\"arg\":{},\"valid\":true,\
+                 \"mods\":{{\"cmd\":{{\"arg\":{},\"subtitle\":\"Open in Calendar\"}}}},\
+                 \"text\":{{\"copy\":{}}},\"match\":{}}}",
+                json::string(&item.event.occurrence.to_token()),
+                json::string(&item.event.title),
+                json::string(&format!(
+                    "{} · {} · ↵ {}",
+                    item.relative, item.subtitle, action
+                )),
+                json::string(arg),
+                json::string(&item.open_url),
+                json::string(arg),
+                json::string(&item.event.title),
+            )
+        })
+        .collect();
+
+    if items.is_empty() {
+        return "{\"items\":[{\"title\":\"No upcoming events\",\"valid\":false}]}".to_string();
+    }
+    format!("{{\"items\":[{}]}}", items.join(","))
+}
+
+/// Items for a Raycast extension's `List`: `title`, `subtitle`, and
+/// `accessories` map onto `List.Item` props, and each of `actions` onto an
+/// `Action.OpenInBrowser`, the first being the primary action.
+pub fn render_raycast(events: &EventCollection, now: DateTime<Local>, config: &Config) -> String {
+    let items: Vec<String> = launcher_items(events, now, config)
+        .iter()
+        .map(|item| {
+            let mut actions = Vec::new();
+            if let Some(url) = item.join_url {
+                actions.push(format!(
+                    "{{\"title\":\"Join\",\"url\":{}}}",
+                    json::string(url)
+                ));
+            }
+            actions.push(format!(
+                "{{\"title\":\"Open in Calendar\",\"url\":{}}}",
+                json::string(&item.open_url)
+            ));
+            format!(
+                "{{\"id\":{},\"title\":{},\"subtitle\":{},\"accessories\":[{{\"text\":{}}}],\
+                 \"actions\":[{}]}}",
+                json::string(&item.event.occurrence.to_token()),
+                json::string(&item.event.title),
+                json::string(&item.subtitle),
+                json::string(&item.relative),
+                actions.join(","),
+            )
+        })
+        .collect();
+    format!("{{\"items\":[{}]}}", items.join(","))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendar::parse_local_time;
+
+    fn events() -> EventCollection {
+        EventCollection::from_fixture(
+            r#"
+            [[event]]
+            title = "Standup"
+            start = "2025-03-07 09:00"
+            end = "2025-03-07 09:15"
+
+            [[event]]
+            title = "Review"
+            start = "2025-03-07 10:00"
+            end = "2025-03-07 11:00"
+            location = "https://zoom.us/j/123"
+            "#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_render_alfred() {
+        let now = parse_local_time("2025-03-07 09:55").unwrap();
+        let output = render_alfred(&events(), now, &Config::default());
+
+        // Standup is over
+        assert!(!output.contains("Standup"));
+        assert!(output.starts_with("{\"items\":[{\"uid\":"));
+        assert!(output.contains(
+            "\"title\":\"Review\",\
+             \"subtitle\":\"in 5m · Today · 10:00 - 11:00 · ↵ Join\",\
+             \"arg\":\"https://zoom.us/j/123\",\"valid\":true,\
+             \"mods\":{\"cmd\":{\"arg\":\"ical://ekevent/Review\",\"subtitle\":\"Open in Calendar\"}}"
+        ));
+    }
+
+    #[test]
+    fn test_render_alfred_empty() {
+        let now = parse_local_time("2025-03-07 23:00").unwrap();
+        assert_eq!(
+            render_alfred(&EventCollection::default(), now, &Config::default()),
+            "{\"items\":[{\"title\":\"No upcoming events\",\"valid\":false}]}"
+        );
+    }
+
+    #[test]
+    fn test_render_raycast() {
+        let now = parse_local_time("2025-03-07 09:55").unwrap();
+        let output = render_raycast(&events(), now, &Config::default());
+
+        assert!(output.contains(
+            "\"title\":\"Review\",\"subtitle\":\"Today · 10:00 - 11:00\",\
+             \"accessories\":[{\"text\":\"in 5m\"}],\
+             \"actions\":[{\"title\":\"Join\",\"url\":\"https://zoom.us/j/123\"},\
+             {\"title\":\"Open in Calendar\",\"url\":\"ical://ekevent/Review\"}]"
+        ));
+    }
+}
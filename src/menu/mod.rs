@@ -1,7 +1,20 @@
 mod builder;
+mod cache;
+mod days;
 mod delegate;
+mod formatting;
+mod health;
 mod icons;
+mod launcher;
+mod model;
+mod next;
+mod preview;
+mod render;
+mod report;
 mod text;
 
-pub use builder::MenuBuilder;
+pub use builder::{access_denied_menu, MenuBuilder};
 pub use delegate::MenuDelegate;
+pub use launcher::{render_alfred, render_raycast};
+pub use next::{render_next, NextTemplate, DEFAULT_NEXT_FORMAT};
+pub use preview::render_preview;
@@ -0,0 +1,324 @@
+use std::fmt;
+use std::ops::Range;
+
+use crate::calendar::Icon;
+
+use super::formatting::TextRole;
+
+/// What choosing a row does, sent to the menu delegate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    OpenUrl,
+    OpenEvent,
+    OpenDay,
+    CopyText,
+    DismissEvent,
+    DismissEvents,
+    RestoreEvents,
+    EditNote,
+    ToggleImportant,
+    ScheduleFollowUp,
+    CreateFocusBlocks,
+    ReadAgenda,
+    PauseApp,
+    ResumeApp,
+    HideForToday,
+    ShowTitle,
+    RefreshNow,
+    QuietFor,
+    ResumeReminders,
+    SetCurrentCountdown,
+    /// Goes to the application rather than the delegate
+    Quit,
+}
+
+/// A row's image, loaded when the menu is drawn.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Image {
+    Icon(Icon),
+    /// An SF Symbol, by name and accessibility description
+    Symbol(&'static str, &'static str),
+    /// A dot in a calendar's color
+    Dot((f64, f64, f64)),
+}
+
+/// A change to a `StyledText`, applied in order. Ranges are bytes of the
+/// text it was created with.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Run {
+    Bold(Range<usize>),
+    Role(TextRole, Range<usize>),
+    /// Text added at the end, styled for a role
+    Append(String, TextRole),
+    /// An SF Symbol added at the end after a space
+    Symbol(&'static str, &'static str),
+}
+
+/// A row title and how it's styled, without the attributed string drawn from
+/// it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct StyledText {
+    text: String,
+    runs: Vec<Run>,
+}
+
+impl StyledText {
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            runs: Vec::new(),
+        }
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn runs(&self) -> &[Run] {
+        &self.runs
+    }
+
+    /// Drawn as a plain title, without an attributed string.
+    pub fn is_plain(&self) -> bool {
+        self.runs.is_empty()
+    }
+
+    pub fn bold(&mut self, range: Range<usize>) -> &mut Self {
+        self.runs.push(Run::Bold(range));
+        self
+    }
+
+    pub fn role(&mut self, role: TextRole, range: Range<usize>) -> &mut Self {
+        self.runs.push(Run::Role(role, range));
+        self
+    }
+
+    pub fn append(&mut self, text: &str, role: TextRole) -> &mut Self {
+        self.runs.push(Run::Append(text.to_string(), role));
+        self
+    }
+
+    pub fn symbol(&mut self, name: &'static str, description: &'static str) -> &mut Self {
+        self.runs.push(Run::Symbol(name, description));
+        self
+    }
+}
+
+/// The text as shown, with symbols as "[name]".
+impl fmt::Display for StyledText {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.text)?;
+        for run in &self.runs {
+            match run {
+                Run::Append(text, _) => write!(f, "{}", text)?,
+                Run::Symbol(name, _) => write!(f, " [{}]", name)?,
+                Run::Bold(_) | Run::Role(..) => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+impl From<&str> for StyledText {
+    fn from(text: &str) -> Self {
+        Self::new(text)
+    }
+}
+
+impl From<String> for StyledText {
+    fn from(text: String) -> Self {
+        Self::new(text)
+    }
+}
+
+/// One menu item. Rows are enabled and do nothing until told otherwise.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Row {
+    pub title: StyledText,
+    pub action: Option<Action>,
+    /// Handed to the action, like the URL to open
+    pub value: Option<String>,
+    pub image: Option<Image>,
+    pub checked: bool,
+    /// Shown in place of the checkmark
+    pub checked_image: Option<Image>,
+    pub enabled: bool,
+    pub tool_tip: Option<String>,
+    pub key_equivalent: &'static str,
+    /// Replaces the row above it while Option is held
+    pub alternate: bool,
+    pub submenu: Option<MenuModel>,
+}
+
+impl Row {
+    pub fn new(title: impl Into<StyledText>) -> Self {
+        Self {
+            title: title.into(),
+            action: None,
+            value: None,
+            image: None,
+            checked: false,
+            checked_image: None,
+            enabled: true,
+            tool_tip: None,
+            key_equivalent: "",
+            alternate: false,
+            submenu: None,
+        }
+    }
+
+    pub fn action(mut self, action: Action) -> Self {
+        self.action = Some(action);
+        self
+    }
+
+    pub fn value(mut self, value: impl Into<String>) -> Self {
+        self.value = Some(value.into());
+        self
+    }
+
+    pub fn image(mut self, image: Image) -> Self {
+        self.image = Some(image);
+        self
+    }
+
+    pub fn checked(mut self, checked: bool) -> Self {
+        self.checked = checked;
+        self
+    }
+
+    pub fn checked_image(mut self, image: Image) -> Self {
+        self.checked_image = Some(image);
+        self
+    }
+
+    pub fn disabled(mut self) -> Self {
+        self.enabled = false;
+        self
+    }
+
+    pub fn tool_tip(mut self, tool_tip: impl Into<String>) -> Self {
+        self.tool_tip = Some(tool_tip.into());
+        self
+    }
+
+    pub fn key_equivalent(mut self, key: &'static str) -> Self {
+        self.key_equivalent = key;
+        self
+    }
+
+    pub fn alternate(mut self) -> Self {
+        self.alternate = true;
+        self
+    }
+
+    pub fn submenu(mut self, submenu: MenuModel) -> Self {
+        self.submenu = Some(submenu);
+        self
+    }
+}
+
+/// A menu as sections of rows, drawn with a separator between each. Built by
+/// `MenuBuilder` and turned into an `NSMenu` by `MenuRenderer`, so layout can
+/// be checked without AppKit.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MenuModel {
+    sections: Vec<Vec<Row>>,
+}
+
+impl MenuModel {
+    pub fn new() -> Self {
+        Self {
+            sections: vec![Vec::new()],
+        }
+    }
+
+    /// Adds `row` to the last section.
+    pub fn push(&mut self, row: Row) {
+        if let Some(section) = self.sections.last_mut() {
+            section.push(row);
+        }
+    }
+
+    /// Starts a new section, below a separator.
+    pub fn separate(&mut self) {
+        self.sections.push(Vec::new());
+    }
+
+    pub fn sections(&self) -> &[Vec<Row>] {
+        &self.sections
+    }
+
+    fn write_rows(&self, f: &mut fmt::Formatter, depth: usize) -> fmt::Result {
+        let indent = "  ".repeat(depth);
+        for (index, section) in self.sections.iter().enumerate() {
+            if index > 0 {
+                writeln!(f, "{}---", indent)?;
+            }
+            for row in section {
+                write!(f, "{}", indent)?;
+                if row.alternate {
+                    write!(f, "⌥ ")?;
+                }
+                if row.checked {
+                    write!(f, "✓ ")?;
+                }
+                write!(f, "{}", row.title)?;
+                if let Some(action) = row.action {
+                    write!(f, " → {:?}", action)?;
+                }
+                if !row.enabled {
+                    write!(f, " (disabled)")?;
+                }
+                writeln!(f)?;
+                if let Some(submenu) = &row.submenu {
+                    submenu.write_rows(f, depth + 1)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Default for MenuModel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A snapshot for tests: one line per row, like "✓ 10:00 - 11:00 Review Join
+/// → OpenUrl", with submenus indented below their row and "---" for
+/// separators.
+impl fmt::Display for MenuModel {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.write_rows(f, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot() {
+        let mut links = MenuModel::new();
+        links.push(Row::new("zoom.us").action(Action::OpenUrl));
+
+        let mut title = StyledText::new("Review");
+        title.bold(0..6).symbol("repeat", "Recurring event");
+        let mut menu = MenuModel::new();
+        menu.push(Row::new(title).checked(true).submenu(links));
+        menu.push(Row::new("Nothing else today").disabled());
+        menu.separate();
+        menu.push(Row::new("Quit").action(Action::Quit));
+
+        assert_eq!(
+            menu.to_string(),
+            "✓ Review [repeat]\n  \
+             zoom.us → OpenUrl\n\
+             Nothing else today (disabled)\n\
+             ---\n\
+             Quit → Quit\n"
+        );
+    }
+}
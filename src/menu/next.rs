@@ -0,0 +1,205 @@
+use chrono::{DateTime, Duration, Local};
+
+use crate::calendar::{format_duration, format_time, EventStatus, ServiceInfo};
+
+use super::builder::JOIN_SOON_MINUTES;
+
+/// `eventually next`'s output when no `--format` is given.
+pub const DEFAULT_NEXT_FORMAT: &str = "{title} {countdown}";
+
+/// What `{name}` in a `--format` template stands for.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Field {
+    Title,
+    /// "in 12m", or "12m left" during the event
+    Countdown,
+    /// Whole minutes until the start, or left during the event
+    Minutes,
+    /// "09:30"
+    Start,
+    End,
+    /// "Zoom", empty without a call link
+    Service,
+    Url,
+    Calendar,
+    /// "now", "soon" within the join window, or "later"
+    Urgency,
+}
+
+impl Field {
+    const ALL: [(&'static str, Self); 9] = [
+        ("title", Self::Title),
+        ("countdown", Self::Countdown),
+        ("minutes", Self::Minutes),
+        ("start", Self::Start),
+        ("end", Self::End),
+        ("service", Self::Service),
+        ("url", Self::Url),
+        ("calendar", Self::Calendar),
+        ("urgency", Self::Urgency),
+    ];
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Piece {
+    Text(String),
+    Field(Field),
+}
+
+/// A `--format` template like "{start} {title} ({urgency})". `{{` and `}}`
+/// stand for literal braces.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NextTemplate(Vec<Piece>);
+
+impl NextTemplate {
+    pub fn parse(template: &str) -> Result<Self, String> {
+        let mut pieces = Vec::new();
+        let mut text = String::new();
+        let mut chars = template.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.as_str().starts_with('{') => {
+                    chars.next();
+                    text.push('{');
+                }
+                '}' if chars.as_str().starts_with('}') => {
+                    chars.next();
+                    text.push('}');
+                }
+                '{' => {
+                    let rest = chars.as_str();
+                    let end = rest
+                        .find('}')
+                        .ok_or_else(|| "unclosed `{` in template".to_string())?;
+                    let name = &rest[..end];
+                    let field = Field::ALL
+                        .iter()
+                        .find(|(field_name, _)| *field_name == name)
+                        .map(|(_, field)| *field)
+                        .ok_or_else(|| {
+                            let names: Vec<&str> =
+                                Field::ALL.iter().map(|(name, _)| *name).collect();
+                            format!(
+                                "unknown placeholder `{{{}}}`, expected one of: {}",
+                                name,
+                                names.join(", ")
+                            )
+                        })?;
+                    if !text.is_empty() {
+                        pieces.push(Piece::Text(std::mem::take(&mut text)));
+                    }
+                    pieces.push(Piece::Field(field));
+                    chars = rest[end + 1..].chars();
+                }
+                '}' => return Err("unmatched `}` in template, write `}}` for a brace".to_string()),
+                c => text.push(c),
+            }
+        }
+        if !text.is_empty() {
+            pieces.push(Piece::Text(text));
+        }
+        Ok(Self(pieces))
+    }
+}
+
+/// The current or next event through `template`. With `color`, the line is
+/// red while the event is on and yellow shortly before, for terminals.
+pub fn render_next(
+    status: &EventStatus,
+    now: DateTime<Local>,
+    template: &NextTemplate,
+    prefer: &[ServiceInfo],
+    color: bool,
+) -> String {
+    let event = status.event();
+    let (urgency, until) = match status {
+        EventStatus::Current(_) => ("now", event.end - now),
+        EventStatus::Upcoming(_) if event.start - now <= Duration::minutes(JOIN_SOON_MINUTES) => {
+            ("soon", event.start - now)
+        }
+        EventStatus::Upcoming(_) => ("later", event.start - now),
+    };
+    let url = event.join_url(prefer);
+
+    let line: String = template
+        .0
+        .iter()
+        .map(|piece| match piece {
+            Piece::Text(text) => text.clone(),
+            Piece::Field(field) => match field {
+                Field::Title => event.title.clone(),
+                Field::Countdown => match status {
+                    EventStatus::Current(_) => format!("{} left", format_duration(until)),
+                    EventStatus::Upcoming(_) => format!("in {}", format_duration(until)),
+                },
+                Field::Minutes => until.num_minutes().to_string(),
+                Field::Start => format_time(&event.start),
+                Field::End => format_time(&event.end),
+                Field::Service => url
+                    .map(ServiceInfo::from_url)
+                    .filter(|service| *service != ServiceInfo::Generic)
+                    .map(|service| service.name().to_string())
+                    .unwrap_or_default(),
+                Field::Url => url.unwrap_or_default().to_string(),
+                Field::Calendar => event.calendar_name.clone(),
+                Field::Urgency => urgency.to_string(),
+            },
+        })
+        .collect();
+
+    match (color, urgency) {
+        (true, "now") => format!("\x1b[1;31m{}\x1b[0m", line),
+        (true, "soon") => format!("\x1b[33m{}\x1b[0m", line),
+        _ => line,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendar::{parse_local_time, EventCollection};
+
+    fn events() -> EventCollection {
+        EventCollection::from_fixture(
+            r#"
+            [[event]]
+            title = "Review"
+            start = "2025-03-07 10:00"
+            end = "2025-03-07 11:00"
+            location = "https://zoom.us/j/123"
+            "#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_render_next() {
+        let events = events();
+        let event = events.iter().next().unwrap();
+        let template =
+            NextTemplate::parse("{start}-{end} {title} [{service}] {{{minutes}}} {urgency}")
+                .unwrap();
+
+        let now = parse_local_time("2025-03-07 09:55").unwrap();
+        assert_eq!(
+            render_next(&EventStatus::Upcoming(event), now, &template, &[], false),
+            "10:00-11:00 Review [Zoom] {5} soon"
+        );
+
+        let now = parse_local_time("2025-03-07 10:48").unwrap();
+        let default = NextTemplate::parse(DEFAULT_NEXT_FORMAT).unwrap();
+        assert_eq!(
+            render_next(&EventStatus::Current(event), now, &default, &[], true),
+            "\x1b[1;31mReview 12m left\x1b[0m"
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_unknown_placeholders() {
+        assert!(NextTemplate::parse("{title").is_err());
+        assert!(NextTemplate::parse("title}").is_err());
+        assert!(NextTemplate::parse("{room}")
+            .unwrap_err()
+            .contains("expected one of: title, countdown"));
+    }
+}
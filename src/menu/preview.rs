@@ -0,0 +1,154 @@
+use chrono::Duration;
+use std::collections::HashSet;
+
+use crate::calendar::{format_total_duration, listed_in_day, EventCollection, OccurrenceRef};
+use crate::clock::Clock;
+use crate::config::{Config, TitleMode};
+
+use super::builder::{join_soon_url, row_title};
+use super::days::day_groups;
+
+/// The status title and menu rows as plain text, as they'd appear at
+/// `clock`'s time. Backs `eventually list` for checking formatting changes and
+/// reproducing reports without waiting for the right moment.
+pub fn render_preview(
+    events: &EventCollection,
+    clock: &dyn Clock,
+    dismissed: &HashSet<OccurrenceRef>,
+    config: &Config,
+) -> String {
+    let now = clock.now();
+    let mut lines = vec![format!(
+        "Title: {}",
+        match config.title.mode {
+            TitleMode::Full => events.get_title(clock, dismissed, None, config),
+            TitleMode::TimeOnly => events.get_time_only_title(clock, dismissed, None, config),
+        }
+    )];
+    lines.push(format!(
+        "Glance: {}",
+        events.today_summary(clock, dismissed, config)
+    ));
+
+    let title_event = events
+        .find_cur_or_next(clock, dismissed, config)
+        .map(|status| status.event().occurrence.clone());
+    let mut listed = 0;
+    for group in day_groups(now.date_naive(), config.menu.horizon()) {
+        let (muted, day_events): (Vec<_>, Vec<_>) = events
+            .iter()
+            .filter(|e| group.contains(e.start.date_naive()))
+            .partition(|e| e.is_muted(&config.calendars));
+        let (banners, day_events): (Vec<_>, Vec<_>) = day_events
+            .into_iter()
+            .partition(|e| e.is_ooo_banner(&config.banners));
+        if day_events.is_empty() && banners.is_empty() && muted.is_empty() {
+            continue;
+        }
+
+        lines.push(String::new());
+        lines.push(format!("{}, {}", group.name, group.dates));
+        if group.first == now.date_naive() {
+            let total = day_events
+                .iter()
+                .filter(|e| e.is_meeting())
+                .fold(Duration::zero(), |total, e| total + (e.end - e.start));
+            if total > Duration::zero() {
+                lines.push(format!("  {} in meetings", format_total_duration(total)));
+            }
+        }
+        if !banners.is_empty() {
+            lines.push(format!("  Out of office ({})", banners.len()));
+        }
+        let mut calendars: Vec<&str> = Vec::new();
+        for event in &muted {
+            if !calendars.contains(&event.calendar_name.as_str()) {
+                calendars.push(&event.calendar_name);
+            }
+        }
+        for calendar in calendars {
+            let count = muted.iter().filter(|e| e.calendar_name == calendar).count();
+            lines.push(format!("  {} ({})", calendar, count));
+        }
+
+        let day_listed = listed_in_day(day_events.len(), listed);
+        for event in &day_events[..day_listed] {
+            let marker = if title_event.as_ref() == Some(&event.occurrence) {
+                "▸"
+            } else {
+                " "
+            };
+            let state = if dismissed.contains(&event.occurrence) {
+                " (dismissed)"
+            } else if event.end < now {
+                " (past)"
+            } else {
+                ""
+            };
+            let join = if join_soon_url(event, now, &config.join.prefer).is_some() {
+                " [Join]"
+            } else {
+                ""
+            };
+            lines.push(format!(
+                "{} {}{}{}",
+                marker,
+                row_title(event, group.spans_days()),
+                join,
+                state
+            ));
+        }
+        listed += day_listed;
+        let hidden = day_events.len() - day_listed;
+        if hidden > 0 {
+            lines.push(format!("  …and {} more", hidden));
+        }
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendar::parse_local_time;
+    use crate::clock::FrozenClock;
+
+    #[test]
+    fn test_render_preview() {
+        let events = EventCollection::from_fixture(
+            r#"
+            [[event]]
+            title = "Standup"
+            start = "2025-03-07 09:00"
+            end = "2025-03-07 09:15"
+
+            [[event]]
+            title = "Review"
+            start = "2025-03-07 10:00"
+            end = "2025-03-07 11:00"
+            location = "https://zoom.us/j/123"
+
+            [[event]]
+            title = "Planning"
+            start = "2025-03-08 14:00"
+            end = "2025-03-08 15:00"
+            "#,
+        )
+        .unwrap();
+        let clock = FrozenClock(parse_local_time("2025-03-07 09:55").unwrap());
+
+        assert_eq!(
+            render_preview(&events, &clock, &HashSet::new(), &Config::default()),
+            "Title: Review • in 5m\n\
+             Glance: 2 meetings · next: Review 10:00 · free after 11:00\n\
+             \n\
+             Today, 07 Mar\n  \
+             1h 15m in meetings\n  \
+             09:00 - 09:15 Standup (past)\n\
+             ▸ 10:00 - 11:00 Review [Join]\n\
+             \n\
+             Tomorrow, 08 Mar\n  \
+             14:00 - 15:00 Planning"
+        );
+    }
+}
@@ -0,0 +1,179 @@
+use objc2::rc::Retained;
+use objc2::runtime::Sel;
+use objc2_app_kit::{
+    NSColor, NSControlStateValueOn, NSEventModifierFlags, NSImage, NSMenu, NSMenuItem,
+};
+use objc2_foundation::{ns_string, MainThreadMarker, NSString};
+use std::cell::RefCell;
+
+use crate::calendar::Icon;
+use crate::ffi::app_kit;
+
+use super::cache::RebuildCache;
+use super::delegate::MenuDelegate;
+use super::formatting;
+use super::model::{Action, Image, MenuModel, Row, Run, StyledText};
+use super::text;
+
+/// Attributed row titles, reused across rebuilds while a title's text and
+/// styling stay the same.
+pub type RowTitleCache = RebuildCache<(StyledText, bool), text::AttributedString>;
+
+/// Draws a `MenuModel` as AppKit menu items.
+pub struct MenuRenderer<'a> {
+    /// Receives every action but Quit
+    target: Option<&'a MenuDelegate>,
+    row_titles: &'a RefCell<RowTitleCache>,
+    /// The "Increase contrast" accessibility setting, read once per build
+    increase_contrast: bool,
+    mtm: MainThreadMarker,
+}
+
+impl<'a> MenuRenderer<'a> {
+    pub fn new(
+        target: Option<&'a MenuDelegate>,
+        row_titles: &'a RefCell<RowTitleCache>,
+        mtm: MainThreadMarker,
+    ) -> Self {
+        Self {
+            target,
+            row_titles,
+            increase_contrast: app_kit::should_increase_contrast(),
+            mtm,
+        }
+    }
+
+    /// Replaces `menu`'s items, so the status item can keep one menu.
+    pub fn render(&self, model: &MenuModel, menu: &NSMenu) {
+        menu.removeAllItems();
+        self.fill(model, menu);
+        self.row_titles.borrow_mut().sweep();
+    }
+
+    fn fill(&self, model: &MenuModel, menu: &NSMenu) {
+        for (index, section) in model.sections().iter().enumerate() {
+            if index > 0 {
+                menu.addItem(&NSMenuItem::separatorItem(self.mtm));
+            }
+            for row in section {
+                menu.addItem(&self.item(row));
+            }
+        }
+    }
+
+    fn item(&self, row: &Row) -> Retained<NSMenuItem> {
+        let title = if row.title.is_plain() {
+            NSString::from_str(row.title.text())
+        } else {
+            NSString::new()
+        };
+        let item = app_kit::init_menu_item(
+            self.mtm,
+            &title,
+            row.action.map(selector),
+            &NSString::from_str(row.key_equivalent),
+        );
+        if !row.title.is_plain() {
+            app_kit::set_attributed_title(&item, self.title(&row.title).as_objc());
+        }
+        if let Some(image) = row.image.as_ref().and_then(|image| self.image(image)) {
+            item.setImage(Some(&image));
+        }
+        if row.checked {
+            item.setState(NSControlStateValueOn);
+        }
+        if let Some(image) = &row.checked_image {
+            app_kit::set_menu_item_on_state_image(&item, self.image(image).as_deref());
+        }
+        if !row.enabled {
+            item.setEnabled(false);
+        }
+        if let Some(tool_tip) = &row.tool_tip {
+            item.setToolTip(Some(&NSString::from_str(tool_tip)));
+        }
+        if row.alternate {
+            item.setAlternate(true);
+            item.setKeyEquivalentModifierMask(NSEventModifierFlags::Option);
+        }
+        if row.action.is_some_and(|action| action != Action::Quit) {
+            app_kit::set_menu_item_target(&item, self.target);
+        }
+        if let Some(value) = &row.value {
+            app_kit::set_menu_item_represented_object(&item, Some(&*NSString::from_str(value)));
+        }
+        if let Some(submenu) = &row.submenu {
+            let menu = app_kit::init_menu(self.mtm, ns_string!(""));
+            self.fill(submenu, &menu);
+            item.setSubmenu(Some(&menu));
+        }
+        item
+    }
+
+    fn title(&self, styled: &StyledText) -> text::AttributedString {
+        let key = (styled.clone(), self.increase_contrast);
+        self.row_titles.borrow_mut().get_or_insert_with(key, || {
+            let text = styled.text();
+            let attr_string = text::AttributedString::new(text);
+            for run in styled.runs() {
+                match run {
+                    Run::Bold(range) => {
+                        attr_string.apply_bold(formatting::utf16_range(text, range.clone()));
+                    }
+                    Run::Role(role, range) => {
+                        attr_string.apply_role(
+                            *role,
+                            self.increase_contrast,
+                            formatting::utf16_range(text, range.clone()),
+                        );
+                    }
+                    Run::Append(appended, role) => {
+                        attr_string.append_with_role(appended, *role, self.increase_contrast);
+                    }
+                    Run::Symbol(name, description) => {
+                        attr_string.append_symbol(name, description);
+                    }
+                }
+            }
+            attr_string
+        })
+    }
+
+    fn image(&self, image: &Image) -> Option<Retained<NSImage>> {
+        match image {
+            Image::Icon(icon) => icon.load(),
+            Image::Symbol(name, description) => app_kit::init_symbol_image(
+                &NSString::from_str(name),
+                &NSString::from_str(description),
+            ),
+            Image::Dot((red, green, blue)) => Icon::load_colored(
+                &NSColor::colorWithDisplayP3Red_green_blue_alpha(*red, *green, *blue, 1.0),
+            ),
+        }
+    }
+}
+
+fn selector(action: Action) -> Sel {
+    match action {
+        Action::OpenUrl => objc2::sel!(openURL:),
+        Action::OpenEvent => objc2::sel!(openEvent:),
+        Action::OpenDay => objc2::sel!(openDay:),
+        Action::CopyText => objc2::sel!(copyText:),
+        Action::DismissEvent => objc2::sel!(dismissEvent:),
+        Action::DismissEvents => objc2::sel!(dismissEvents:),
+        Action::RestoreEvents => objc2::sel!(restoreEvents:),
+        Action::EditNote => objc2::sel!(editNote:),
+        Action::ToggleImportant => objc2::sel!(toggleImportant:),
+        Action::ScheduleFollowUp => objc2::sel!(scheduleFollowUp:),
+        Action::CreateFocusBlocks => objc2::sel!(createFocusBlocks:),
+        Action::ReadAgenda => objc2::sel!(readAgenda:),
+        Action::PauseApp => objc2::sel!(pauseApp:),
+        Action::ResumeApp => objc2::sel!(resumeApp:),
+        Action::HideForToday => objc2::sel!(hideForToday:),
+        Action::ShowTitle => objc2::sel!(showTitle:),
+        Action::RefreshNow => objc2::sel!(refreshNow:),
+        Action::QuietFor => objc2::sel!(quietFor:),
+        Action::ResumeReminders => objc2::sel!(resumeReminders:),
+        Action::SetCurrentCountdown => objc2::sel!(setCurrentCountdown:),
+        Action::Quit => objc2::sel!(terminate:),
+    }
+}
@@ -0,0 +1,96 @@
+use std::collections::HashSet;
+
+use crate::calendar::{is_all_day, EventCollection};
+use crate::config::{Config, TitleMode};
+
+use super::health::FetchHealth;
+
+/// What's worth knowing to act on a bug report, with nothing identifying:
+/// no titles, attendees, links, or calendar names, only counts.
+pub(super) struct ProblemReport<'a> {
+    /// "Version 15.3 (Build 24D60)"
+    pub os_version: String,
+    /// "full access", "denied"
+    pub calendar_access: &'static str,
+    pub health: &'a FetchHealth,
+    pub events: &'a EventCollection,
+    pub config: &'a Config,
+}
+
+impl ProblemReport<'_> {
+    pub fn text(&self) -> String {
+        let calendars: HashSet<&str> = self.events.iter().map(|e| e.calendar_id.as_str()).collect();
+        let all_day = self
+            .events
+            .iter()
+            .filter(|e| is_all_day(&e.start, &e.end))
+            .count();
+        let muted = self
+            .events
+            .iter()
+            .filter(|e| e.is_muted(&self.config.calendars))
+            .count();
+        let mode = match self.config.title.mode {
+            TitleMode::Full => "full",
+            TitleMode::TimeOnly => "time-only",
+        };
+
+        [
+            format!("eventually {}", env!("CARGO_PKG_VERSION")),
+            format!("macOS: {}", self.os_version),
+            format!("Calendar access: {}", self.calendar_access),
+            format!("Last fetch: {}", self.health.footer()),
+            format!(
+                "Events: {} ({} all-day, {} muted) · Calendars: {}",
+                self.events.iter().count(),
+                all_day,
+                muted,
+                calendars.len()
+            ),
+            format!(
+                "Title mode: {} · Days: {}",
+                mode,
+                self.config.menu.horizon()
+            ),
+        ]
+        .join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_leaves_out_event_details() {
+        let events = EventCollection::from_fixture(
+            r#"
+            [[event]]
+            title = "Salary review"
+            start = "2025-03-07 10:00"
+            end = "2025-03-07 11:00"
+            location = "https://zoom.us/j/123"
+
+            [[event]]
+            title = "Offsite"
+            start = "2025-03-07"
+            "#,
+        )
+        .unwrap();
+        let mut health = FetchHealth::default();
+        health.failed("EventKit", "Calendar store unavailable");
+        let text = ProblemReport {
+            os_version: "Version 15.3 (Build 24D60)".to_string(),
+            calendar_access: "full access",
+            health: &health,
+            events: &events,
+            config: &Config::default(),
+        }
+        .text();
+
+        assert!(text.contains("\nCalendar access: full access\n"));
+        assert!(text.contains("EventKit: Calendar store unavailable"));
+        assert!(text.contains("\nEvents: 2 (1 all-day, 0 muted) · Calendars: 1\n"));
+        assert!(!text.contains("Salary") && !text.contains("zoom"));
+    }
+}
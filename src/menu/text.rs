@@ -5,6 +5,9 @@ use objc2_foundation::{NSRange, NSString};
 
 use crate::ffi::app_kit;
 
+use super::formatting::{utf16_len, Shade, TextRole};
+
+#[derive(Clone)]
 pub struct AttributedString {
     inner: Retained<AnyObject>,
 }
@@ -30,14 +33,43 @@ impl AttributedString {
         self
     }
 
-    pub fn apply_secondary_color(&self, range: NSRange) -> &Self {
-        let secondary_color = NSColor::secondaryLabelColor();
-        self.apply_color(&secondary_color, range)
+    /// Colors `range` for `role`, following the "Increase contrast" setting.
+    pub fn apply_role(&self, role: TextRole, increase_contrast: bool, range: NSRange) -> &Self {
+        let color = match role.shade(increase_contrast) {
+            Shade::SecondaryLabel => NSColor::secondaryLabelColor(),
+            Shade::TertiaryLabel => NSColor::tertiaryLabelColor(),
+            Shade::Accent => NSColor::controlAccentColor(),
+            Shade::Red => NSColor::systemRedColor(),
+            Shade::BoldLabel => {
+                self.apply_bold(range);
+                NSColor::labelColor()
+            }
+        };
+        self.apply_color(&color, range)
+    }
+
+    /// Appends `text` styled for `role`, e.g. an inline action after an event title.
+    pub fn append_with_role(&self, text: &str, role: TextRole, increase_contrast: bool) -> &Self {
+        let start = app_kit::attributed_string_length(&self.inner);
+        app_kit::append_string(&self.inner, &NSString::from_str(text));
+        self.apply_role(
+            role,
+            increase_contrast,
+            NSRange::new(start, utf16_len(text)),
+        )
     }
 
-    pub fn apply_tertiary_color(&self, range: NSRange) -> &Self {
-        let tertiary_color = NSColor::tertiaryLabelColor();
-        self.apply_color(&tertiary_color, range)
+    /// Appends an SF Symbol after a space, e.g. a repeat glyph after an event title.
+    pub fn append_symbol(&self, name: &str, description: &str) -> &Self {
+        let image =
+            app_kit::init_symbol_image(&NSString::from_str(name), &NSString::from_str(description));
+        if let Some(image) = image {
+            app_kit::append_string(&self.inner, &NSString::from_str(" "));
+            app_kit::append_image(&self.inner, &image);
+        } else {
+            eprintln!("Error: Failed to load symbol image: {}", name);
+        }
+        self
     }
 
     pub fn as_objc(&self) -> &AnyObject {
@@ -0,0 +1,97 @@
+use chrono::{DateTime, Days, Duration, Local};
+
+use crate::quiet::QuietMode;
+
+/// Temporary states toggled from the menu that change what the app shows.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Modes {
+    pub quiet: QuietMode,
+    pub pause: Pause,
+    /// Set by "Hide for Today": only the icon shows, and banners and media
+    /// pausing stay off, until the day is over
+    hidden_until: Option<DateTime<Local>>,
+}
+
+impl Modes {
+    pub fn hide_for_today(&mut self, now: DateTime<Local>) {
+        self.hidden_until = now
+            .date_naive()
+            .checked_add_days(Days::new(1))
+            .and_then(|tomorrow| tomorrow.and_hms_opt(0, 0, 0))
+            .and_then(|midnight| midnight.and_local_timezone(Local).earliest());
+    }
+
+    pub fn unhide(&mut self) {
+        self.hidden_until = None;
+    }
+
+    pub fn is_hidden(&self, now: DateTime<Local>) -> bool {
+        self.hidden_until.is_some_and(|until| now < until)
+    }
+}
+
+/// Freezes refreshes and the status title, e.g. during demos. Like quiet mode it
+/// lapses on its own once `until` has passed.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Pause {
+    until: Option<DateTime<Local>>,
+}
+
+impl Pause {
+    pub fn start(&mut self, now: DateTime<Local>, duration: Duration) {
+        self.until = Some(now + duration);
+    }
+
+    pub fn resume(&mut self) {
+        self.until = None;
+    }
+
+    pub fn remaining(&self, now: DateTime<Local>) -> Option<Duration> {
+        self.until
+            .map(|until| until.signed_duration_since(now))
+            .filter(|remaining| *remaining > Duration::zero())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_hide_for_today_lasts_until_midnight() {
+        let now = Local
+            .with_ymd_and_hms(2025, 3, 5, 9, 30, 0)
+            .single()
+            .unwrap();
+        let mut modes = Modes::default();
+        modes.hide_for_today(now);
+
+        assert!(modes.is_hidden(now));
+        assert!(modes.is_hidden(now + Duration::hours(14)));
+        assert!(!modes.is_hidden(now + Duration::hours(15)));
+
+        modes.unhide();
+        assert!(!modes.is_hidden(now));
+    }
+
+    #[test]
+    fn test_pause_resumes_automatically() {
+        let now = Local::now();
+        let mut pause = Pause::default();
+        pause.start(now, Duration::minutes(45));
+
+        assert_eq!(pause.remaining(now), Some(Duration::minutes(45)));
+        assert!(pause.remaining(now + Duration::minutes(45)).is_none());
+    }
+
+    #[test]
+    fn test_pause_resume() {
+        let now = Local::now();
+        let mut pause = Pause::default();
+        pause.start(now, Duration::hours(1));
+        pause.resume();
+
+        assert!(pause.remaining(now).is_none());
+    }
+}
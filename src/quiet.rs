@@ -0,0 +1,89 @@
+use chrono::{DateTime, Duration, Local};
+
+/// Quiet durations offered in the settings submenu, in minutes.
+pub const QUIET_DURATIONS: [i64; 3] = [30, 60, 120];
+
+/// Temporarily silences reminders while keeping the plain status title.
+///
+/// Quiet mode expires on its own: once `until` has passed the state reads as
+/// inactive, so the periodic refresh resumes reminders without extra bookkeeping.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct QuietMode {
+    until: Option<DateTime<Local>>,
+}
+
+impl QuietMode {
+    pub fn start(&mut self, now: DateTime<Local>, duration: Duration) {
+        self.until = Some(now + duration);
+    }
+
+    pub fn resume(&mut self) {
+        self.until = None;
+    }
+
+    pub fn remaining(&self, now: DateTime<Local>) -> Option<Duration> {
+        self.until
+            .map(|until| until.signed_duration_since(now))
+            .filter(|remaining| *remaining > Duration::zero())
+    }
+}
+
+pub fn duration_label(minutes: i64) -> String {
+    if minutes % 60 == 0 {
+        let hours = minutes / 60;
+        if hours == 1 {
+            "1 hour".to_string()
+        } else {
+            format!("{} hours", hours)
+        }
+    } else {
+        format!("{} minutes", minutes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quiet_mode_default_inactive() {
+        let quiet = QuietMode::default();
+        assert!(quiet.remaining(Local::now()).is_none());
+    }
+
+    #[test]
+    fn test_quiet_mode_start_and_remaining() {
+        let now = Local::now();
+        let mut quiet = QuietMode::default();
+        quiet.start(now, Duration::hours(1));
+
+        assert_eq!(quiet.remaining(now), Some(Duration::hours(1)));
+    }
+
+    #[test]
+    fn test_quiet_mode_expires() {
+        let now = Local::now();
+        let mut quiet = QuietMode::default();
+        quiet.start(now, Duration::minutes(30));
+
+        assert!(quiet.remaining(now + Duration::minutes(30)).is_none());
+        assert!(quiet.remaining(now + Duration::hours(1)).is_none());
+    }
+
+    #[test]
+    fn test_quiet_mode_resume() {
+        let now = Local::now();
+        let mut quiet = QuietMode::default();
+        quiet.start(now, Duration::hours(2));
+        quiet.resume();
+
+        assert!(quiet.remaining(now).is_none());
+    }
+
+    #[test]
+    fn test_duration_label() {
+        assert_eq!(duration_label(30), "30 minutes");
+        assert_eq!(duration_label(60), "1 hour");
+        assert_eq!(duration_label(120), "2 hours");
+    }
+}
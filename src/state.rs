@@ -0,0 +1,175 @@
+//! `eventually state export` and `import`: an instance's config and local
+//! notes as one JSON document, for moving to another Mac or starting over
+//! with the same setup. Pinned titles and muted calendars live in the config,
+//! so they come along. Keychain tokens deliberately don't.
+
+use std::fs;
+use std::io::{Error, ErrorKind, Result};
+use std::path::{Path, PathBuf};
+
+use crate::config::Config;
+use crate::json::{self, Value};
+use crate::local_notes::LocalNotes;
+
+/// Bumped when the document changes shape; newer documents are refused.
+const FORMAT_VERSION: u32 = 1;
+
+/// Each file's contents as written, `None` when the file doesn't exist.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct StateDocument {
+    pub config: Option<String>,
+    pub notes: Option<String>,
+}
+
+impl StateDocument {
+    /// Reads the instance's state files.
+    pub fn collect(instance: Option<&str>) -> Result<Self> {
+        let (config_path, notes_path) = paths(instance)?;
+        Ok(Self {
+            config: read_if_exists(&config_path)?,
+            notes: read_if_exists(&notes_path)?,
+        })
+    }
+
+    pub fn to_json(&self) -> String {
+        let field = |contents: &Option<String>| match contents {
+            Some(contents) => json::string(contents),
+            None => "null".to_string(),
+        };
+        format!(
+            "{{\n  \"version\": {},\n  \"config\": {},\n  \"notes\": {}\n}}\n",
+            FORMAT_VERSION,
+            field(&self.config),
+            field(&self.notes)
+        )
+    }
+
+    /// Parses an exported document, checking that what it holds would load.
+    pub fn from_json(input: &str) -> std::result::Result<Self, String> {
+        let mut object = json::parse_flat_object(input)?;
+        match object.remove("version") {
+            Some(Value::Number(version)) if version == FORMAT_VERSION as f64 => {}
+            Some(Value::Number(version)) => {
+                return Err(format!(
+                    "state version {} isn't supported by this version of eventually",
+                    version
+                ))
+            }
+            _ => return Err("missing state version".to_string()),
+        }
+        let mut field = |name: &str| match object.remove(name) {
+            Some(Value::String(contents)) => Ok(Some(contents)),
+            Some(Value::Null) | None => Ok(None),
+            Some(_) => Err(format!("`{}` must be a string or null", name)),
+        };
+        let document = Self {
+            config: field("config")?,
+            notes: field("notes")?,
+        };
+
+        if let Some(config) = &document.config {
+            Config::parse(config).map_err(|e| format!("invalid config: {}", e))?;
+        }
+        if let Some(notes) = &document.notes {
+            toml::from_str::<LocalNotes>(notes).map_err(|e| format!("invalid notes: {}", e))?;
+        }
+        Ok(document)
+    }
+
+    /// Replaces the instance's state with this document's. Files the document
+    /// has no contents for are removed, so importing resets cleanly. Replaced
+    /// files are kept next to the new ones with a `.bak` suffix. Returns the
+    /// paths written or removed.
+    pub fn apply(&self, instance: Option<&str>) -> Result<Vec<PathBuf>> {
+        let (config_path, notes_path) = paths(instance)?;
+        let mut changed = Vec::new();
+        for (path, contents) in [(config_path, &self.config), (notes_path, &self.notes)] {
+            if replace_file(&path, contents.as_deref())? {
+                changed.push(path);
+            }
+        }
+        Ok(changed)
+    }
+}
+
+fn paths(instance: Option<&str>) -> Result<(PathBuf, PathBuf)> {
+    match (Config::path(instance), LocalNotes::path(instance)) {
+        (Some(config), Some(notes)) => Ok((config, notes)),
+        _ => Err(Error::other("HOME is not set")),
+    }
+}
+
+fn read_if_exists(path: &Path) -> Result<Option<String>> {
+    match fs::read_to_string(path) {
+        Ok(contents) => Ok(Some(contents)),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(Error::new(
+            e.kind(),
+            format!("Failed to read `{}`: {}", path.display(), e),
+        )),
+    }
+}
+
+/// Writes `contents` to `path`, or removes it for `None`, backing up what was
+/// there. Returns whether anything changed.
+fn replace_file(path: &Path, contents: Option<&str>) -> Result<bool> {
+    let current = read_if_exists(path)?;
+    if current.as_deref() == contents {
+        return Ok(false);
+    }
+    if current.is_some() {
+        let mut backup = path.as_os_str().to_owned();
+        backup.push(".bak");
+        fs::copy(path, &backup)?;
+    }
+    match contents {
+        Some(contents) => {
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir)?;
+            }
+            fs::write(path, contents)?;
+        }
+        None => fs::remove_file(path)?,
+    }
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let document = StateDocument {
+            config: Some("[countdown]\npinned = [\"Offsite\"]\n".to_string()),
+            notes: None,
+        };
+        assert_eq!(
+            StateDocument::from_json(&document.to_json()).unwrap(),
+            document
+        );
+    }
+
+    #[test]
+    fn test_rejects_invalid_state() {
+        assert!(StateDocument::from_json(r#"{"config": null}"#).is_err());
+        assert!(StateDocument::from_json(r#"{"version": 2, "config": null}"#).is_err());
+        assert!(StateDocument::from_json(r#"{"version": 1, "config": "[title"}"#).is_err());
+        assert!(StateDocument::from_json(r#"{"version": 1, "notes": 3}"#).is_err());
+    }
+
+    #[test]
+    fn test_replace_file_backs_up() {
+        let dir = std::env::temp_dir().join(format!("eventually-state-{}", std::process::id()));
+        let path = dir.join("config.toml");
+        let backup = dir.join("config.toml.bak");
+
+        assert!(replace_file(&path, Some("a = 1\n")).unwrap());
+        assert!(!replace_file(&path, Some("a = 1\n")).unwrap());
+        assert!(replace_file(&path, None).unwrap());
+        assert!(!path.exists());
+        assert_eq!(fs::read_to_string(&backup).unwrap(), "a = 1\n");
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
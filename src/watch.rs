@@ -0,0 +1,336 @@
+//! The watch socket: a Unix socket that long-lived consumers such as
+//! Hammerspoon connect to and read calendar transitions from, one JSON object
+//! per line. Every line has `version`, `type`, and `at` (Unix seconds); the
+//! rest depends on the type:
+//!
+//! - `title_changed`: `title`, the menu bar title. The latest one is also sent
+//!   to each client as it connects.
+//! - `event_started`: a timed event on a calendar that isn't muted began.
+//! - `joined`: a call of the current or next meeting was opened.
+//! - `dismissed`: an event was dismissed from the menu.
+//!
+//! Event lines carry `event_id` and `start` (Unix seconds), which identify the
+//! occurrence, then `end`, `title`, `calendar`, and `join_url`. `dismissed` has
+//! null for those when the event is no longer on display. Fields may be added
+//! within a version; removing or changing one bumps it.
+
+use std::fs;
+use std::io::{ErrorKind, Result, Write};
+use std::os::fd::AsRawFd;
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use chrono::{DateTime, Local};
+
+use crate::calendar::{EventInfo, OccurrenceRef};
+use crate::json;
+
+/// Bumped when a line changes incompatibly.
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// A transition sent to the socket's clients.
+#[derive(Debug, Clone, Copy)]
+pub enum WatchEvent<'a> {
+    TitleChanged(&'a str),
+    EventStarted(&'a EventInfo),
+    Joined(&'a EventInfo, &'a str),
+    /// The event when it's still on display
+    Dismissed(&'a OccurrenceRef, Option<&'a EventInfo>),
+}
+
+impl WatchEvent<'_> {
+    fn kind(&self) -> &'static str {
+        match self {
+            Self::TitleChanged(_) => "title_changed",
+            Self::EventStarted(_) => "event_started",
+            Self::Joined(..) => "joined",
+            Self::Dismissed(..) => "dismissed",
+        }
+    }
+
+    /// The event as one line of JSON, newline included.
+    pub fn to_line(self, at: DateTime<Local>) -> String {
+        let mut fields = vec![
+            ("version", PROTOCOL_VERSION.to_string()),
+            ("type", json::string(self.kind())),
+            ("at", at.timestamp().to_string()),
+        ];
+        match self {
+            Self::TitleChanged(title) => fields.push(("title", json::string(title))),
+            Self::EventStarted(event) => event_fields(
+                &mut fields,
+                &event.occurrence,
+                Some(event),
+                event.join_url(&[]),
+            ),
+            // The link opened, which needn't be the preferred one
+            Self::Joined(event, url) => {
+                event_fields(&mut fields, &event.occurrence, Some(event), Some(url))
+            }
+            Self::Dismissed(occurrence, event) => event_fields(
+                &mut fields,
+                occurrence,
+                event,
+                event.and_then(|e| e.join_url(&[])),
+            ),
+        }
+
+        let fields: Vec<String> = fields
+            .iter()
+            .map(|(name, value)| format!("\"{}\":{}", name, value))
+            .collect();
+        format!("{{{}}}\n", fields.join(","))
+    }
+}
+
+fn event_fields(
+    fields: &mut Vec<(&'static str, String)>,
+    occurrence: &OccurrenceRef,
+    event: Option<&EventInfo>,
+    join_url: Option<&str>,
+) {
+    let or_null = |value: Option<String>| value.unwrap_or_else(|| "null".to_string());
+    fields.push(("event_id", json::string(&occurrence.event_id)));
+    fields.push(("start", occurrence.start.to_string()));
+    fields.push(("end", or_null(event.map(|e| e.end.timestamp().to_string()))));
+    fields.push(("title", or_null(event.map(|e| json::string(&e.title)))));
+    fields.push((
+        "calendar",
+        or_null(event.map(|e| json::string(&e.calendar_name))),
+    ));
+    fields.push(("join_url", or_null(join_url.map(json::string))));
+}
+
+#[derive(Default)]
+struct Clients {
+    streams: Vec<UnixStream>,
+    /// The last `title_changed` line, for clients that connect later
+    title_line: Option<String>,
+}
+
+/// Accepts clients on a background thread and writes lines to them from the
+/// main thread. A client that can't keep up is dropped rather than blocking
+/// the app.
+pub struct WatchSocket {
+    clients: Arc<Mutex<Clients>>,
+    path: PathBuf,
+}
+
+impl WatchSocket {
+    /// `config.sock` for the default instance, `<instance>.sock` for named ones.
+    pub fn path(instance: Option<&str>) -> Option<PathBuf> {
+        let home = std::env::var("HOME").ok()?;
+        Some(PathBuf::from(format!(
+            "{}/.config/eventually/{}.sock",
+            home,
+            instance.unwrap_or("config")
+        )))
+    }
+
+    /// The process listening at `path`, when another copy of the instance is
+    /// running. A socket left behind by one that crashed refuses connections.
+    pub fn running_pid(path: &Path) -> Option<libc::pid_t> {
+        let stream = UnixStream::connect(path).ok()?;
+        let mut pid: libc::pid_t = 0;
+        let mut len = std::mem::size_of::<libc::pid_t>() as libc::socklen_t;
+        let result = unsafe {
+            libc::getsockopt(
+                stream.as_raw_fd(),
+                libc::SOL_LOCAL,
+                libc::LOCAL_PEERPID,
+                (&mut pid as *mut libc::pid_t).cast(),
+                &mut len,
+            )
+        };
+        (result == 0).then_some(pid)
+    }
+
+    /// Locks the instance for as long as the returned file stays open, with a
+    /// lock file next to `path`. `None` while another copy holds it.
+    pub fn lock(path: &Path) -> Result<Option<fs::File>> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let file = fs::OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(path.with_extension("lock"))?;
+        if unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX | libc::LOCK_NB) } == 0 {
+            return Ok(Some(file));
+        }
+        let error = std::io::Error::last_os_error();
+        if error.kind() == ErrorKind::WouldBlock {
+            Ok(None)
+        } else {
+            Err(error)
+        }
+    }
+
+    /// Listens at `path`, replacing a socket left behind by an earlier run.
+    /// Holding the `lock` first keeps this from replacing a running copy's.
+    pub fn bind(path: PathBuf) -> Result<Self> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        match fs::remove_file(&path) {
+            Err(e) if e.kind() != ErrorKind::NotFound => return Err(e),
+            _ => {}
+        }
+        let listener = UnixListener::bind(&path)?;
+
+        let clients = Arc::new(Mutex::new(Clients::default()));
+        thread::spawn({
+            let clients = Arc::clone(&clients);
+            move || {
+                for stream in listener.incoming() {
+                    let mut stream = match stream {
+                        Ok(stream) => stream,
+                        Err(e) => {
+                            eprintln!("Error: Failed to accept watch client: {}", e);
+                            continue;
+                        }
+                    };
+                    let Ok(mut clients) = clients.lock() else {
+                        return;
+                    };
+                    if stream.set_nonblocking(true).is_err() {
+                        continue;
+                    }
+                    let greeting = clients.title_line.clone().unwrap_or_default();
+                    if stream.write_all(greeting.as_bytes()).is_ok() {
+                        clients.streams.push(stream);
+                    }
+                }
+            }
+        });
+
+        Ok(Self { clients, path })
+    }
+
+    pub fn send(&self, event: WatchEvent, at: DateTime<Local>) {
+        let line = event.to_line(at);
+        let Ok(mut clients) = self.clients.lock() else {
+            eprintln!("Error: Failed to acquire lock when writing to watch clients");
+            return;
+        };
+        if let WatchEvent::TitleChanged(_) = event {
+            clients.title_line = Some(line.clone());
+        }
+        clients
+            .streams
+            .retain_mut(|stream| stream.write_all(line.as_bytes()).is_ok());
+    }
+}
+
+impl Drop for WatchSocket {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendar::{parse_local_time, EventCollection};
+    use crate::json::{parse_flat_object, Value};
+    use std::io::{BufRead, BufReader};
+
+    fn review() -> EventInfo {
+        EventCollection::from_fixture(
+            r#"
+            [[event]]
+            title = "Review"
+            start = "2025-03-07 10:00"
+            end = "2025-03-07 11:00"
+            location = "https://zoom.us/j/123"
+            "#,
+        )
+        .unwrap()
+        .iter()
+        .next()
+        .unwrap()
+        .clone()
+    }
+
+    #[test]
+    fn test_event_started_line() {
+        let event = review();
+        let at = parse_local_time("2025-03-07 10:00").unwrap();
+        let line = WatchEvent::EventStarted(&event).to_line(at);
+
+        assert!(line.ends_with("}\n") && line.matches('\n').count() == 1);
+        let fields = parse_flat_object(&line).unwrap();
+        assert_eq!(fields["version"], Value::Number(1.0));
+        assert_eq!(fields["type"], Value::String("event_started".to_string()));
+        assert_eq!(fields["at"], Value::Number(at.timestamp() as f64));
+        assert_eq!(fields["title"], Value::String("Review".to_string()));
+        assert_eq!(fields["start"], Value::Number(at.timestamp() as f64));
+        assert_eq!(
+            fields["join_url"],
+            Value::String("https://zoom.us/j/123".to_string())
+        );
+    }
+
+    #[test]
+    fn test_dismissed_line_without_event() {
+        let at = parse_local_time("2025-03-07 10:00").unwrap();
+        let occurrence = OccurrenceRef::new("AB12", 1741338000);
+        let fields =
+            parse_flat_object(&WatchEvent::Dismissed(&occurrence, None).to_line(at)).unwrap();
+
+        assert_eq!(fields["event_id"], Value::String("AB12".to_string()));
+        assert_eq!(fields["start"], Value::Number(1741338000.0));
+        assert_eq!(fields["title"], Value::Null);
+        assert_eq!(fields["join_url"], Value::Null);
+    }
+
+    #[test]
+    fn test_clients_get_latest_title() {
+        let path =
+            std::env::temp_dir().join(format!("eventually-watch-{}.sock", std::process::id()));
+        let socket = WatchSocket::bind(path.clone()).unwrap();
+        let at = parse_local_time("2025-03-07 10:00").unwrap();
+        socket.send(WatchEvent::TitleChanged("Review • 5m left"), at);
+
+        let client = UnixStream::connect(&path).unwrap();
+        let mut line = String::new();
+        BufReader::new(client).read_line(&mut line).unwrap();
+        assert_eq!(
+            parse_flat_object(&line).unwrap()["title"],
+            Value::String("Review • 5m left".to_string())
+        );
+
+        drop(socket);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_running_pid() {
+        let path =
+            std::env::temp_dir().join(format!("eventually-running-{}.sock", std::process::id()));
+        let socket = WatchSocket::bind(path.clone()).unwrap();
+        assert_eq!(
+            WatchSocket::running_pid(&path),
+            Some(std::process::id() as libc::pid_t)
+        );
+
+        drop(socket);
+        assert_eq!(WatchSocket::running_pid(&path), None);
+    }
+
+    #[test]
+    fn test_lock() {
+        let path =
+            std::env::temp_dir().join(format!("eventually-lock-{}.sock", std::process::id()));
+        let lock = WatchSocket::lock(&path).unwrap();
+        assert!(lock.is_some());
+        assert!(WatchSocket::lock(&path).unwrap().is_none());
+
+        drop(lock);
+        assert!(WatchSocket::lock(&path).unwrap().is_some());
+        fs::remove_file(path.with_extension("lock")).unwrap();
+    }
+}